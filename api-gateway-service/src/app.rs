@@ -4,8 +4,11 @@ use crate::cors::Cors;
 use crate::docs;
 use crate::logger::Logger;
 use crate::metrics::GatewayMetrics;
-use crate::proxy::{GatewayProxy, Proxy, ProxyOptions};
-use crate::request_context::RequestContextManager;
+use crate::proxy::{
+    GatewayProxy, MAX_REQUEST_BODY_BYTES, Proxy, ProxyOptions, RequestBodyFailure,
+    limit_request_body,
+};
+use crate::request_context::{RequestContext, RequestContextManager};
 use crate::routing::Routing;
 use crate::session::SessionManager;
 use anyhow::Result;
@@ -19,7 +22,6 @@ use axum::http::{HeaderMap, HeaderName, Method, Request, Response, StatusCode, h
 use axum::response::IntoResponse;
 use axum::routing::{delete, get, head, options, patch, post, put};
 use http::HeaderValue;
-use http_body_util::BodyExt;
 use serde_json::json;
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
@@ -469,25 +471,26 @@ async fn handle_proxy(
         None
     };
 
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared_length.is_some_and(|length| length > MAX_REQUEST_BODY_BYTES) {
+        return body_failure_response(
+            context,
+            target.service,
+            RequestBodyFailure::TooLarge,
+            cors_headers,
+        );
+    }
+
     let (parts, body) = request.into_parts();
-    let body_bytes = if requires_body(&parts.method) {
-        match body.collect().await {
-            Ok(collected) => Some(collected.to_bytes()),
-            Err(error) => {
-                state.logger.error(
-                    "proxy.body_read_failed",
-                    json!({"error": error.to_string()}),
-                );
-                context.complete(400, json!({"route": target.service, "reason": "body-read"}));
-                return json_response(
-                    StatusCode::BAD_REQUEST,
-                    json!({"error": "Invalid request body"}),
-                    cors_headers,
-                );
-            }
-        }
+    let (body, body_failure) = if requires_body(&parts.method) {
+        let (body, failure) = limit_request_body(body, MAX_REQUEST_BODY_BYTES);
+        (Some(body), Some(failure))
     } else {
-        None
+        (None, None)
     };
 
     let request_id = context.request_id.clone();
@@ -519,7 +522,7 @@ async fn handle_proxy(
         .proxy
         .forward(
             parts,
-            body_bytes,
+            body,
             ProxyOptions {
                 target: &target,
                 query: parsed.query.as_deref(),
@@ -534,6 +537,15 @@ async fn handle_proxy(
         )
         .await;
 
+    // A body that failed mid-stream surfaces as an upstream failure; it is the client's.
+    if let Some(failure) = body_failure.as_ref().and_then(|failure| failure.get()) {
+        state.logger.error(
+            "proxy.body_read_failed",
+            json!({"requestId": request_id, "reason": format!("{failure:?}")}),
+        );
+        return body_failure_response(context, target.service, *failure, cors_headers);
+    }
+
     let status = response.status().as_u16();
     let cache_status = response
         .headers()
@@ -550,6 +562,26 @@ async fn handle_proxy(
     response
 }
 
+fn body_failure_response(
+    context: RequestContext,
+    service: &str,
+    failure: RequestBodyFailure,
+    cors_headers: HeaderMap,
+) -> Response<Body> {
+    let (status, reason, message) = match failure {
+        RequestBodyFailure::TooLarge => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "body-too-large",
+            "Request body too large",
+        ),
+        RequestBodyFailure::Unreadable => {
+            (StatusCode::BAD_REQUEST, "body-read", "Invalid request body")
+        }
+    };
+    context.complete(status.as_u16(), json!({"route": service, "reason": reason}));
+    json_response(status, json!({"error": message}), cors_headers)
+}
+
 fn requires_body(method: &Method) -> bool {
     !(method == Method::GET || method == Method::HEAD)
 }
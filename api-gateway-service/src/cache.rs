@@ -1,11 +1,18 @@
 use crate::config::{CacheConfig, MemoryCacheConfig};
 use crate::logger::Logger;
 use anyhow::Result;
+use axum::body::Body;
+use http::{HeaderMap, Response, StatusCode, header};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+/// Conditional request headers. They are stripped before a cacheable request goes upstream so
+/// the cache always stores a full representation, and are answered by the gateway instead.
+pub const CONDITIONAL_REQUEST_HEADERS: [header::HeaderName; 2] =
+    [header::IF_NONE_MATCH, header::IF_MODIFIED_SINCE];
+
 #[derive(Clone)]
 pub struct CacheHandle {
     ttl: Duration,
@@ -97,3 +104,90 @@ impl MemoryCache {
         );
     }
 }
+
+/// Whether the client's `If-None-Match` / `If-Modified-Since` validators still match a
+/// response's `ETag` / `Last-Modified`. `If-None-Match` wins when both are sent.
+///
+/// This mirrors `Validators::matches` in radio-service-rs rather than sharing it: the services
+/// have no common crate, and the gateway answers from the headers of a cached response, not
+/// from validators it computed. Both sides use the weak comparison for `If-None-Match` and
+/// second precision for dates; the tests pin the same cases on each side.
+pub fn is_not_modified(request: &HeaderMap, response: &HeaderMap) -> bool {
+    if let Some(candidates) = request
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return response
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|etag| etag_list_matches(candidates, etag));
+    }
+
+    let parse = |headers: &HeaderMap, name: header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value).ok())
+    };
+    match (
+        parse(request, header::IF_MODIFIED_SINCE),
+        parse(response, header::LAST_MODIFIED),
+    ) {
+        (Some(since), Some(last_modified)) => last_modified <= since,
+        _ => false,
+    }
+}
+
+/// `If-None-Match` uses the weak comparison, so `W/"x"` matches `"x"` either way round.
+fn etag_list_matches(header_value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+    let etag = opaque(etag);
+    header_value
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque(candidate) == etag)
+}
+
+/// Turns a full response into a bodiless 304 that keeps its validators and caching headers.
+pub fn into_not_modified(response: Response<Body>) -> Response<Body> {
+    let (mut parts, _) = response.into_parts();
+    parts.status = StatusCode::NOT_MODIFIED;
+    for name in [
+        header::CONTENT_TYPE,
+        header::CONTENT_LENGTH,
+        header::CONTENT_ENCODING,
+    ] {
+        parts.headers.remove(name);
+    }
+    Response::from_parts(parts, Body::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_comparison_matches_the_radio_service() {
+        // Same cases as `conditional::tests` in radio-service-rs.
+        let cases = [
+            ("\"a\"", "\"a\"", true),
+            ("W/\"a\"", "\"a\"", true),
+            ("\"a\"", "W/\"a\"", true),
+            ("\"stale\", W/\"a\"", "\"a\"", true),
+            ("*", "\"a\"", true),
+            ("\"stale\"", "\"a\"", false),
+            ("W/\"stale\"", "W/\"a\"", false),
+        ];
+        for (if_none_match, etag, expected) in cases {
+            let mut request = HeaderMap::new();
+            request.insert(header::IF_NONE_MATCH, if_none_match.parse().unwrap());
+            let mut response = HeaderMap::new();
+            response.insert(header::ETAG, etag.parse().unwrap());
+            assert_eq!(
+                is_not_modified(&request, &response),
+                expected,
+                "{if_none_match} vs {etag}"
+            );
+        }
+    }
+}
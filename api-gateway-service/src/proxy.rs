@@ -1,4 +1,4 @@
-use crate::cache::CacheHandle;
+use crate::cache::{CONDITIONAL_REQUEST_HEADERS, CacheHandle, into_not_modified, is_not_modified};
 use crate::headers::{
     append_forwarded_for, find_header_key, resolve_client_ip, sanitize_headers_for_cache,
     sanitize_request_headers, sanitize_response_headers,
@@ -9,22 +9,53 @@ use crate::session::SessionSnapshot;
 use async_trait::async_trait;
 use axum::body::Body;
 use base64::{Engine as _, engine::general_purpose::STANDARD};
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use futures_util::{StreamExt, TryStreamExt, stream};
 use http::{HeaderMap, HeaderName, HeaderValue, Response, StatusCode, header};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::str;
+use std::sync::{Arc, OnceLock};
 
 const MAX_CACHE_BODY_BYTES: usize = 512 * 1024;
+/// Largest request body forwarded upstream; clients only send JSON and playlist uploads.
+pub const MAX_REQUEST_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Why a streamed request body was cut off. The upstream request then fails like any other
+/// send error, so the caller checks this to answer with the client's error instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestBodyFailure {
+    TooLarge,
+    Unreadable,
+}
+
+/// Caps a request body at `limit` bytes as it streams upstream and records why it stopped.
+pub fn limit_request_body(body: Body, limit: usize) -> (Body, Arc<OnceLock<RequestBodyFailure>>) {
+    let failure = Arc::new(OnceLock::new());
+    let recorded = failure.clone();
+    let mut forwarded = 0usize;
+    let body = Body::from_stream(body.into_data_stream().map(move |chunk| {
+        let chunk = chunk.inspect_err(|_| {
+            let _ = recorded.set(RequestBodyFailure::Unreadable);
+        })?;
+        forwarded += chunk.len();
+        if forwarded > limit {
+            let _ = recorded.set(RequestBodyFailure::TooLarge);
+            return Err(axum::Error::new("request body exceeds the gateway limit"));
+        }
+        Ok(chunk)
+    }));
+    (body, failure)
+}
 
 #[async_trait]
 pub trait GatewayProxy: Send + Sync {
     async fn forward(
         &self,
         parts: http::request::Parts,
-        body_bytes: Option<Bytes>,
+        body: Option<Body>,
         options: ProxyOptions<'_>,
     ) -> Response<Body>;
 }
@@ -64,7 +95,7 @@ impl GatewayProxy for Proxy {
     async fn forward(
         &self,
         parts: http::request::Parts,
-        body_bytes: Option<Bytes>,
+        body: Option<Body>,
         options: ProxyOptions<'_>,
     ) -> Response<Body> {
         if options.cacheable
@@ -72,13 +103,22 @@ impl GatewayProxy for Proxy {
             && let Some(cached) = self.cache.get(cache_key).await
             && let Ok(entry) = serde_json::from_str::<CacheEntry>(&cached)
         {
-            return build_cached_response(entry, &options.cors_headers);
+            let response = build_cached_response(entry, &options.cors_headers);
+            if is_not_modified(&parts.headers, response.headers()) {
+                return into_not_modified(response);
+            }
+            return response;
         }
 
         let target_url = build_target_url(options.target, options.query);
         let mut outbound_headers = sanitize_request_headers(&parts.headers);
         // Never allow clients to inject internal auth headers.
         outbound_headers.remove(HeaderName::from_static("x-fmd-token"));
+        if options.cacheable {
+            for name in CONDITIONAL_REQUEST_HEADERS {
+                outbound_headers.remove(name);
+            }
+        }
         append_forwarded_for(&mut outbound_headers, options.remote_addr.as_ref());
         let client_ip = resolve_client_ip(
             &parts.headers,
@@ -126,8 +166,9 @@ impl GatewayProxy for Proxy {
             .request(parts.method.clone(), &target_url)
             .headers(convert_headers(&outbound_headers));
 
-        if let Some(body) = body_bytes {
-            request_builder = request_builder.body(body);
+        if let Some(body) = body {
+            request_builder =
+                request_builder.body(reqwest::Body::wrap_stream(body.into_data_stream()));
         }
 
         let response = match request_builder.send().await {
@@ -151,25 +192,6 @@ impl GatewayProxy for Proxy {
 
         let status = response.status();
         let headers = sanitize_response_headers(response.headers());
-        let bytes = match response.bytes().await {
-            Ok(body) => body,
-            Err(error) => {
-                self.logger.error(
-                    "proxy.response_read_failed",
-                    serde_json::json!({
-                        "requestId": options.request_id,
-                        "target": target_url,
-                        "error": error.to_string(),
-                    }),
-                );
-                return build_error_response(
-                    StatusCode::BAD_GATEWAY,
-                    "Upstream response invalid",
-                    &options.cors_headers,
-                );
-            }
-        };
-
         let mut response_headers = HeaderMap::new();
         for (key, value) in headers.iter() {
             response_headers.insert(key.clone(), value.clone());
@@ -209,39 +231,135 @@ impl GatewayProxy for Proxy {
             );
         }
 
-        if options.cacheable
-            && let Some(cache_key) = &options.cache_key
-            && let Some(content_type) = response_headers
-                .get(header::CONTENT_TYPE)
-                .and_then(|value| value.to_str().ok())
-            && content_type.contains("application/json")
-            && status.is_success()
-            && bytes.len() <= MAX_CACHE_BODY_BYTES
-        {
-            let cache_headers = sanitize_headers_for_cache(&headers);
-            let entry = CacheEntry {
-                status: status.as_u16(),
-                headers: header_map_to_string(cache_headers),
-                body_b64: STANDARD.encode(&bytes),
-                body_len: bytes.len(),
-            };
-            if let Ok(serialized) = serde_json::to_string(&entry) {
-                self.cache.set(cache_key, &serialized, None).await;
+        let cache_key = options.cache_key.as_deref().filter(|_| {
+            options.cacheable
+                && status.is_success()
+                && response_headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|content_type| content_type.contains("application/json"))
+        });
+        response_headers.remove(header::CONTENT_LENGTH);
+
+        // Anything that cannot end up in the cache (audio streams, event streams, large
+        // payloads) is piped straight through instead of being held in memory.
+        let Some(cache_key) = cache_key else {
+            let body = self.stream_body(response, options.request_id, &target_url);
+            return build_response(status, response_headers, body);
+        };
+
+        let bytes = match read_bounded_body(response, MAX_CACHE_BODY_BYTES).await {
+            Ok(BoundedBody::Complete(bytes)) => bytes,
+            Ok(BoundedBody::Overflow { prefix, response }) => {
+                let rest = self.stream_body(response, options.request_id, &target_url);
+                let body = Body::from_stream(
+                    stream::once(async move { Ok::<_, axum::Error>(prefix) })
+                        .chain(rest.into_data_stream()),
+                );
+                return build_response(status, response_headers, body);
             }
+            Err(error) => {
+                self.logger.error(
+                    "proxy.response_read_failed",
+                    serde_json::json!({
+                        "requestId": options.request_id,
+                        "target": target_url,
+                        "error": error.to_string(),
+                    }),
+                );
+                return build_error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "Upstream response invalid",
+                    &options.cors_headers,
+                );
+            }
+        };
+
+        let cache_headers = sanitize_headers_for_cache(&headers);
+        let entry = CacheEntry {
+            status: status.as_u16(),
+            headers: header_map_to_string(cache_headers),
+            body_b64: STANDARD.encode(&bytes),
+            body_len: bytes.len(),
+        };
+        if let Ok(serialized) = serde_json::to_string(&entry) {
+            self.cache.set(cache_key, &serialized, None).await;
         }
 
-        response_headers.remove(header::CONTENT_LENGTH);
-        let mut builder = Response::builder().status(status);
-        *builder.headers_mut().unwrap() = response_headers;
-        builder.body(Body::from(bytes)).unwrap_or_else(|_| {
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(Body::from("{}"))
-                .expect("failed to build proxy fallback response")
-        })
+        let response = build_response(status, response_headers, Body::from(bytes));
+        if is_not_modified(&parts.headers, response.headers()) {
+            return into_not_modified(response);
+        }
+        response
     }
 }
 
+impl Proxy {
+    fn stream_body(&self, response: reqwest::Response, request_id: &str, target: &str) -> Body {
+        let logger = self.logger.clone();
+        let request_id = request_id.to_string();
+        let target = target.to_string();
+        Body::from_stream(response.bytes_stream().inspect_err(move |error| {
+            logger.warn(
+                "proxy.stream_interrupted",
+                serde_json::json!({
+                    "requestId": request_id,
+                    "target": target,
+                    "error": error.to_string(),
+                }),
+            );
+        }))
+    }
+}
+
+enum BoundedBody {
+    Complete(Bytes),
+    Overflow {
+        prefix: Bytes,
+        response: reqwest::Response,
+    },
+}
+
+/// Reads the upstream body into memory as long as it stays within `limit` bytes.
+/// Bodies that grow past the limit hand back what was read so far together with
+/// the still-open response so the caller can keep streaming it.
+async fn read_bounded_body(
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<BoundedBody, reqwest::Error> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        return Ok(BoundedBody::Overflow {
+            prefix: Bytes::new(),
+            response,
+        });
+    }
+    let mut buffer = BytesMut::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        if buffer.len() > limit {
+            return Ok(BoundedBody::Overflow {
+                prefix: buffer.freeze(),
+                response,
+            });
+        }
+    }
+    Ok(BoundedBody::Complete(buffer.freeze()))
+}
+
+fn build_response(status: StatusCode, headers: HeaderMap, body: Body) -> Response<Body> {
+    let mut builder = Response::builder().status(status);
+    *builder.headers_mut().unwrap() = headers;
+    builder.body(body).unwrap_or_else(|_| {
+        Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from("{}"))
+            .expect("failed to build proxy fallback response")
+    })
+}
+
 fn build_target_url(target: &Target, query: Option<&str>) -> String {
     let mut url = format!("{}{}", target.base_url, target.path);
     if let Some(q) = query
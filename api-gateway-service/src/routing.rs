@@ -161,7 +161,9 @@ impl Routing {
         if method != http::Method::GET {
             return false;
         }
-        target.service == "radio" && target.path.starts_with("/stations")
+        target.service == "radio"
+            && target.path.starts_with("/stations")
+            && !is_live_stream_path(&target.path)
     }
 
     pub fn build_cache_key(&self, target: &Target, query: Option<&str>) -> String {
@@ -192,6 +194,11 @@ impl Routing {
     }
 }
 
+/// Audio streams and event feeds never end, so there is nothing to cache for them.
+fn is_live_stream_path(path: &str) -> bool {
+    path.ends_with("/stream") || path.contains("/stream/") || path.ends_with("/events")
+}
+
 fn decode_until_stable(value: &str) -> String {
     let mut current = value.to_string();
     for _ in 0..3 {
@@ -0,0 +1,322 @@
+use api_gateway_service::cache::CacheHandle;
+use api_gateway_service::config::{CacheConfig, MemoryCacheConfig};
+use api_gateway_service::logger::Logger;
+use api_gateway_service::proxy::{
+    GatewayProxy, Proxy, ProxyOptions, RequestBodyFailure, limit_request_body,
+};
+use api_gateway_service::routing::Target;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{HeaderMap, Method, Request, StatusCode, header};
+use axum::response::Response;
+use axum::routing::{get, post};
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, mpsc};
+
+type ChunkReceiver = Arc<Mutex<Option<mpsc::Receiver<Bytes>>>>;
+
+#[tokio::test]
+async fn streams_non_cacheable_responses_before_upstream_finishes() {
+    let (sender, receiver) = mpsc::channel::<Bytes>(4);
+    let upstream = spawn_upstream(Arc::new(Mutex::new(Some(receiver)))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/stations/abc/stream");
+
+    let response = proxy
+        .forward(
+            request_parts(Method::GET),
+            None,
+            proxy_options(&target, Some("radio:/stations/abc/stream")),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "audio/mpeg");
+
+    let mut body = response.into_body();
+    sender.send(Bytes::from_static(b"first")).await.unwrap();
+    let first = tokio::time::timeout(Duration::from_secs(5), body.frame())
+        .await
+        .expect("first chunk should arrive while upstream is still open")
+        .unwrap()
+        .unwrap()
+        .into_data()
+        .unwrap();
+    assert_eq!(first, Bytes::from_static(b"first"));
+
+    sender.send(Bytes::from_static(b"second")).await.unwrap();
+    drop(sender);
+    let rest = body.collect().await.unwrap().to_bytes();
+    assert_eq!(rest, Bytes::from_static(b"second"));
+}
+
+#[tokio::test]
+async fn buffers_and_caches_small_json_responses() {
+    let upstream = spawn_upstream(Arc::new(Mutex::new(None))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/stations");
+
+    for expected in ["MISS", "HIT"] {
+        let response = proxy
+            .forward(
+                request_parts(Method::GET),
+                None,
+                proxy_options(&target, Some("radio:/stations")),
+            )
+            .await;
+        assert_eq!(response.headers()["x-cache"], expected);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from_static(br#"{"items":[]}"#));
+    }
+}
+
+#[tokio::test]
+async fn answers_conditional_requests_from_cache() {
+    let upstream = spawn_upstream(Arc::new(Mutex::new(None))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/stations/etag");
+
+    // The client's validator is not forwarded, so the first response is cached in full.
+    let mut parts = request_parts(Method::GET);
+    parts
+        .headers
+        .insert(header::IF_NONE_MATCH, "\"v1\"".parse().unwrap());
+    let response = proxy
+        .forward(
+            parts,
+            None,
+            proxy_options(&target, Some("radio:/stations/etag")),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()["x-cache"], "MISS");
+    assert_eq!(response.headers()[header::ETAG], "\"v1\"");
+
+    let mut parts = request_parts(Method::GET);
+    parts
+        .headers
+        .insert(header::IF_NONE_MATCH, "W/\"v1\"".parse().unwrap());
+    let response = proxy
+        .forward(
+            parts,
+            None,
+            proxy_options(&target, Some("radio:/stations/etag")),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()["x-cache"], "HIT");
+    assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(body.is_empty());
+
+    let mut parts = request_parts(Method::GET);
+    parts.headers.insert(
+        header::IF_MODIFIED_SINCE,
+        "Tue, 30 Apr 2024 00:00:00 GMT".parse().unwrap(),
+    );
+    let response = proxy
+        .forward(
+            parts,
+            None,
+            proxy_options(&target, Some("radio:/stations/etag")),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-cache"], "HIT");
+}
+
+#[tokio::test]
+async fn oversized_json_is_streamed_without_caching() {
+    let upstream = spawn_upstream(Arc::new(Mutex::new(None))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/stations/large");
+
+    for _ in 0..2 {
+        let response = proxy
+            .forward(
+                request_parts(Method::GET),
+                None,
+                proxy_options(&target, Some("radio:/stations/large")),
+            )
+            .await;
+        assert_eq!(response.headers()["x-cache"], "MISS");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body.len(), large_body().len());
+    }
+}
+
+#[tokio::test]
+async fn streams_request_bodies_upstream() {
+    let upstream = spawn_upstream(Arc::new(Mutex::new(None))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/echo");
+
+    let chunks = futures_util::stream::iter([
+        Ok::<_, Infallible>(Bytes::from_static(b"hello ")),
+        Ok(Bytes::from_static(b"world")),
+    ]);
+    let response = proxy
+        .forward(
+            request_parts(Method::POST),
+            Some(Body::from_stream(chunks)),
+            proxy_options(&target, None),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(body, Bytes::from_static(b"hello world"));
+}
+
+#[tokio::test]
+async fn records_why_a_request_body_was_cut_off() {
+    let upstream = spawn_upstream(Arc::new(Mutex::new(None))).await;
+    let proxy = build_proxy().await;
+    let target = radio_target(&upstream, "/echo");
+
+    let chunks = futures_util::stream::iter([
+        Ok::<_, std::io::Error>(Bytes::from_static(b"hello ")),
+        Ok(Bytes::from_static(b"world")),
+    ]);
+    let (body, failure) = limit_request_body(Body::from_stream(chunks), 8);
+    let response = proxy
+        .forward(
+            request_parts(Method::POST),
+            Some(body),
+            proxy_options(&target, None),
+        )
+        .await;
+    assert_ne!(response.status(), StatusCode::OK);
+    assert_eq!(failure.get(), Some(&RequestBodyFailure::TooLarge));
+
+    let chunks = futures_util::stream::iter([
+        Ok(Bytes::from_static(b"hello ")),
+        Err(std::io::Error::other("client went away")),
+    ]);
+    let (body, failure) = limit_request_body(Body::from_stream(chunks), 1024);
+    proxy
+        .forward(
+            request_parts(Method::POST),
+            Some(body),
+            proxy_options(&target, None),
+        )
+        .await;
+    assert_eq!(failure.get(), Some(&RequestBodyFailure::Unreadable));
+}
+
+async fn build_proxy() -> Proxy {
+    let logger = Logger::new("gateway-test");
+    let cache = CacheHandle::new(
+        CacheConfig {
+            ttl: Duration::from_secs(60),
+            memory: MemoryCacheConfig {
+                enabled: true,
+                max_entries: 50,
+            },
+        },
+        logger.clone(),
+    )
+    .await
+    .expect("cache handle");
+    Proxy::new(reqwest::Client::new(), cache, logger, false)
+}
+
+fn radio_target(upstream: &SocketAddr, path: &str) -> Target {
+    Target {
+        base_url: format!("http://{upstream}"),
+        path: path.to_string(),
+        service: "radio",
+    }
+}
+
+fn request_parts(method: Method) -> http::request::Parts {
+    Request::builder()
+        .method(method)
+        .uri("/radio/test")
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0
+}
+
+fn proxy_options<'a>(target: &'a Target, cache_key: Option<&str>) -> ProxyOptions<'a> {
+    ProxyOptions {
+        target,
+        query: None,
+        session: None,
+        cors_headers: HeaderMap::new(),
+        cache_key: cache_key.map(|key| key.to_string()),
+        cacheable: cache_key.is_some(),
+        remote_addr: None,
+        request_id: "test-request",
+        extra_request_headers: HeaderMap::new(),
+    }
+}
+
+fn large_body() -> String {
+    format!(r#"{{"padding":"{}"}}"#, "x".repeat(600 * 1024))
+}
+
+async fn spawn_upstream(chunks: ChunkReceiver) -> SocketAddr {
+    let router = Router::new()
+        .route(
+            "/stations",
+            get(|| async {
+                Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(r#"{"items":[]}"#))
+                    .unwrap()
+            }),
+        )
+        .route(
+            "/stations/etag",
+            get(|headers: HeaderMap| async move {
+                assert!(headers.get(header::IF_NONE_MATCH).is_none());
+                Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .header(header::ETAG, "\"v1\"")
+                    .header(header::LAST_MODIFIED, "Wed, 01 May 2024 12:00:00 GMT")
+                    .body(Body::from(r#"{"items":[]}"#))
+                    .unwrap()
+            }),
+        )
+        .route(
+            "/stations/large",
+            get(|| async {
+                Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(large_body()))
+                    .unwrap()
+            }),
+        )
+        .route(
+            "/stations/abc/stream",
+            get(move || {
+                let chunks = chunks.clone();
+                async move {
+                    let receiver = chunks.lock().await.take().expect("single stream listener");
+                    let stream = futures_util::stream::unfold(receiver, |mut receiver| async {
+                        receiver
+                            .recv()
+                            .await
+                            .map(|chunk| (Ok::<_, Infallible>(chunk), receiver))
+                    });
+                    Response::builder()
+                        .header(header::CONTENT_TYPE, "audio/mpeg")
+                        .body(Body::from_stream(stream))
+                        .unwrap()
+                }
+            }),
+        )
+        .route("/echo", post(|body: Bytes| async move { body }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    addr
+}
@@ -168,7 +168,7 @@ impl GatewayProxy for MockProxy {
     async fn forward(
         &self,
         _parts: http::request::Parts,
-        _body_bytes: Option<Bytes>,
+        _body: Option<Body>,
         options: ProxyOptions<'_>,
     ) -> Response<Body> {
         let body = match options.target.service {
@@ -57,6 +57,27 @@ pub struct RadioStation {
     pub click_count: i64,
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct NowPlayingInfo {
+    title: Option<String>,
+    artist: Option<String>,
+    #[serde(rename = "streamTitle")]
+    stream_title: Option<String>,
+}
+
+impl NowPlayingInfo {
+    fn display_title(&self) -> Option<String> {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => Some(format!("{artist} - {title}")),
+            _ => self
+                .stream_title
+                .clone()
+                .or_else(|| self.title.clone())
+                .filter(|value| !value.trim().is_empty()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct StationsResponse {
     pub meta: StationsMeta,
@@ -193,6 +214,19 @@ struct TimeoutHandle {
     _closure: Rc<wasm_bindgen::closure::Closure<dyn FnMut()>>,
 }
 
+#[cfg(target_arch = "wasm32")]
+struct NowPlayingFeed {
+    source: web_sys::EventSource,
+    _listener: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for NowPlayingFeed {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
 #[component]
 pub fn RadioPage() -> Element {
     let config = use_context::<RuntimeConfig>();
@@ -235,6 +269,12 @@ pub fn RadioPage() -> Element {
     let mut has_more = use_signal(|| false);
     let mut last_filters = use_signal(|| None::<Filters>);
     let mut last_stream_id = use_signal(|| None::<String>);
+    let mut now_playing = use_signal(|| None::<NowPlayingInfo>);
+    let mut last_now_playing_id = use_signal(|| None::<String>);
+    #[cfg(target_arch = "wasm32")]
+    let mut now_playing_feed = use_signal(|| None::<NowPlayingFeed>);
+    #[cfg(not(target_arch = "wasm32"))]
+    let _now_playing_feed = ();
     #[cfg(target_arch = "wasm32")]
     let mut last_hls_key = use_signal(|| None::<String>);
     #[cfg(target_arch = "wasm32")]
@@ -456,6 +496,47 @@ pub fn RadioPage() -> Element {
         }
     });
 
+    use_effect({
+        let base_url = base_url.clone();
+        move || {
+            let selection_id = selected()
+                .map(|station| station.id)
+                .filter(|id| match_secret(id).is_none());
+            if last_now_playing_id() == selection_id {
+                return;
+            }
+            last_now_playing_id.set(selection_id.clone());
+            now_playing.set(None);
+            #[cfg(target_arch = "wasm32")]
+            {
+                now_playing_feed.set(None);
+                if let Some(station_id) = selection_id {
+                    log_debug("radio: open now playing feed");
+                    let base_url = base_url.clone();
+                    spawn(async move {
+                        let Ok((token, proof)) = ensure_gateway_session().await else {
+                            return;
+                        };
+                        // The listener may have tuned elsewhere while the session resolved.
+                        if last_now_playing_id().as_deref() != Some(station_id.as_str()) {
+                            return;
+                        }
+                        let url =
+                            build_now_playing_events_url(&base_url, &station_id, &token, &proof);
+                        match open_now_playing_feed(&url, now_playing) {
+                            Ok(feed) => now_playing_feed.set(Some(feed)),
+                            Err(err) => {
+                                log_debug(&format!("radio: now playing feed failed: {err}"))
+                            }
+                        }
+                    });
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            let _ = (&base_url, selection_id);
+        }
+    });
+
     #[cfg(target_arch = "wasm32")]
     {
         let midnight_timer = midnight_timer;
@@ -615,6 +696,9 @@ pub fn RadioPage() -> Element {
     });
 
     let active_station_id = active_station.as_ref().map(|station| station.id.clone());
+    let now_playing_label = now_playing()
+        .and_then(|info| info.display_title())
+        .unwrap_or_else(|| "Unknown".to_string());
     let share_link = active_station
         .as_ref()
         .map(build_share_url)
@@ -717,6 +801,10 @@ pub fn RadioPage() -> Element {
                                             dt { "Status" }
                                             dd { if station.is_online { "Online" } else { "Offline" } }
                                         }
+                                        div {
+                                            dt { "Now Playing" }
+                                            dd { "{now_playing_label}" }
+                                        }
                                     }
                                     div { class: "radio-tags",
                                         span { "Tags: " }
@@ -1345,6 +1433,51 @@ async fn resolve_stream_url(base_url: &str, station: &RadioStation) -> String {
     stream_url
 }
 
+#[cfg(target_arch = "wasm32")]
+fn build_now_playing_events_url(
+    base_url: &str,
+    station_id: &str,
+    csrf_token: &str,
+    csrf_proof: &str,
+) -> String {
+    // EventSource cannot send custom headers, so the CSRF pair travels in the query.
+    format!(
+        "{}/stations/{}/now-playing/events?csrfToken={}&csrfProof={}",
+        base_url.trim_end_matches('/'),
+        urlencoding::encode(station_id),
+        urlencoding::encode(csrf_token),
+        urlencoding::encode(csrf_proof)
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_now_playing_feed(
+    url: &str,
+    mut target: Signal<Option<NowPlayingInfo>>,
+) -> Result<NowPlayingFeed, String> {
+    use wasm_bindgen::closure::Closure;
+
+    let init = web_sys::EventSourceInit::new();
+    init.set_with_credentials(true);
+    let source = web_sys::EventSource::new_with_event_source_init_dict(url, &init)
+        .map_err(|err| format!("{err:?}"))?;
+    let listener =
+        Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            if let Some(data) = event.data().as_string() {
+                if let Ok(info) = serde_json::from_str::<NowPlayingInfo>(&data) {
+                    target.set(Some(info));
+                }
+            }
+        });
+    source
+        .add_event_listener_with_callback("now-playing", listener.as_ref().unchecked_ref())
+        .map_err(|err| format!("{err:?}"))?;
+    Ok(NowPlayingFeed {
+        source,
+        _listener: listener,
+    })
+}
+
 #[cfg(target_arch = "wasm32")]
 fn is_hls_station(station: &RadioStation, resolved_url: &str) -> bool {
     if station.hls {
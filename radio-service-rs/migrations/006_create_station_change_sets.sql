@@ -0,0 +1,17 @@
+ALTER TABLE stations
+  ADD COLUMN IF NOT EXISTS signature TEXT;
+
+CREATE TABLE IF NOT EXISTS station_change_sets (
+  id BIGSERIAL PRIMARY KEY,
+  fingerprint TEXT NOT NULL,
+  previous_fingerprint TEXT,
+  added TEXT[] NOT NULL DEFAULT '{}',
+  changed TEXT[] NOT NULL DEFAULT '{}',
+  removed TEXT[] NOT NULL DEFAULT '{}',
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS station_change_sets_fingerprint_idx
+  ON station_change_sets (fingerprint);
+CREATE INDEX IF NOT EXISTS station_change_sets_created_at_idx
+  ON station_change_sets (created_at);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS station_payload_history (
+  id BIGSERIAL PRIMARY KEY,
+  fingerprint TEXT NOT NULL UNIQUE,
+  schema_version TEXT,
+  updated_at TIMESTAMPTZ NOT NULL,
+  source TEXT,
+  requests JSONB NOT NULL DEFAULT '[]'::jsonb,
+  total BIGINT NOT NULL,
+  stations JSONB NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
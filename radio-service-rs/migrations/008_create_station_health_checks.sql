@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS station_health_checks (
+  id BIGSERIAL PRIMARY KEY,
+  station_id TEXT NOT NULL,
+  stream_url TEXT NOT NULL,
+  ok BOOLEAN NOT NULL,
+  status INTEGER,
+  reason TEXT,
+  latency_ms INTEGER,
+  content_type TEXT,
+  checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS station_health_checks_station_checked_idx
+  ON station_health_checks (station_id, checked_at DESC);
+CREATE INDEX IF NOT EXISTS station_health_checks_checked_at_idx
+  ON station_health_checks (checked_at);
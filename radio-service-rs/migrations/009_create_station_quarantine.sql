@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS station_quarantine (
+  id TEXT PRIMARY KEY,
+  stream_url TEXT NOT NULL,
+  station JSONB NOT NULL,
+  reason TEXT NOT NULL,
+  dropped_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+ALTER TABLE station_state
+  ADD COLUMN IF NOT EXISTS refreshed_at TIMESTAMPTZ;
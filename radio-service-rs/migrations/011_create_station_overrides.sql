@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS station_overrides (
+  station_id TEXT PRIMARY KEY,
+  hidden BOOLEAN NOT NULL DEFAULT FALSE,
+  pinned BOOLEAN NOT NULL DEFAULT FALSE,
+  patch JSONB NOT NULL DEFAULT '{}'::jsonb,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS radio_favorite_transfers (
+  code TEXT PRIMARY KEY,
+  source_key TEXT NOT NULL,
+  expires_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS radio_favorite_transfers_source_key_idx
+  ON radio_favorite_transfers (source_key);
+
+CREATE INDEX IF NOT EXISTS radio_favorite_transfers_expires_at_idx
+  ON radio_favorite_transfers (expires_at);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS radio_listening_history (
+  id BIGSERIAL PRIMARY KEY,
+  key TEXT NOT NULL,
+  station_id TEXT NOT NULL,
+  station JSONB,
+  played_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS radio_listening_history_key_played_at_idx
+  ON radio_listening_history (key, played_at DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS radio_listening_history_expires_at_idx
+  ON radio_listening_history (expires_at);
@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, VecDeque},
+    hash::{BuildHasher, RandomState},
     sync::Arc,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
@@ -19,9 +20,19 @@ use crate::{
     config::Config,
     database::create_postgres_pool,
     favorites::FavoritesStore,
+    history::HistoryStore,
+    now_playing::NowPlayingService,
+    now_playing_hub::NowPlayingHub,
     radio_browser::RadioBrowserClient,
-    refresh,
-    stations::{sanitize_persisted_payload, ProcessedStations, StationStorage, StationsPayload},
+    refresh::{self, SchedulerOutcome, SchedulerStatus},
+    revalidation,
+    sources::{CuratedSource, StationSource, StationSources},
+    stations::{
+        apply_station_overrides, sanitize_persisted_payload, ProcessedStations, StationStorage,
+        StationsPayload,
+    },
+    stream_health::{self, HealthScores},
+    stream_relay::StreamRelayHub,
     stream_validation::StreamValidator,
 };
 
@@ -32,14 +43,23 @@ pub struct AppState {
     pub postgres: PgPool,
     pub stations: StationStorage,
     pub favorites: FavoritesStore,
+    pub history: HistoryStore,
     pub radio_browser: RadioBrowserClient,
+    /// Station providers queried by a refresh, in merge priority order.
+    pub sources: StationSources,
     pub http_client: Client,
     processed_cache: Arc<RwLock<Option<ProcessedCache>>>,
+    health_scores: Arc<RwLock<Option<Arc<HealthScores>>>>,
     pub stream_validator: StreamValidator,
+    pub now_playing: NowPlayingService,
+    pub now_playing_hub: NowPlayingHub,
+    pub stream_relay: StreamRelayHub,
     memory_cache: Arc<RwLock<Option<MemoryEntry>>>,
     cache_state_updated_at: Arc<RwLock<Option<DateTime<Utc>>>>,
     refresh_mutex: Arc<Mutex<()>>,
+    scheduler_status: Arc<RwLock<SchedulerStatus>>,
     rate_limiter: Arc<RateLimiter>,
+    transfer_rate_limiter: Arc<RateLimiter>,
     status_monitor: Arc<EventLoopMonitor>,
     system: Arc<Mutex<System>>,
     started_at: Instant,
@@ -213,6 +233,12 @@ pub enum SchemaVersionValue {
     String(String),
 }
 
+pub enum RollbackOutcome {
+    Restored(StationsPayload),
+    NotFound,
+    Busy,
+}
+
 #[derive(Clone)]
 pub struct LoadStationsResult {
     pub payload: StationsPayload,
@@ -229,8 +255,9 @@ impl AppState {
             .await
             .context("failed to validate postgres connectivity")?;
 
-        let stations = StationStorage::new(postgres.clone());
-        let favorites = FavoritesStore::new(postgres.clone());
+        let stations = StationStorage::new(postgres.clone(), config.payload_history_limit);
+        let favorites = FavoritesStore::new(postgres.clone(), config.favorites.clone());
+        let history = HistoryStore::new(postgres.clone());
         let http_client = Client::builder()
             .build()
             .context("failed to build http client")?;
@@ -238,14 +265,37 @@ impl AppState {
             config.radio_browser.clone(),
             config.allow_insecure_transports,
         )?;
+        let mut sources: Vec<Arc<dyn StationSource>> = Vec::new();
+        if let Some(dir) = &config.curated_stations_dir {
+            sources.push(Arc::new(CuratedSource::new(
+                dir,
+                config.radio_browser.enforce_https_streams,
+                config.allow_insecure_transports,
+            )));
+        }
+        sources.push(Arc::new(radio_browser.clone()));
+        let sources = Arc::new(sources);
         let stream_validator =
             StreamValidator::new(config.stream_validation.clone(), http_client.clone());
+        let now_playing = NowPlayingService::new(config.now_playing.clone(), http_client.clone());
+        let now_playing_hub = NowPlayingHub::new(config.now_playing.clone(), now_playing.clone());
+        let stream_relay = StreamRelayHub::new(
+            config.stream_relay.clone(),
+            http_client.clone(),
+            config.radio_browser.user_agent.clone(),
+            Duration::from_millis(config.stream_proxy.timeout_ms),
+        );
         let processed_cache = Arc::new(RwLock::new(None));
+        let health_scores = Arc::new(RwLock::new(None));
         let memory_cache = Arc::new(RwLock::new(None));
         let cache_state_updated_at = Arc::new(RwLock::new(None));
 
         let refresh_mutex = Arc::new(Mutex::new(()));
+        let scheduler_status = Arc::new(RwLock::new(SchedulerStatus::default()));
         let rate_limiter = Arc::new(RateLimiter::new(100, Duration::from_secs(60)));
+        // Transfer codes are short enough to guess, so issuing and redeeming them is held to
+        // a much tighter budget than the rest of the API.
+        let transfer_rate_limiter = Arc::new(RateLimiter::new(10, Duration::from_secs(600)));
         let status_monitor = Arc::new(EventLoopMonitor::new());
         EventLoopMonitor::spawn(status_monitor.clone());
         let system = Arc::new(Mutex::new(System::new_all()));
@@ -256,14 +306,22 @@ impl AppState {
             postgres,
             stations,
             favorites,
+            history,
             radio_browser,
+            sources,
             http_client,
             processed_cache,
+            health_scores,
             stream_validator,
+            now_playing,
+            now_playing_hub,
+            stream_relay,
             memory_cache,
             cache_state_updated_at,
             refresh_mutex,
+            scheduler_status,
             rate_limiter,
+            transfer_rate_limiter,
             status_monitor,
             system,
             started_at,
@@ -335,6 +393,30 @@ impl AppState {
         processed
     }
 
+    /// Stream health scores for the current catalogue. Loaded once per station state change
+    /// (refresh, rollback, or another replica's refresh); a failed load serves no scores
+    /// rather than failing the listing.
+    pub async fn health_scores(&self) -> Arc<HealthScores> {
+        if let Some(existing) = self.health_scores.read().await.as_ref() {
+            return existing.clone();
+        }
+        let updated_at = *self.cache_state_updated_at.read().await;
+        match stream_health::load_scores(&self.postgres, updated_at).await {
+            Ok(scores) => {
+                let scores = Arc::new(scores);
+                *self.health_scores.write().await = Some(scores.clone());
+                scores
+            }
+            Err(error) => {
+                logger().warn(
+                    "stations.health_load_failed",
+                    json!({ "error": error.to_string() }),
+                );
+                Arc::new(HealthScores::default())
+            }
+        }
+    }
+
     pub async fn load_stations(&self, force_refresh: bool) -> anyhow::Result<LoadStationsResult> {
         self.ensure_cache_state_sync().await?;
 
@@ -346,6 +428,7 @@ impl AppState {
             if let Some(payload) = self.stations.load_latest_payload().await? {
                 if let Some(sanitized) = self.sanitize_payload(payload) {
                     let mut payload = sanitized.payload;
+                    self.apply_overrides(&mut payload).await?;
                     payload
                         .ensure_fingerprint()
                         .context("failed to compute database fingerprint")?;
@@ -393,6 +476,46 @@ impl AppState {
         self.refresh_and_cache().await
     }
 
+    /// Makes a retained payload snapshot the live catalogue again. Holds the refresh lock so
+    /// a concurrent refresh cannot interleave, and drops this replica's caches; other replicas
+    /// notice the bumped `station_state` marker on their next load.
+    pub async fn rollback_stations(&self, history_id: i64) -> anyhow::Result<RollbackOutcome> {
+        let process_guard = self.refresh_mutex.lock().await;
+        let Some(_lock) = self.try_acquire_refresh_lock().await? else {
+            return Ok(RollbackOutcome::Busy);
+        };
+        drop(process_guard);
+
+        let Some(mut payload) = self.stations.load_history_payload(history_id).await? else {
+            return Ok(RollbackOutcome::NotFound);
+        };
+        payload
+            .ensure_fingerprint()
+            .context("failed to compute snapshot fingerprint")?;
+        // The served catalogue changes now; keeping the snapshot's time would let
+        // If-Modified-Since validators from the replaced payload match.
+        payload.updated_at = Utc::now();
+        let outcome = self.stations.persist_payload(&payload, true).await?;
+        self.apply_overrides(&mut payload).await?;
+
+        *self.memory_cache.write().await = None;
+        *self.processed_cache.write().await = None;
+        *self.health_scores.write().await = None;
+        self.update_cache_state_marker().await?;
+
+        logger().warn(
+            "stations.rollback",
+            json!({
+                "historyId": history_id,
+                "fingerprint": payload.fingerprint,
+                "added": outcome.added,
+                "updated": outcome.updated,
+                "removed": outcome.removed,
+            }),
+        );
+        Ok(RollbackOutcome::Restored(payload))
+    }
+
     pub async fn record_station_click(&self, station_id: &str) -> anyhow::Result<()> {
         self.radio_browser.record_click(station_id).await
     }
@@ -422,12 +545,18 @@ impl AppState {
         _lock: PgRefreshLockGuard,
     ) -> anyhow::Result<StationsPayload> {
         let mut result = refresh::run_refresh(self).await?;
+        self.apply_overrides(&mut result.payload).await?;
         result
             .payload
             .ensure_fingerprint()
             .context("failed to compute refreshed fingerprint")?;
+        sqlx::query("UPDATE station_state SET refreshed_at = NOW() WHERE id = TRUE")
+            .execute(&self.postgres)
+            .await?;
 
         self.update_cache_state_marker().await?;
+        // The refresh just recorded new health checks.
+        *self.health_scores.write().await = None;
 
         self.cache_in_memory(result.payload.clone(), "radio-browser")
             .await;
@@ -492,6 +621,7 @@ impl AppState {
         if changed {
             *self.memory_cache.write().await = None;
             *self.processed_cache.write().await = None;
+            *self.health_scores.write().await = None;
         }
 
         Ok(())
@@ -553,6 +683,7 @@ impl AppState {
                 if let Some(payload) = self.stations.load_latest_payload().await? {
                     if let Some(sanitized) = self.sanitize_payload(payload) {
                         let mut payload = sanitized.payload;
+                        self.apply_overrides(&mut payload).await?;
                         payload
                             .ensure_fingerprint()
                             .context("failed to compute database fingerprint")?;
@@ -588,6 +719,130 @@ impl AppState {
         ))
     }
 
+    /// Starts the periodic Radio Browser refresh. Every replica runs the timer, but only the
+    /// one holding the advisory refresh lock refreshes, and a replica that wakes shortly
+    /// after another one refreshed skips its turn.
+    pub fn spawn_refresh_scheduler(&self) {
+        if self.config.refresh_interval_seconds == 0 {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.refresh_interval_seconds);
+        let jitter = Duration::from_secs(self.config.refresh_jitter_seconds);
+        let state = self.clone();
+        tokio::spawn(async move {
+            let seeds = RandomState::new();
+            loop {
+                let delay =
+                    refresh::jittered_delay(interval, jitter, seeds.hash_one(Instant::now()));
+                state.scheduler_status.write().await.next_run_at =
+                    chrono::Duration::from_std(delay)
+                        .ok()
+                        .map(|delay| Utc::now() + delay);
+                tokio::time::sleep(delay).await;
+
+                let (outcome, error) = match state.run_scheduled_refresh(interval).await {
+                    Ok(outcome) => (outcome, None),
+                    Err(error) => {
+                        logger().warn(
+                            "stations.scheduled_refresh_error",
+                            json!({
+                                "error": format!("{:?}", error)
+                            }),
+                        );
+                        (SchedulerOutcome::Failed, Some(error.to_string()))
+                    }
+                };
+                let mut status = state.scheduler_status.write().await;
+                status.last_run_at = Some(Utc::now());
+                status.last_outcome = Some(outcome);
+                status.last_error = error;
+            }
+        });
+    }
+
+    async fn run_scheduled_refresh(&self, interval: Duration) -> anyhow::Result<SchedulerOutcome> {
+        let process_guard = self.refresh_mutex.lock().await;
+        let Some(lock) = self.try_acquire_refresh_lock().await? else {
+            return Ok(SchedulerOutcome::SkippedLocked);
+        };
+        drop(process_guard);
+
+        let refreshed_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT refreshed_at FROM station_state WHERE id = TRUE")
+                .fetch_optional(&self.postgres)
+                .await?
+                .flatten();
+        let recent = chrono::Duration::from_std(interval / 2).unwrap_or_default();
+        if refreshed_at.is_some_and(|at| Utc::now() - at < recent) {
+            return Ok(SchedulerOutcome::SkippedRecent);
+        }
+
+        self.perform_refresh_with_lock(lock).await?;
+        Ok(SchedulerOutcome::Refreshed)
+    }
+
+    pub async fn scheduler_status(&self) -> SchedulerStatus {
+        self.scheduler_status.read().await.clone()
+    }
+
+    /// Starts the loop that re-checks streams whose validation has expired between full
+    /// refreshes. Each pass checks at most one batch, so the validator's concurrency limit
+    /// bounds the load it adds.
+    pub fn spawn_revalidation_scheduler(&self) {
+        let config = &self.config.stream_validation;
+        if !config.enabled || config.revalidation_interval_seconds == 0 {
+            return;
+        }
+        let interval = Duration::from_secs(config.revalidation_interval_seconds);
+        let state = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(error) = state.revalidate_stations().await {
+                    logger().warn(
+                        "stream.revalidation_error",
+                        json!({
+                            "error": format!("{:?}", error)
+                        }),
+                    );
+                }
+            }
+        });
+    }
+
+    async fn revalidate_stations(&self) -> anyhow::Result<()> {
+        let process_guard = self.refresh_mutex.lock().await;
+        // A refresh validates everything anyway; skip this pass rather than queue behind it.
+        let Some(_lock) = self.try_acquire_refresh_lock().await? else {
+            return Ok(());
+        };
+        drop(process_guard);
+
+        self.ensure_cache_state_sync().await?;
+        // The memory cache holds the overridden view; re-validate the stored catalogue so
+        // hidden stations are not persisted as removed.
+        let Some(live) = self.stations.load_latest_payload().await? else {
+            return Ok(());
+        };
+
+        let result = revalidation::run_revalidation(self, &live).await?;
+        if result.checked > 0 {
+            // The pass recorded health checks even if no station was dropped or restored.
+            *self.health_scores.write().await = None;
+        }
+        let Some(mut payload) = result.payload else {
+            return Ok(());
+        };
+        self.apply_overrides(&mut payload).await?;
+
+        self.update_cache_state_marker().await?;
+        self.cache_in_memory(payload.clone(), "database").await;
+        if let Ok(key) = payload.processed_cache_key() {
+            self.ensure_processed(&key, &payload.stations).await;
+        }
+        Ok(())
+    }
+
     fn schedule_background_refresh(&self) {
         let state = self.clone();
         tokio::spawn(async move {
@@ -602,6 +857,14 @@ impl AppState {
         });
     }
 
+    /// Hides, pins and patches stations per the admin overrides. Runs on every payload before
+    /// it is cached or indexed; the stored catalogue itself stays as upstream reported it.
+    async fn apply_overrides(&self, payload: &mut StationsPayload) -> anyhow::Result<()> {
+        let overrides = self.stations.load_overrides().await?;
+        let state_updated_at = self.read_station_state_updated_at().await?;
+        apply_station_overrides(payload, &overrides, state_updated_at)
+    }
+
     fn sanitize_payload(&self, payload: StationsPayload) -> Option<SanitizedPayload> {
         sanitize_persisted_payload(
             payload,
@@ -620,6 +883,10 @@ impl AppState {
         self.rate_limiter.check(key).await
     }
 
+    pub async fn check_transfer_rate_limit(&self, key: &str) -> RateLimitDecision {
+        self.transfer_rate_limiter.check(key).await
+    }
+
     pub async fn status_snapshot(&self) -> StatusSnapshot {
         let mut system = self.system.lock().await;
         system.refresh_memory();
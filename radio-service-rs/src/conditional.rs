@@ -0,0 +1,176 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+const ETAG_HEX_LEN: usize = 32;
+
+/// Validators for a representation: an ETag and, when the payload has one, the time it last
+/// changed.
+#[derive(Debug, Clone)]
+pub struct Validators {
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl Validators {
+    pub fn new(parts: &[&str], last_modified: Option<DateTime<Utc>>) -> Self {
+        Self {
+            etag: strong_etag(parts),
+            last_modified,
+        }
+    }
+
+    /// Validators for a body that has no better identity than its own bytes.
+    pub fn for_body(body: &[u8]) -> Self {
+        Self {
+            etag: etag_from_digest(Sha256::digest(body).as_slice()),
+            last_modified: None,
+        }
+    }
+
+    /// Whether the request's `If-None-Match` / `If-Modified-Since` headers show the client
+    /// already holds this representation. `If-None-Match` takes precedence when present.
+    pub fn matches(&self, request_headers: &HeaderMap) -> bool {
+        if let Some(value) = request_headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+        {
+            return etag_list_matches(value, &self.etag);
+        }
+        let (Some(last_modified), Some(since)) = (
+            self.last_modified,
+            request_headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| DateTime::parse_from_rfc2822(value).ok()),
+        ) else {
+            return false;
+        };
+        // HTTP dates have second precision.
+        last_modified.timestamp() <= since.timestamp()
+    }
+
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, value);
+        }
+        if let Some(value) = self
+            .last_modified
+            .and_then(|time| HeaderValue::from_str(&http_date(time)).ok())
+        {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    pub fn not_modified(&self) -> Response {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        self.apply(response.headers_mut());
+        response
+    }
+}
+
+pub fn strong_etag(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    etag_from_digest(hasher.finalize().as_slice())
+}
+
+fn etag_from_digest(digest: &[u8]) -> String {
+    let digest = hex::encode(digest);
+    format!("\"{}\"", &digest[..ETAG_HEX_LEN])
+}
+
+pub fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// `If-None-Match` uses the weak comparison, so `W/"x"` matches `"x"` either way round.
+fn etag_list_matches(header_value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+    let etag = opaque(etag);
+    header_value
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque(candidate) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn headers(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn etag_depends_on_every_part() {
+        let etag = strong_etag(&["fingerprint", "query"]);
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        assert_eq!(etag, strong_etag(&["fingerprint", "query"]));
+        assert_ne!(etag, strong_etag(&["fingerprint", "other"]));
+        assert_ne!(strong_etag(&["ab", "c"]), strong_etag(&["a", "bc"]));
+    }
+
+    #[test]
+    fn matches_if_none_match_before_if_modified_since() {
+        let updated = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let validators = Validators::new(&["payload"], Some(updated));
+        let etag = validators.etag.clone();
+
+        assert!(validators.matches(&headers(header::IF_NONE_MATCH, &etag)));
+        assert!(validators.matches(&headers(
+            header::IF_NONE_MATCH,
+            &format!("\"stale\", W/{etag}")
+        )));
+        assert!(!validators.matches(&headers(header::IF_NONE_MATCH, "\"stale\"")));
+
+        assert!(validators.matches(&headers(header::IF_MODIFIED_SINCE, &http_date(updated))));
+        assert!(!validators.matches(&headers(
+            header::IF_MODIFIED_SINCE,
+            "Wed, 01 May 2024 11:59:59 GMT"
+        )));
+
+        let mut both = headers(header::IF_NONE_MATCH, "\"stale\"");
+        both.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&http_date(updated)).unwrap(),
+        );
+        assert!(!validators.matches(&both));
+        assert_eq!(validators.not_modified().status(), StatusCode::NOT_MODIFIED);
+    }
+    #[test]
+    fn weak_comparison_matches_the_gateway() {
+        // Same cases as `cache::tests` in api-gateway-service, which answers cached listings.
+        let cases = [
+            ("\"a\"", "\"a\"", true),
+            ("W/\"a\"", "\"a\"", true),
+            ("\"a\"", "W/\"a\"", true),
+            ("\"stale\", W/\"a\"", "\"a\"", true),
+            ("*", "\"a\"", true),
+            ("\"stale\"", "\"a\"", false),
+            ("W/\"stale\"", "W/\"a\"", false),
+        ];
+        for (if_none_match, etag, expected) in cases {
+            let validators = Validators {
+                etag: etag.to_string(),
+                last_modified: None,
+            };
+            assert_eq!(
+                validators.matches(&headers(header::IF_NONE_MATCH, if_none_match)),
+                expected,
+                "{if_none_match} vs {etag}"
+            );
+        }
+    }
+}
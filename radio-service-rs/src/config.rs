@@ -18,11 +18,22 @@ pub struct Config {
     pub refresh_token: String,
     pub allow_insecure_transports: bool,
     pub radio_browser: RadioBrowserConfig,
+    /// Directory of hand-picked `*.json`, `*.m3u` and `*.pls` station files merged ahead of
+    /// Radio Browser on every refresh.
+    pub curated_stations_dir: Option<String>,
     pub stream_proxy: StreamProxyConfig,
+    pub stream_relay: StreamRelayConfig,
     pub stream_validation: StreamValidationConfig,
+    pub now_playing: NowPlayingConfig,
+    pub favorites: FavoritesConfig,
     pub memory_cache_ttl_seconds: u64,
+    pub payload_history_limit: usize,
     pub refresh_lock_key: String,
     pub refresh_lock_retry_attempts: u64,
+    /// Seconds between scheduled refreshes; 0 leaves refreshing to the endpoint and stale reads.
+    pub refresh_interval_seconds: u64,
+    /// Upper bound of the random delay added to each scheduled refresh.
+    pub refresh_jitter_seconds: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -46,6 +57,10 @@ pub enum SslMode {
 pub struct ApiConfig {
     pub default_page_size: usize,
     pub max_page_size: usize,
+    /// Public prefix of this API as clients reach it through the gateway. Exported playlists
+    /// point at `{public_base_url}/stations/{id}/stream`; a path-only value is completed with
+    /// the request's forwarded host.
+    pub public_base_url: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -61,11 +76,30 @@ pub struct RadioBrowserConfig {
     pub enforce_https_streams: bool,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoritesConfig {
+    /// Named collections a session may keep, including the default one.
+    pub max_collections: usize,
+    /// Upper bound for the slot count a collection can be created or resized with.
+    pub max_collection_slots: usize,
+    /// Lifetime of a one-time code that copies a session's favorites to another device.
+    pub transfer_ttl_seconds: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct StreamProxyConfig {
     pub timeout_ms: u64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamRelayConfig {
+    pub enabled: bool,
+    pub max_relays: usize,
+    pub buffer_chunks: usize,
+    pub burst_bytes: usize,
+    pub max_lag_events: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct StreamValidationConfig {
     pub enabled: bool,
@@ -73,6 +107,17 @@ pub struct StreamValidationConfig {
     pub concurrency: usize,
     pub cache_ttl_seconds: u64,
     pub failure_cache_ttl_seconds: u64,
+    /// Seconds between background re-validation passes; 0 disables them.
+    pub revalidation_interval_seconds: u64,
+    pub revalidation_batch_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NowPlayingConfig {
+    pub timeout_ms: u64,
+    pub cache_ttl_seconds: u64,
+    pub poll_interval_seconds: u64,
+    pub max_watchers: usize,
 }
 
 impl Config {
@@ -85,12 +130,21 @@ impl Config {
             .map(|value| value == "true")
             .unwrap_or(false);
         let radio_browser = RadioBrowserConfig::from_env(allow_insecure_transports)?;
+        let curated_stations_dir = env::var("CURATED_STATIONS_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty());
         let stream_proxy = StreamProxyConfig::from_env()?;
+        let stream_relay = StreamRelayConfig::from_env()?;
         let stream_validation = StreamValidationConfig::from_env()?;
+        let now_playing = NowPlayingConfig::from_env()?;
+        let favorites = FavoritesConfig::from_env()?;
         let memory_cache_ttl_seconds = env_u64("STATIONS_MEMORY_CACHE_TTL", 5)?;
+        let payload_history_limit = env_usize("STATIONS_PAYLOAD_HISTORY", 5)?;
         let refresh_lock_key = env::var("STATIONS_REFRESH_LOCK_KEY")
             .unwrap_or_else(|_| "radio:stations:refresh-lock".into());
         let refresh_lock_retry_attempts = env_u64("STATIONS_REFRESH_LOCK_RETRY_ATTEMPTS", 10)?;
+        let refresh_interval_seconds = env_u64("STATIONS_REFRESH_INTERVAL", 21600)?;
+        let refresh_jitter_seconds = env_u64("STATIONS_REFRESH_JITTER", 300)?;
 
         let config = Self {
             port,
@@ -99,11 +153,18 @@ impl Config {
             refresh_token,
             allow_insecure_transports,
             radio_browser,
+            curated_stations_dir,
             stream_proxy,
+            stream_relay,
             stream_validation,
+            now_playing,
+            favorites,
             memory_cache_ttl_seconds,
+            payload_history_limit,
             refresh_lock_key,
             refresh_lock_retry_attempts,
+            refresh_interval_seconds,
+            refresh_jitter_seconds,
         };
 
         config.validate()?;
@@ -252,9 +313,16 @@ impl ApiConfig {
         let max_page_size = env_usize("API_MAX_PAGE_SIZE", 100)?;
         let default_page_size = default_page_size.min(max_page_size).max(1);
 
+        let public_base_url = env::var("RADIO_PUBLIC_BASE_URL")
+            .ok()
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "/api/radio".into());
+
         Ok(Self {
             default_page_size,
             max_page_size: max_page_size.max(1),
+            public_base_url,
         })
     }
 }
@@ -364,6 +432,16 @@ impl RadioBrowserConfig {
     }
 }
 
+impl FavoritesConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        Ok(Self {
+            max_collections: env_usize("FAVORITES_MAX_COLLECTIONS", 10)?,
+            max_collection_slots: env_usize("FAVORITES_MAX_COLLECTION_SLOTS", 50)?,
+            transfer_ttl_seconds: env_u64("FAVORITES_TRANSFER_TTL_SECONDS", 600)?,
+        })
+    }
+}
+
 impl StreamProxyConfig {
     fn from_env() -> Result<Self, ConfigError> {
         let timeout_ms = env_u64("STREAM_PROXY_TIMEOUT_MS", 5000)?;
@@ -371,6 +449,23 @@ impl StreamProxyConfig {
     }
 }
 
+impl StreamRelayConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        let enabled = env_bool("STREAM_RELAY_ENABLED").unwrap_or(true);
+        let max_relays = env_usize("STREAM_RELAY_MAX_RELAYS", 50)?;
+        let buffer_chunks = env_usize("STREAM_RELAY_BUFFER_CHUNKS", 64)?;
+        let burst_bytes = env_usize("STREAM_RELAY_BURST_BYTES", 64 * 1024)?;
+        let max_lag_events = env_usize("STREAM_RELAY_MAX_LAG_EVENTS", 3)?;
+        Ok(Self {
+            enabled,
+            max_relays,
+            buffer_chunks,
+            burst_bytes,
+            max_lag_events,
+        })
+    }
+}
+
 impl StreamValidationConfig {
     fn from_env() -> Result<Self, ConfigError> {
         let enabled = env_bool("STREAM_VALIDATION_ENABLED").unwrap_or(true);
@@ -378,12 +473,31 @@ impl StreamValidationConfig {
         let concurrency = env_usize("STREAM_VALIDATION_CONCURRENCY", 8)?.max(1);
         let cache_ttl_seconds = env_u64("STREAM_VALIDATION_CACHE_TTL", 86400)?;
         let failure_cache_ttl_seconds = env_u64("STREAM_VALIDATION_FAILURE_CACHE_TTL", 3600)?;
+        let revalidation_interval_seconds = env_u64("STREAM_REVALIDATION_INTERVAL", 300)?;
+        let revalidation_batch_size = env_usize("STREAM_REVALIDATION_BATCH_SIZE", 200)?.max(1);
         Ok(Self {
             enabled,
             timeout_ms,
             concurrency,
             cache_ttl_seconds,
             failure_cache_ttl_seconds,
+            revalidation_interval_seconds,
+            revalidation_batch_size,
+        })
+    }
+}
+
+impl NowPlayingConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        let timeout_ms = env_u64("NOW_PLAYING_TIMEOUT_MS", 5000)?;
+        let cache_ttl_seconds = env_u64("NOW_PLAYING_CACHE_TTL", 15)?;
+        let poll_interval_seconds = env_u64("NOW_PLAYING_POLL_INTERVAL", 20)?;
+        let max_watchers = env_usize("NOW_PLAYING_MAX_WATCHERS", 100)?;
+        Ok(Self {
+            timeout_ms,
+            cache_ttl_seconds,
+            poll_interval_seconds,
+            max_watchers,
         })
     }
 }
@@ -395,6 +509,13 @@ impl Config {
                 "STATIONS_REFRESH_LOCK_KEY must be provided".into(),
             ));
         }
+        if self.refresh_interval_seconds > 0
+            && self.refresh_jitter_seconds > self.refresh_interval_seconds
+        {
+            return Err(ConfigError::Message(
+                "STATIONS_REFRESH_JITTER must not exceed STATIONS_REFRESH_INTERVAL".into(),
+            ));
+        }
         if self.refresh_lock_retry_attempts == 0 {
             return Err(ConfigError::Message(
                 "STATIONS_REFRESH_LOCK_RETRY_ATTEMPTS must be greater than zero".into(),
@@ -420,11 +541,51 @@ impl Config {
                 "STREAM_VALIDATION_FAILURE_CACHE_TTL must be greater than zero".into(),
             ));
         }
+        if self.favorites.max_collections == 0 {
+            return Err(ConfigError::Message(
+                "FAVORITES_MAX_COLLECTIONS must be greater than zero".into(),
+            ));
+        }
+        if self.favorites.max_collection_slots == 0 {
+            return Err(ConfigError::Message(
+                "FAVORITES_MAX_COLLECTION_SLOTS must be greater than zero".into(),
+            ));
+        }
+        if self.favorites.transfer_ttl_seconds == 0 {
+            return Err(ConfigError::Message(
+                "FAVORITES_TRANSFER_TTL_SECONDS must be greater than zero".into(),
+            ));
+        }
         if self.stream_proxy.timeout_ms == 0 {
             return Err(ConfigError::Message(
                 "STREAM_PROXY_TIMEOUT_MS must be greater than zero".into(),
             ));
         }
+        if self.stream_relay.buffer_chunks == 0 {
+            return Err(ConfigError::Message(
+                "STREAM_RELAY_BUFFER_CHUNKS must be greater than zero".into(),
+            ));
+        }
+        if self.payload_history_limit == 0 {
+            return Err(ConfigError::Message(
+                "STATIONS_PAYLOAD_HISTORY must be greater than zero".into(),
+            ));
+        }
+        if self.now_playing.timeout_ms == 0 {
+            return Err(ConfigError::Message(
+                "NOW_PLAYING_TIMEOUT_MS must be greater than zero".into(),
+            ));
+        }
+        if self.now_playing.poll_interval_seconds == 0 {
+            return Err(ConfigError::Message(
+                "NOW_PLAYING_POLL_INTERVAL must be greater than zero".into(),
+            ));
+        }
+        if self.now_playing.max_watchers == 0 {
+            return Err(ConfigError::Message(
+                "NOW_PLAYING_MAX_WATCHERS must be greater than zero".into(),
+            ));
+        }
         self.radio_browser
             .validate(self.allow_insecure_transports)?;
         Ok(())
@@ -448,6 +609,7 @@ mod tests {
         let config = Config::load().expect("config should load with dummy env");
         assert!(config.memory_cache_ttl_seconds > 0);
         assert!(config.refresh_lock_retry_attempts > 0);
+        assert!(config.payload_history_limit > 0);
         assert!(config.stream_validation.concurrency > 0);
     }
 }
@@ -1,26 +1,45 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    config::FavoritesConfig, playlist::PlaylistEntry, sources::stream_url_key, stations::Station,
+};
 
 const FAVORITES_KEY_PREFIX: &str = "radio:favorites:";
 const FAVORITES_CLIENT_PREFIX: &str = "radio:favorites:client:";
-const FAVORITES_STORAGE_VERSION: u32 = 2;
+const FAVORITES_STORAGE_VERSION: u32 = 3;
 pub const FAVORITES_TTL_SECONDS: i64 = 60 * 60 * 24 * 30;
+/// Slots of the default collection, and of new collections created without a size.
 pub const MAX_FAVORITES: usize = 6;
+pub const DEFAULT_COLLECTION_ID: &str = "default";
+const DEFAULT_COLLECTION_NAME: &str = "Favorites";
+const MAX_COLLECTION_ID_LENGTH: usize = 40;
+const MAX_COLLECTION_NAME_LENGTH: usize = 60;
 
 #[derive(Clone)]
 pub struct FavoritesStore {
     pool: PgPool,
+    limits: FavoritesConfig,
 }
 
 impl FavoritesStore {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, limits: FavoritesConfig) -> Self {
+        Self { pool, limits }
+    }
+
+    pub fn limits(&self) -> &FavoritesConfig {
+        &self.limits
     }
 
-    pub async fn read(&self, key: &str) -> anyhow::Result<Vec<FavoriteEntry>> {
+    /// Every collection stored under `key`, the default collection first. Keys that were
+    /// never written still report an empty default collection.
+    pub async fn read(&self, key: &str) -> anyhow::Result<Vec<FavoriteCollection>> {
         let payload: Option<Value> = sqlx::query_scalar(
             r#"
             SELECT payload
@@ -33,16 +52,16 @@ impl FavoritesStore {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(match payload {
-            Some(value) => dedupe_entries(normalize_entries_from_raw(&value)),
-            None => vec![],
-        })
+        Ok(normalize_collections(
+            payload.as_ref().unwrap_or(&Value::Null),
+            &self.limits,
+        ))
     }
 
-    pub async fn write(&self, key: &str, favorites: &[FavoriteEntry]) -> anyhow::Result<()> {
+    pub async fn write(&self, key: &str, collections: &[FavoriteCollection]) -> anyhow::Result<()> {
         let payload = FavoritesPayload {
             version: FAVORITES_STORAGE_VERSION,
-            entries: favorites,
+            collections,
         };
         let serialized = serde_json::to_value(&payload)?;
         sqlx::query(
@@ -63,8 +82,10 @@ impl FavoritesStore {
         Ok(())
     }
 
-    pub async fn refresh_ttl(&self, key: &str) -> anyhow::Result<()> {
-        sqlx::query(
+    /// Extends the lifetime of stored favorites. Returns false when nothing is stored under
+    /// `key` or it already expired.
+    pub async fn refresh_ttl(&self, key: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
             r#"
             UPDATE radio_favorites
               SET expires_at = NOW() + ($2 * interval '1 second'),
@@ -77,8 +98,221 @@ impl FavoritesStore {
         .bind(FAVORITES_TTL_SECONDS)
         .execute(&self.pool)
         .await?;
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Issues a one-time code for the favorites stored under `source_key`, replacing any code
+    /// the session issued before. Returns `None` when the session has no stored favorites.
+    /// The favorites' TTL is refreshed so they outlive the code.
+    pub async fn create_transfer(
+        &self,
+        source_key: &str,
+    ) -> anyhow::Result<Option<FavoritesTransfer>> {
+        if !self.refresh_ttl(source_key).await? {
+            return Ok(None);
+        }
+        sqlx::query(
+            r#"
+            DELETE FROM radio_favorite_transfers
+            WHERE source_key = $1
+               OR expires_at <= NOW()
+            "#,
+        )
+        .bind(source_key)
+        .execute(&self.pool)
+        .await?;
+
+        for _ in 0..TRANSFER_CODE_ATTEMPTS {
+            let code = generate_transfer_code();
+            let expires_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+                r#"
+                INSERT INTO radio_favorite_transfers (code, source_key, expires_at)
+                VALUES ($1, $2, NOW() + ($3 * interval '1 second'))
+                ON CONFLICT (code) DO NOTHING
+                RETURNING expires_at
+                "#,
+            )
+            .bind(&code)
+            .bind(source_key)
+            .bind(self.limits.transfer_ttl_seconds as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+            if let Some(expires_at) = expires_at {
+                return Ok(Some(FavoritesTransfer {
+                    code: format_transfer_code(&code),
+                    expires_at,
+                }));
+            }
+        }
+        Err(anyhow::anyhow!("could not allocate a unique transfer code"))
+    }
+
+    /// Consumes a transfer code on behalf of `redeemer_key`. The session that issued a code
+    /// cannot redeem it, and trying leaves the code usable for the other device.
+    pub async fn redeem_transfer(
+        &self,
+        code: &str,
+        redeemer_key: &str,
+    ) -> anyhow::Result<TransferRedemption> {
+        let source_key: Option<String> = sqlx::query_scalar(
+            r#"
+            DELETE FROM radio_favorite_transfers
+            WHERE code = $1
+              AND source_key <> $2
+              AND expires_at > NOW()
+            RETURNING source_key
+            "#,
+        )
+        .bind(code)
+        .bind(redeemer_key)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(source_key) = source_key {
+            return Ok(TransferRedemption::Redeemed(source_key));
+        }
+
+        let own: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+              SELECT 1
+              FROM radio_favorite_transfers
+              WHERE code = $1
+                AND source_key = $2
+                AND expires_at > NOW()
+            )
+            "#,
+        )
+        .bind(code)
+        .bind(redeemer_key)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(if own {
+            TransferRedemption::OwnCode
+        } else {
+            TransferRedemption::Missing
+        })
+    }
+}
+
+pub enum TransferRedemption {
+    /// The code was consumed; holds the key of the favorites it was issued for.
+    Redeemed(String),
+    /// The redeeming session issued the code itself.
+    OwnCode,
+    /// Unknown, expired or already redeemed.
+    Missing,
+}
+
+/// A claim code that copies one session's favorites into another session.
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoritesTransfer {
+    pub code: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferMode {
+    /// Add the transferred stations to the collections already on this device.
+    #[default]
+    Merge,
+    /// Discard this device's collections and take the transferred ones.
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TransferSummary {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+/// Applies transferred collections to the redeeming session. Merging appends stations to the
+/// collection with the same id while it has free slots and adds collections that do not exist
+/// yet while the session is below `max_collections`; everything else is skipped.
+pub fn apply_transfer(
+    target: &mut Vec<FavoriteCollection>,
+    source: Vec<FavoriteCollection>,
+    mode: TransferMode,
+    limits: &FavoritesConfig,
+) -> TransferSummary {
+    let mut summary = TransferSummary::default();
+    if mode == TransferMode::Replace {
+        summary.added = source
+            .iter()
+            .map(|collection| collection.entries.len())
+            .sum();
+        *target = source;
+        return summary;
     }
+
+    for collection in source {
+        match target
+            .iter()
+            .position(|existing| existing.id == collection.id)
+        {
+            Some(index) => {
+                let existing = &mut target[index];
+                for entry in collection.entries {
+                    if existing.entries.iter().any(|saved| saved.id == entry.id) {
+                        continue;
+                    }
+                    if existing.entries.len() >= existing.max_slots {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    existing.entries.push(entry);
+                    summary.added += 1;
+                }
+            }
+            None if target.len() < limits.max_collections => {
+                summary.added += collection.entries.len();
+                target.push(collection);
+            }
+            None => summary.skipped += collection.entries.len(),
+        }
+    }
+    summary
+}
+
+/// Uppercase letters and digits without the look-alikes 0/O, 1/I/L, so codes survive being
+/// read aloud or typed from another screen.
+const TRANSFER_CODE_ALPHABET: &[u8] = b"23456789ABCDEFGHJKMNPQRSTUVWXYZ";
+const TRANSFER_CODE_LENGTH: usize = 8;
+const TRANSFER_CODE_ATTEMPTS: usize = 5;
+
+fn generate_transfer_code() -> String {
+    let alphabet_len = TRANSFER_CODE_ALPHABET.len();
+    // Rejecting bytes above the largest multiple of the alphabet size keeps the draw unbiased.
+    let threshold = (256 / alphabet_len * alphabet_len) as u8;
+    let mut code = String::with_capacity(TRANSFER_CODE_LENGTH);
+    while code.len() < TRANSFER_CODE_LENGTH {
+        let random = Sha256::digest(Uuid::new_v4().as_bytes());
+        for byte in random.iter().copied().filter(|byte| *byte < threshold) {
+            code.push(TRANSFER_CODE_ALPHABET[byte as usize % alphabet_len] as char);
+            if code.len() == TRANSFER_CODE_LENGTH {
+                break;
+            }
+        }
+    }
+    code
+}
+
+fn format_transfer_code(code: &str) -> String {
+    let (head, tail) = code.split_at(TRANSFER_CODE_LENGTH / 2);
+    format!("{head}-{tail}")
+}
+
+/// Accepts codes in any case, with or without separators.
+pub fn sanitize_transfer_code(value: &str) -> Option<String> {
+    let code: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    (code.len() == TRANSFER_CODE_LENGTH
+        && code.bytes().all(|b| TRANSFER_CODE_ALPHABET.contains(&b)))
+    .then_some(code)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -112,10 +346,41 @@ pub struct FavoriteEntry {
     pub station: Option<FavoriteStation>,
 }
 
+/// A named, ordered list of favorites with its own slot count.
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoriteCollection {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "maxSlots")]
+    pub max_slots: usize,
+    pub entries: Vec<FavoriteEntry>,
+}
+
+impl FavoriteCollection {
+    pub fn new(id: String, name: String, max_slots: usize) -> Self {
+        Self {
+            id,
+            name,
+            max_slots,
+            entries: Vec::new(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct FavoritesPayload<'a> {
     version: u32,
-    entries: &'a [FavoriteEntry],
+    collections: &'a [FavoriteCollection],
+}
+
+#[derive(Deserialize)]
+struct StoredCollection {
+    id: String,
+    name: Option<String>,
+    #[serde(rename = "maxSlots")]
+    max_slots: Option<usize>,
+    #[serde(default)]
+    entries: Value,
 }
 
 #[derive(Deserialize)]
@@ -161,11 +426,123 @@ fn normalize_entries_from_raw(value: &Value) -> Vec<FavoriteEntry> {
     }
 }
 
+/// Reads a stored favorites payload. Version 3 payloads hold named collections; older ones
+/// hold a single list, which becomes the default collection.
+fn normalize_collections(value: &Value, limits: &FavoritesConfig) -> Vec<FavoriteCollection> {
+    let stored: Vec<FavoriteCollection> = match value.get("collections").and_then(Value::as_array) {
+        Some(items) => items
+            .iter()
+            .filter_map(|item| serde_json::from_value::<StoredCollection>(item.clone()).ok())
+            .filter_map(|collection| {
+                Some(FavoriteCollection {
+                    id: sanitize_collection_id(&collection.id)?,
+                    name: collection
+                        .name
+                        .as_deref()
+                        .and_then(sanitize_collection_name)
+                        .unwrap_or_else(|| DEFAULT_COLLECTION_NAME.into()),
+                    max_slots: collection.max_slots.unwrap_or(MAX_FAVORITES),
+                    entries: normalize_entries_from_raw(&collection.entries),
+                })
+            })
+            .collect(),
+        None => vec![FavoriteCollection {
+            entries: normalize_entries_from_raw(value),
+            ..default_collection()
+        }],
+    };
+
+    let mut collections = Vec::with_capacity(stored.len() + 1);
+    let (defaults, others): (Vec<_>, Vec<_>) = stored
+        .into_iter()
+        .partition(|collection| collection.id == DEFAULT_COLLECTION_ID);
+    collections.push(
+        defaults
+            .into_iter()
+            .next()
+            .unwrap_or_else(default_collection),
+    );
+    let mut seen = HashSet::from([DEFAULT_COLLECTION_ID.to_string()]);
+    collections.extend(
+        others
+            .into_iter()
+            .filter(|collection| seen.insert(collection.id.clone())),
+    );
+    collections.truncate(limits.max_collections.max(1));
+    for collection in &mut collections {
+        collection.max_slots = collection
+            .max_slots
+            .clamp(1, limits.max_collection_slots.max(1));
+        collection.entries = dedupe_entries(
+            std::mem::take(&mut collection.entries),
+            collection.max_slots,
+        );
+    }
+    collections
+}
+
+fn default_collection() -> FavoriteCollection {
+    FavoriteCollection::new(
+        DEFAULT_COLLECTION_ID.into(),
+        DEFAULT_COLLECTION_NAME.into(),
+        MAX_FAVORITES,
+    )
+}
+
+/// Lowercase letters, digits and dashes.
+pub fn sanitize_collection_id(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_COLLECTION_ID_LENGTH {
+        return None;
+    }
+    trimmed
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        .then(|| trimmed.to_string())
+}
+
+pub fn sanitize_collection_name(value: &str) -> Option<String> {
+    let name = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty()
+        || name.chars().count() > MAX_COLLECTION_NAME_LENGTH
+        || name.chars().any(char::is_control)
+    {
+        return None;
+    }
+    Some(name)
+}
+
+/// A slug of `name` that no existing collection uses, e.g. "Night Drive" -> "night-drive".
+pub fn new_collection_id(name: &str, existing: &[FavoriteCollection]) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let mut slug = slug.trim_end_matches('-').to_string();
+    slug.truncate(MAX_COLLECTION_ID_LENGTH - 4);
+    let slug = match slug.trim_end_matches('-') {
+        "" => "collection".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+    let taken = |id: &str| existing.iter().any(|collection| collection.id == id);
+    if !taken(&slug) {
+        return slug;
+    }
+    (2..)
+        .map(|n| format!("{slug}-{n}"))
+        .find(|id| !taken(id))
+        .unwrap_or(slug)
+}
+
 fn current_timestamp() -> i64 {
     chrono::Utc::now().timestamp_millis()
 }
 
-pub fn dedupe_entries(entries: Vec<FavoriteEntry>) -> Vec<FavoriteEntry> {
+pub fn dedupe_entries(entries: Vec<FavoriteEntry>, limit: usize) -> Vec<FavoriteEntry> {
     let mut seen = HashSet::new();
     let mut deduped = Vec::new();
     for entry in entries {
@@ -182,7 +559,7 @@ pub fn dedupe_entries(entries: Vec<FavoriteEntry>) -> Vec<FavoriteEntry> {
                 });
             }
         }
-        if deduped.len() >= MAX_FAVORITES {
+        if deduped.len() >= limit {
             break;
         }
     }
@@ -226,3 +603,273 @@ pub fn is_valid_favorites_session(value: &str) -> bool {
         .chars()
         .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnmatchedReason {
+    NotFound,
+    /// Only the name matched, and more than one station carries it.
+    Ambiguous,
+}
+
+#[derive(Debug, Default)]
+pub struct PlaylistMatch {
+    /// Matched station ids in playlist order, without duplicates.
+    pub station_ids: Vec<String>,
+    pub unmatched: Vec<(PlaylistEntry, UnmatchedReason)>,
+}
+
+/// Resolves imported playlist entries to catalogue stations. An entry matches, in order of
+/// preference, by a proxied `/stations/{id}/stream` URL from our own export, by a `tvg-id`
+/// attribute, by its upstream stream URL, or by a name only one station uses.
+pub fn match_playlist_entries(entries: &[PlaylistEntry], stations: &[Station]) -> PlaylistMatch {
+    let by_id: HashSet<&str> = stations.iter().map(|station| station.id.as_str()).collect();
+    let mut by_url: HashMap<String, &str> = HashMap::with_capacity(stations.len());
+    let mut by_name: HashMap<String, Vec<&str>> = HashMap::with_capacity(stations.len());
+    for station in stations {
+        by_url
+            .entry(stream_url_key(&station.stream_url))
+            .or_insert(&station.id);
+        by_name
+            .entry(normalize_name(&station.name))
+            .or_default()
+            .push(&station.id);
+    }
+
+    let mut result = PlaylistMatch::default();
+    let mut seen = HashSet::new();
+    for entry in entries {
+        let known = |id: Option<String>| id.filter(|id| by_id.contains(id.as_str()));
+        let matched = known(proxied_station_id(&entry.url))
+            .or_else(|| known(entry.attribute("tvg-id").map(str::to_string)))
+            .or_else(|| {
+                by_url
+                    .get(&stream_url_key(&entry.url))
+                    .map(|id| id.to_string())
+            });
+        let matched = match matched {
+            Some(id) => Ok(id),
+            None => match entry
+                .title
+                .as_deref()
+                .and_then(|title| by_name.get(&normalize_name(title)))
+                .map(Vec::as_slice)
+            {
+                Some([id]) => Ok(id.to_string()),
+                Some([_, _, ..]) => Err(UnmatchedReason::Ambiguous),
+                _ => Err(UnmatchedReason::NotFound),
+            },
+        };
+        match matched {
+            Ok(id) => {
+                if seen.insert(id.clone()) {
+                    result.station_ids.push(id);
+                }
+            }
+            Err(reason) => result.unmatched.push((entry.clone(), reason)),
+        }
+    }
+    result
+}
+
+/// The station id in a `…/stations/{id}/stream` URL, as produced by the favorites export.
+fn proxied_station_id(url: &str) -> Option<String> {
+    let (_, rest) = url.rsplit_once("/stations/")?;
+    let (id, tail) = rest.split_once('/')?;
+    if !tail.starts_with("stream") {
+        return None;
+    }
+    let decoded = urlencoding::decode(id).ok()?;
+    sanitize_station_id(&decoded)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    fn limits(max_collections: usize) -> FavoritesConfig {
+        FavoritesConfig {
+            max_collections,
+            max_collection_slots: 20,
+            transfer_ttl_seconds: 600,
+        }
+    }
+
+    fn collection(id: &str, max_slots: usize, ids: &[&str]) -> FavoriteCollection {
+        FavoriteCollection {
+            entries: ids
+                .iter()
+                .map(|id| FavoriteEntry {
+                    id: (*id).into(),
+                    saved_at: 1,
+                    station: None,
+                })
+                .collect(),
+            ..FavoriteCollection::new(id.into(), id.into(), max_slots)
+        }
+    }
+
+    fn entry(url: &str, title: &str) -> PlaylistEntry {
+        PlaylistEntry {
+            url: url.into(),
+            title: Some(title.into()),
+            attributes: vec![],
+        }
+    }
+
+    #[test]
+    fn migrates_single_list_payloads_into_the_default_collection() {
+        let limits = limits(3);
+        let legacy = serde_json::json!({
+            "version": 2,
+            "entries": [
+                { "id": "station-a", "savedAt": 1 },
+                { "id": "station-b", "savedAt": 2 },
+                { "id": "station-a", "savedAt": 3 }
+            ]
+        });
+        let collections = normalize_collections(&legacy, &limits);
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].id, DEFAULT_COLLECTION_ID);
+        assert_eq!(collections[0].max_slots, MAX_FAVORITES);
+        let ids: Vec<_> = collections[0]
+            .entries
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["station-a", "station-b"]);
+
+        let current = serde_json::json!({
+            "version": 3,
+            "collections": [
+                { "id": "night", "name": "Night", "maxSlots": 500, "entries": ["station-c"] },
+                { "id": "default", "name": "Favorites", "maxSlots": 6, "entries": [] },
+                { "id": "night", "name": "Duplicate", "entries": [] },
+                { "id": "work", "name": "Work", "entries": [] },
+                { "id": "extra", "name": "Over the limit", "entries": [] }
+            ]
+        });
+        let collections = normalize_collections(&current, &limits);
+        let ids: Vec<_> = collections.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["default", "night", "work"]);
+        assert_eq!(collections[1].max_slots, 20);
+        assert_eq!(collections[1].entries[0].id, "station-c");
+
+        assert_eq!(
+            new_collection_id("  Night Drive! ", &collections),
+            "night-drive"
+        );
+        assert_eq!(new_collection_id("Night", &collections), "night-2");
+        assert_eq!(new_collection_id("☾", &collections), "collection");
+    }
+
+    #[test]
+    fn matches_playlist_entries_against_the_catalogue() {
+        let stations = [
+            Station {
+                name: "Jazz FM".into(),
+                stream_url: "https://jazz.example.com/live".into(),
+                ..test_station("abc")
+            },
+            Station {
+                name: "Radio One".into(),
+                stream_url: "https://one.example.com/a".into(),
+                ..test_station("def")
+            },
+            Station {
+                name: "Radio One".into(),
+                stream_url: "https://one.example.com/b".into(),
+                ..test_station("ghi")
+            },
+            Station {
+                name: "Talk  Radio".into(),
+                stream_url: "https://talk.example.com".into(),
+                ..test_station("jkl")
+            },
+        ];
+        let result = match_playlist_entries(
+            &[
+                entry("https://site.example/api/radio/stations/ghi/stream", "x"),
+                entry("https://JAZZ.example.com/live/", "renamed"),
+                entry("https://elsewhere.example.com", "talk radio"),
+                entry("https://elsewhere.example.com", "Radio One"),
+                entry("https://unknown.example.com", "Nothing"),
+                entry("https://site.example/api/radio/stations/abc/stream", "dup"),
+            ],
+            &stations,
+        );
+
+        assert_eq!(result.station_ids, vec!["ghi", "abc", "jkl"]);
+        let reasons: Vec<_> = result
+            .unmatched
+            .iter()
+            .map(|(entry, reason)| (entry.title.as_deref().unwrap_or_default(), *reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("Radio One", UnmatchedReason::Ambiguous),
+                ("Nothing", UnmatchedReason::NotFound),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_transferred_collections_within_the_limits() {
+        let mut target = vec![
+            collection("default", 3, &["station-a", "station-b"]),
+            collection("work", 5, &[]),
+        ];
+        let source = vec![
+            collection("default", 6, &["station-b", "station-c", "station-d"]),
+            collection("night", 6, &["station-e"]),
+            collection("gym", 6, &["station-f", "station-g"]),
+        ];
+
+        let summary = apply_transfer(&mut target, source.clone(), TransferMode::Merge, &limits(3));
+        let ids: Vec<_> = target.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["default", "work", "night"]);
+        let defaults: Vec<_> = target[0].entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(defaults, vec!["station-a", "station-b", "station-c"]);
+        assert_eq!(
+            summary,
+            TransferSummary {
+                added: 2,
+                skipped: 3
+            }
+        );
+
+        let summary = apply_transfer(&mut target, source, TransferMode::Replace, &limits(3));
+        assert_eq!(target.len(), 3);
+        assert_eq!(target[2].id, "gym");
+        assert_eq!(
+            summary,
+            TransferSummary {
+                added: 6,
+                skipped: 0
+            }
+        );
+    }
+
+    #[test]
+    fn transfer_codes_are_typeable() {
+        let code = generate_transfer_code();
+        assert_eq!(code.len(), TRANSFER_CODE_LENGTH);
+        let formatted = format_transfer_code(&code);
+        assert_eq!(
+            sanitize_transfer_code(&formatted.to_lowercase()),
+            Some(code)
+        );
+        assert_eq!(sanitize_transfer_code("ab2c-d3ef"), Some("AB2CD3EF".into()));
+        assert_eq!(sanitize_transfer_code("AB0C-D3EF"), None);
+        assert_eq!(sanitize_transfer_code("AB2C"), None);
+    }
+}
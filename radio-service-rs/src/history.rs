@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+
+use crate::favorites::{FavoriteStation, FAVORITES_TTL_SECONDS};
+
+/// Plays kept per session; older ones are dropped as new plays arrive.
+pub const MAX_HISTORY_ENTRIES: i64 = 200;
+
+/// Recently played stations per favorites key. A session's history lives as long as its
+/// favorites: every play and every read pushes the expiry of the whole history out by
+/// `FAVORITES_TTL_SECONDS`, and an idle history expires as one.
+#[derive(Clone)]
+pub struct HistoryStore {
+    pool: PgPool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    #[serde(rename = "stationId")]
+    pub station_id: String,
+    #[serde(rename = "playedAt")]
+    pub played_at: DateTime<Utc>,
+    /// The station as it looked when played, for stations that later leave the catalogue.
+    pub station: Option<FavoriteStation>,
+}
+
+pub struct HistoryPage {
+    pub total: usize,
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl HistoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a play. Playing the most recent station again only moves its time forward, so
+    /// re-tuning does not flood the history with one station.
+    pub async fn record(&self, key: &str, station: &FavoriteStation) -> anyhow::Result<()> {
+        let snapshot = serde_json::to_value(station)?;
+        let mut tx = self.pool.begin().await?;
+
+        let touched = sqlx::query(
+            r#"
+            UPDATE radio_listening_history
+              SET played_at = NOW(),
+                  station = $3
+            WHERE id = (
+                SELECT id
+                FROM radio_listening_history
+                WHERE key = $1
+                  AND expires_at > NOW()
+                ORDER BY played_at DESC, id DESC
+                LIMIT 1
+              )
+              AND station_id = $2
+            "#,
+        )
+        .bind(key)
+        .bind(&station.id)
+        .bind(&snapshot)
+        .execute(&mut *tx)
+        .await?;
+        if touched.rows_affected() == 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO radio_listening_history (key, station_id, station, expires_at)
+                VALUES ($1, $2, $3, NOW() + ($4 * interval '1 second'))
+                "#,
+            )
+            .bind(key)
+            .bind(&station.id)
+            .bind(&snapshot)
+            .bind(FAVORITES_TTL_SECONDS)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM radio_listening_history
+            WHERE key = $1
+              AND (
+                expires_at <= NOW()
+                OR id NOT IN (
+                  SELECT id
+                  FROM radio_listening_history
+                  WHERE key = $1
+                  ORDER BY played_at DESC, id DESC
+                  LIMIT $2
+                )
+              )
+            "#,
+        )
+        .bind(key)
+        .bind(MAX_HISTORY_ENTRIES)
+        .execute(&mut *tx)
+        .await?;
+        refresh_ttl(&mut *tx, key).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Newest plays first.
+    pub async fn read(
+        &self,
+        key: &str,
+        limit: usize,
+        offset: usize,
+    ) -> anyhow::Result<HistoryPage> {
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM radio_listening_history
+            WHERE key = $1
+              AND expires_at > NOW()
+            "#,
+        )
+        .bind(key)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT station_id, station, played_at
+            FROM radio_listening_history
+            WHERE key = $1
+              AND expires_at > NOW()
+            ORDER BY played_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(key)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let station: Option<Value> = row.try_get("station")?;
+                Ok(HistoryEntry {
+                    station_id: row.try_get("station_id")?,
+                    played_at: row.try_get("played_at")?,
+                    station: station.and_then(|value| serde_json::from_value(value).ok()),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        Ok(HistoryPage {
+            total: total.max(0) as usize,
+            entries,
+        })
+    }
+
+    pub async fn refresh_ttl(&self, key: &str) -> anyhow::Result<()> {
+        refresh_ttl(&self.pool, key).await
+    }
+
+    pub async fn clear(&self, key: &str) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM radio_listening_history WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+async fn refresh_ttl<'e, E>(executor: E, key: &str) -> anyhow::Result<()>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        UPDATE radio_listening_history
+          SET expires_at = NOW() + ($2 * interval '1 second')
+        WHERE key = $1
+          AND expires_at > NOW()
+        "#,
+    )
+    .bind(key)
+    .bind(FAVORITES_TTL_SECONDS)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
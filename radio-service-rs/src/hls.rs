@@ -0,0 +1,144 @@
+use url::Url;
+
+/// HLS playlists are served as `*mpegurl` but plenty of origins send `.m3u8` files as
+/// `text/plain` or `application/octet-stream`.
+pub fn is_hls_url(url: &str) -> bool {
+    Url::parse(url)
+        .map(|parsed| parsed.path().to_ascii_lowercase().ends_with(".m3u8"))
+        .unwrap_or(false)
+}
+
+/// The first variant of a master playlist, as written in the playlist. None for media
+/// playlists.
+pub fn first_variant_uri(playlist: &str) -> Option<String> {
+    let mut lines = playlist.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if line.starts_with("#EXT-X-STREAM-INF") {
+            return lines
+                .find(|candidate| !candidate.is_empty() && !candidate.starts_with('#'))
+                .map(str::to_string);
+        }
+    }
+    None
+}
+
+pub struct MediaSegment {
+    /// The `#EXTINF` value that preceded the segment, if any.
+    pub info: Option<String>,
+    pub uri: String,
+}
+
+/// The newest segment of a media playlist: the live edge, and the one least likely to have
+/// left a sliding window by the time it is fetched.
+pub fn last_media_segment(playlist: &str) -> Option<MediaSegment> {
+    let mut pending_info = None;
+    let mut last = None;
+    for line in playlist.lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending_info = Some(info.to_string());
+        } else if !line.is_empty() && !line.starts_with('#') {
+            last = Some(MediaSegment {
+                info: pending_info.take(),
+                uri: line.to_string(),
+            });
+        }
+    }
+    last
+}
+
+/// Resolves a playlist URI the way the stream proxy rewrites it; see `upgrade_playlist_url`.
+pub fn resolve_playlist_uri(base: &Url, uri: &str) -> Option<Url> {
+    upgrade_playlist_url(base.join(uri).ok()?)
+}
+
+/// Playlist URIs are upgraded to HTTPS and refused if they still are not HTTPS.
+pub fn upgrade_playlist_url(mut url: Url) -> Option<Url> {
+    if url.scheme() == "http" {
+        let _ = url.set_scheme("https");
+    }
+    (url.scheme() == "https").then_some(url)
+}
+
+/// Whether the stream proxy may fetch `target` on behalf of the station at `stream_url`:
+/// the same origin or host, a sibling host under the stream's parent domain, or any host
+/// under the same registrable-looking base domain.
+pub fn is_segment_origin_allowed(stream_url: &str, target: &Url) -> bool {
+    let Ok(stream_origin) = Url::parse(stream_url) else {
+        return false;
+    };
+    if stream_origin.origin().ascii_serialization() == target.origin().ascii_serialization() {
+        return true;
+    }
+    let Some(stream_host) = stream_origin.host_str() else {
+        return false;
+    };
+    let Some(target_host) = target.host_str() else {
+        return false;
+    };
+    if stream_host == target_host {
+        return true;
+    }
+    if let Some(parent) = stream_host.split_once('.').map(|(_, suffix)| suffix) {
+        if target_host == parent || target_host.ends_with(&format!(".{parent}")) {
+            return true;
+        }
+    }
+    match (base_domain(stream_host), base_domain(target_host)) {
+        (Some(stream_base), Some(target_base)) => stream_base == target_base,
+        _ => false,
+    }
+}
+
+fn base_domain(host: &str) -> Option<String> {
+    let parts: Vec<&str> = host.split('.').filter(|part| !part.is_empty()).collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    let base = parts[parts.len() - 2..].join(".");
+    if base.is_empty() {
+        None
+    } else {
+        Some(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_playlists_and_segment_origins_like_the_proxy() {
+        let master = "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=64000\nlow/index.m3u8\n";
+        let media =
+            "#EXTM3U\n#EXT-X-TARGETDURATION:6\n#EXTINF:6,\nseg-1.aac\n#EXTINF:6,\nseg-2.aac\n";
+        assert_eq!(first_variant_uri(master).as_deref(), Some("low/index.m3u8"));
+        assert_eq!(first_variant_uri(media), None);
+        assert_eq!(
+            last_media_segment(media).map(|segment| segment.uri),
+            Some("seg-2.aac".to_string())
+        );
+        assert!(last_media_segment("#EXTM3U\n#EXT-X-ENDLIST\n").is_none());
+
+        let base = Url::parse("https://live.example.com/radio/low/index.m3u8").unwrap();
+        assert_eq!(
+            resolve_playlist_uri(&base, "http://cdn.example.com/seg-1.aac").map(String::from),
+            Some("https://cdn.example.com/seg-1.aac".to_string())
+        );
+        assert_eq!(
+            resolve_playlist_uri(&base, "ftp://cdn.example.com/seg-1.aac"),
+            None
+        );
+
+        let stream = "https://live.example.com/radio/master.m3u8";
+        let allowed =
+            |target: &str| is_segment_origin_allowed(stream, &Url::parse(target).unwrap());
+        assert!(allowed("https://live.example.com/radio/low/seg-1.aac"));
+        assert!(allowed("https://cdn.example.com/seg-1.aac"));
+        assert!(!allowed("https://example.org/seg-1.aac"));
+
+        assert!(is_hls_url(
+            "https://live.example.com/radio/Master.M3U8?token=1"
+        ));
+        assert!(!is_hls_url("https://live.example.com/radio.m3u"));
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     net::SocketAddr,
     time::{Instant, SystemTime, UNIX_EPOCH},
@@ -10,12 +10,15 @@ use axum::{
     extract::{ConnectInfo, Path, Query, State},
     http::{header, HeaderMap, HeaderValue, Request, StatusCode},
     middleware::{self, Next},
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post, put},
     Json, Router,
 };
 use chrono::Utc;
-use futures_util::TryStreamExt;
+use futures_util::{stream, StreamExt, TryStreamExt};
 use reqwest::header::{HeaderMap as ReqwestHeaderMap, HeaderName};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -28,12 +31,26 @@ use uuid::Uuid;
 
 use crate::logging::logger;
 use crate::{
-    app_state::{AppState, RateLimitMetadata},
+    app_state::{AppState, RateLimitMetadata, RollbackOutcome},
+    conditional::Validators,
     favorites::{
-        build_favorites_key, dedupe_entries, is_valid_favorites_session, is_valid_session_token,
-        sanitize_station_id, FavoriteEntry, FavoriteStation, MAX_FAVORITES,
+        apply_transfer, build_favorites_key, dedupe_entries, is_valid_favorites_session,
+        is_valid_session_token, match_playlist_entries, new_collection_id, sanitize_collection_id,
+        sanitize_collection_name, sanitize_station_id, sanitize_transfer_code, FavoriteCollection,
+        FavoriteEntry, FavoriteStation, TransferMode, TransferRedemption, TransferSummary,
+        UnmatchedReason, DEFAULT_COLLECTION_ID, MAX_FAVORITES,
+    },
+    history::HistoryEntry,
+    hls::{is_hls_url, is_segment_origin_allowed, upgrade_playlist_url},
+    now_playing::NowPlayingError,
+    playlist::{is_hls_playlist, PlaylistEntry, PlaylistFormat, StreamPlaylist},
+    stations::{
+        apply_change_overrides, intersect_lists, sanitize_station_url, union_lists, ChangeCursor,
+        FacetCounts, PayloadHistoryEntry, ProcessedStations, SortOrder, Station, StationOverride,
+        StationPatch, StationSort, StationsPayload,
     },
-    stations::{intersect_lists, ProcessedStations, Station, StationsPayload},
+    stream_health::{HealthScore, HealthScores},
+    stream_relay::{RelayError, RelayListener},
 };
 
 const OPENAPI_SPEC: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json"));
@@ -177,6 +194,7 @@ enum ApiError {
     NotFound(&'static str),
     Conflict(&'static str),
     Forbidden(&'static str),
+    Gone(&'static str),
     TooManyRequests {
         message: &'static str,
         info: RateLimitMetadata,
@@ -237,6 +255,9 @@ impl IntoResponse for ApiError {
                 Json(ErrorResponse { error: message }),
             )
                 .into_response(),
+            ApiError::Gone(message) => {
+                (StatusCode::GONE, Json(ErrorResponse { error: message })).into_response()
+            }
             ApiError::TooManyRequests { message, info } => {
                 let mut response = (
                     StatusCode::TOO_MANY_REQUESTS,
@@ -294,11 +315,50 @@ pub async fn serve(state: AppState) -> anyhow::Result<()> {
         .route("/docs/json", get(openapi_spec))
         .route("/docs", get(swagger_ui))
         .route("/stations", get(get_stations))
+        .route("/stations/changes", get(get_station_changes))
         .route("/stations/refresh", post(refresh_stations))
+        .route("/stations/payloads", get(list_station_payloads))
+        .route(
+            "/stations/payloads/{history_id}/rollback",
+            post(rollback_station_payload),
+        )
+        .route("/stations/overrides", get(list_station_overrides))
+        .route(
+            "/stations/{station_id}/override",
+            put(put_station_override).delete(delete_station_override),
+        )
         .route("/stations/{station_id}/stream", get(stream_station))
         .route("/stations/{station_id}/stream/segment", get(stream_segment))
+        .route("/stations/{station_id}/similar", get(similar_stations))
         .route("/stations/{station_id}/click", post(record_click))
+        .route("/stations/{station_id}/now-playing", get(now_playing))
+        .route(
+            "/stations/{station_id}/now-playing/events",
+            get(now_playing_events),
+        )
+        .route("/history", get(get_history).delete(clear_history))
         .route("/favorites", get(get_favorites))
+        .route("/favorites/export", get(export_favorites))
+        .route("/favorites/import", post(import_favorites))
+        .route(
+            "/favorites/collections",
+            get(list_favorite_collections).post(create_favorite_collection),
+        )
+        .route(
+            "/favorites/collections/{collection_id}",
+            get(get_favorite_collection)
+                .patch(update_favorite_collection)
+                .delete(delete_favorite_collection),
+        )
+        .route(
+            "/favorites/collections/{collection_id}/stations/{station_id}",
+            put(upsert_collection_favorite).delete(delete_collection_favorite),
+        )
+        .route("/favorites/transfers", post(create_favorites_transfer))
+        .route(
+            "/favorites/transfers/redeem",
+            post(redeem_favorites_transfer),
+        )
         .route(
             "/favorites/{station_id}",
             put(upsert_favorite).delete(delete_favorite),
@@ -340,6 +400,8 @@ async fn healthz(State(state): State<AppState>) -> Response {
 async fn internal_status(State(state): State<AppState>) -> Response {
     let postgres_ok = state.ping_postgres().await.is_ok();
     let metrics = state.status_snapshot().await;
+    let relay_stats = state.stream_relay.stats();
+    let scheduler = state.scheduler_status().await;
     let overall_ok = postgres_ok;
     let status = if overall_ok { "ok" } else { "error" };
     let body = json!({
@@ -355,7 +417,21 @@ async fn internal_status(State(state): State<AppState>) -> Response {
                 "totalBytes": metrics.memory_total_bytes,
             },
             "uptimeSeconds": metrics.uptime_seconds,
-        }
+            "nowPlayingWatchers": state.now_playing_hub.watcher_count(),
+            "streamRelays": {
+                "relays": relay_stats.relays,
+                "listeners": relay_stats.listeners,
+            },
+        },
+        "refreshScheduler": {
+            "enabled": state.config.refresh_interval_seconds > 0,
+            "intervalSeconds": state.config.refresh_interval_seconds,
+            "jitterSeconds": state.config.refresh_jitter_seconds,
+            "lastRunAt": scheduler.last_run_at.map(|at| at.to_rfc3339()),
+            "lastOutcome": scheduler.last_outcome,
+            "lastError": scheduler.last_error,
+            "nextRunAt": scheduler.next_run_at.map(|at| at.to_rfc3339()),
+        },
     });
 
     let code = if overall_ok {
@@ -408,13 +484,16 @@ struct StationsMeta {
     #[serde(rename = "requestedLimit")]
     requested_limit: Option<RequestedLimitMetaValue>,
     offset: usize,
-    #[serde(rename = "cacheSource")]
-    cache_source: String,
     origin: Option<String>,
     #[serde(rename = "updatedAt")]
     updated_at: String,
     countries: Vec<String>,
     genres: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<&'static str>,
+    facets: FacetCounts,
 }
 
 #[derive(Serialize)]
@@ -444,6 +523,12 @@ struct StationListItem {
     is_online: bool,
     #[serde(rename = "clickCount")]
     click_count: i32,
+    #[serde(rename = "distanceKm", skip_serializing_if = "Option::is_none")]
+    distance_km: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    similarity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    health: Option<HealthScore>,
 }
 
 #[derive(Serialize)]
@@ -456,67 +541,93 @@ struct FavoritesResponse {
 struct FavoritesMeta {
     #[serde(rename = "maxSlots")]
     max_slots: usize,
+    collection: FavoritesCollectionMeta,
+}
+
+#[derive(Serialize)]
+struct FavoritesCollectionMeta {
+    id: String,
+    name: String,
 }
 
 fn project_stations(
     payload: &StationsPayload,
     processed: &ProcessedStations,
+    health: &HealthScores,
     query: &NormalizedStationsQuery,
     max_limit: usize,
-    cache_source: &str,
 ) -> StationsListResponse {
     let total = payload.stations.len();
-    let mut candidate_lists: Vec<Vec<usize>> = Vec::new();
-    if let Some(country) = &query.country {
-        if let Some(indexes) = processed.indexes_for_country(country) {
-            candidate_lists.push(indexes.to_vec());
-        } else {
-            return empty_stations_response(payload, processed, query, max_limit, cache_source);
-        }
+    let mut candidate_lists = query.include.index_lists(processed);
+    if candidate_lists.iter().any(Vec::is_empty) {
+        return empty_stations_response(payload, processed, query, max_limit);
     }
-    if let Some(language) = &query.language {
-        if let Some(indexes) = processed.indexes_for_language(language) {
-            candidate_lists.push(indexes.to_vec());
-        } else {
-            return empty_stations_response(payload, processed, query, max_limit, cache_source);
-        }
+    if query.min_bitrate.is_some() || query.max_bitrate.is_some() {
+        candidate_lists.push(processed.indexes_for_bitrate(query.min_bitrate, query.max_bitrate));
     }
-    if let Some(tag) = &query.tag {
-        if let Some(indexes) = processed.indexes_for_tag(tag) {
-            candidate_lists.push(indexes.to_vec());
-        } else {
-            return empty_stations_response(payload, processed, query, max_limit, cache_source);
-        }
+    if let Some(hls) = query.hls {
+        candidate_lists.push(processed.indexes_for_hls(hls).to_vec());
     }
-    if let Some(genre) = &query.genre {
-        if let Some(indexes) = processed.indexes_for_tag(genre) {
-            candidate_lists.push(indexes.to_vec());
-        } else {
-            return empty_stations_response(payload, processed, query, max_limit, cache_source);
-        }
+    if let Some(is_online) = query.is_online {
+        candidate_lists.push(processed.indexes_for_online(is_online).to_vec());
+    }
+
+    let mut distances: HashMap<usize, f64> = HashMap::new();
+    if let Some(near) = &query.near {
+        let nearby = processed.nearby(near.lat, near.lon, near.radius_km);
+        candidate_lists.push(nearby.iter().map(|(idx, _)| *idx).collect());
+        distances.extend(nearby);
     }
 
     let mut indexes = intersect_lists(&candidate_lists, processed.station_count);
+    let excluded: HashSet<usize> = query
+        .exclude
+        .index_lists(processed)
+        .into_iter()
+        .flatten()
+        .collect();
+    if !excluded.is_empty() {
+        indexes.retain(|idx| !excluded.contains(idx));
+    }
+    if let Some(min_reliability) = query.min_reliability {
+        // Stations without any recorded checks have no score and do not qualify.
+        indexes.retain(|idx| {
+            payload
+                .stations
+                .get(*idx)
+                .and_then(|station| health.get(&station.id))
+                .is_some_and(|score| score.reliability >= min_reliability)
+        });
+    }
     if let Some(search) = &query.search {
         processed.search_matches(search, &mut indexes);
     }
-
-    let mut filtered_indexes = Vec::new();
-    for idx in indexes {
-        if let Some(station) = payload.stations.get(idx) {
-            if station_matches_filters(station, query) {
-                filtered_indexes.push(idx);
-            }
-        }
+    if let Some((sort, order)) = query.sort {
+        // An explicit sort wins over relevance and distance ordering.
+        processed.sort_indexes(sort, order, &mut indexes);
+    } else if query.near.is_some() {
+        // A location query reads nearest first, even when combined with search.
+        indexes.sort_by(|a, b| distances[a].total_cmp(&distances[b]).then(a.cmp(b)));
     }
 
+    // Every filter is resolved through the processed indexes above, so only guard against
+    // indexes that no longer exist in the payload.
+    let filtered_indexes: Vec<usize> = indexes.into_iter().filter(|idx| *idx < total).collect();
+
+    let facets = processed.facet_counts(&filtered_indexes);
     let total_matches = filtered_indexes.len();
     let start = query.offset.min(total_matches);
     let end = (start + query.limit).min(total_matches);
     let has_more = end < total_matches;
     let items = filtered_indexes[start..end]
         .iter()
-        .filter_map(|idx| payload.stations.get(*idx).map(project_station_for_client))
+        .filter_map(|idx| {
+            payload.stations.get(*idx).map(|station| StationListItem {
+                distance_km: distances.get(idx).map(|km| (km * 100.0).round() / 100.0),
+                health: health.get(&station.id),
+                ..project_station_for_client(station)
+            })
+        })
         .collect::<Vec<_>>();
 
     StationsListResponse {
@@ -533,11 +644,13 @@ fn project_stations(
                 .as_ref()
                 .map(RequestedLimitMetaValue::from),
             offset: query.offset,
-            cache_source: cache_source.to_string(),
             origin: payload.source.clone(),
             updated_at: payload.updated_at.to_rfc3339(),
             countries: processed.countries.clone(),
             genres: processed.genres.clone(),
+            sort: query.sort.map(|(sort, _)| sort.as_str()),
+            order: query.sort.map(|(_, order)| order.as_str()),
+            facets,
         },
         items,
     }
@@ -548,7 +661,6 @@ fn empty_stations_response(
     processed: &ProcessedStations,
     query: &NormalizedStationsQuery,
     max_limit: usize,
-    cache_source: &str,
 ) -> StationsListResponse {
     StationsListResponse {
         meta: StationsMeta {
@@ -564,73 +676,34 @@ fn empty_stations_response(
                 .as_ref()
                 .map(RequestedLimitMetaValue::from),
             offset: query.offset,
-            cache_source: cache_source.to_string(),
             origin: payload.source.clone(),
             updated_at: payload.updated_at.to_rfc3339(),
             countries: processed.countries.clone(),
             genres: processed.genres.clone(),
+            sort: query.sort.map(|(sort, _)| sort.as_str()),
+            order: query.sort.map(|(_, order)| order.as_str()),
+            facets: FacetCounts::default(),
         },
         items: Vec::new(),
     }
 }
 
-fn station_matches_filters(station: &Station, filters: &NormalizedStationsQuery) -> bool {
-    if let Some(country) = &filters.country {
-        if station
-            .country
-            .as_ref()
-            .map(|value| !value.eq_ignore_ascii_case(country))
-            .unwrap_or(true)
-            && station
-                .country_code
-                .as_ref()
-                .map(|value| !value.eq_ignore_ascii_case(country))
-                .unwrap_or(true)
-        {
-            return false;
-        }
-    }
-    if let Some(language) = &filters.language {
-        if !station
-            .languages
-            .iter()
-            .any(|value| value.eq_ignore_ascii_case(language))
-        {
-            return false;
-        }
-    }
-    if let Some(tag) = &filters.tag {
-        if !station
-            .tags
-            .iter()
-            .any(|value| value.eq_ignore_ascii_case(tag))
-        {
-            return false;
-        }
-    }
-    if let Some(genre) = &filters.genre {
-        if !station
-            .tags
-            .iter()
-            .any(|value| value.eq_ignore_ascii_case(genre))
-        {
-            return false;
-        }
-    }
-    // Search is already handled by ProcessedStations::search_matches; skip redundant work.
-    true
-}
-
 fn build_favorites_response(
     payload: &StationsPayload,
     processed: &ProcessedStations,
-    entries: Vec<FavoriteEntry>,
-) -> (FavoritesResponse, bool, Vec<FavoriteEntry>) {
+    collection: FavoriteCollection,
+) -> (FavoritesResponse, bool, FavoriteCollection) {
     let mut items = Vec::new();
     let mut persist = false;
     let mut next_entries = Vec::new();
-
-    for entry in entries.into_iter().take(MAX_FAVORITES) {
+    let FavoriteCollection {
+        id,
+        name,
+        max_slots,
+        entries,
+    } = collection;
+
+    for entry in entries.into_iter().take(max_slots) {
         if let Some(station) = get_station_by_id(payload, processed, &entry.id) {
             let projected = project_station(&station);
             let changed = entry.station.as_ref() != Some(&projected);
@@ -651,12 +724,21 @@ fn build_favorites_response(
     (
         FavoritesResponse {
             meta: FavoritesMeta {
-                max_slots: MAX_FAVORITES,
+                max_slots,
+                collection: FavoritesCollectionMeta {
+                    id: id.clone(),
+                    name: name.clone(),
+                },
             },
             items,
         },
         persist,
-        next_entries,
+        FavoriteCollection {
+            id,
+            name,
+            max_slots,
+            entries: next_entries,
+        },
     )
 }
 
@@ -677,6 +759,9 @@ fn project_station_for_client(station: &Station) -> StationListItem {
         hls: station.hls,
         is_online: station.is_online,
         click_count: station.click_count,
+        distance_km: None,
+        similarity: None,
+        health: None,
     }
 }
 
@@ -747,6 +832,22 @@ async fn enforce_rate_limit(
     }
 }
 
+async fn enforce_transfer_rate_limit(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    let key = resolve_client_key(headers);
+    let decision = state.check_transfer_rate_limit(&key).await;
+    if decision.allowed {
+        Ok(())
+    } else {
+        Err(ApiError::TooManyRequests {
+            message: "Too many favorites transfer attempts. Please try again later.",
+            info: decision.metadata,
+        })
+    }
+}
+
 fn resolve_client_key(headers: &HeaderMap) -> String {
     if let Some(value) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
         if let Some(first) = value.split(',').next() {
@@ -873,6 +974,22 @@ fn forward_stream_response(response: reqwest::Response) -> Response {
         })
 }
 
+fn relay_stream_response(listener: RelayListener) -> Response {
+    let mut builder = Response::builder().status(StatusCode::OK);
+    for (key, value) in listener.headers.iter() {
+        builder = builder.header(key, value.clone());
+    }
+    builder
+        .header("Cache-Control", "no-store")
+        .body(Body::from_stream(listener.into_stream()))
+        .unwrap_or_else(|err| {
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(err.to_string()))
+                .unwrap()
+        })
+}
+
 fn pick_forward_headers(headers: &HeaderMap, names: &[&str]) -> ReqwestHeaderMap {
     let mut map = ReqwestHeaderMap::new();
     for &name in names {
@@ -885,17 +1002,13 @@ fn pick_forward_headers(headers: &HeaderMap, names: &[&str]) -> ReqwestHeaderMap
     map
 }
 
+/// HLS playlists, plus the PLS, M3U and ASX wrappers that must be read before streaming.
 fn should_treat_as_playlist(url: &str, content_type: &str) -> bool {
     let lowered = content_type.to_lowercase();
-    if lowered.contains("mpegurl") || lowered.contains("scpls") {
+    if lowered.contains("mpegurl") || StreamPlaylist::detect(url, content_type).is_some() {
         return true;
     }
-    if let Ok(parsed) = Url::parse(url) {
-        return parsed.path().ends_with(".m3u8")
-            || parsed.path().ends_with(".m3u")
-            || parsed.path().ends_with(".pls");
-    }
-    false
+    is_hls_url(url)
 }
 
 fn rewrite_playlist(
@@ -913,13 +1026,9 @@ fn rewrite_playlist(
                 line.to_string()
             } else if let Ok(base_url) = &base {
                 if let Ok(resolved) = base_url.join(trimmed) {
-                    let mut upgrade = resolved.clone();
-                    if upgrade.scheme() == "http" {
-                        let _ = upgrade.set_scheme("https");
-                    }
-                    if upgrade.scheme() != "https" {
+                    let Some(upgrade) = upgrade_playlist_url(resolved) else {
                         return "# dropped http stream".to_string();
-                    }
+                    };
                     let mut proxied = segment_path.to_string();
                     if !proxied.ends_with("?") && !proxied.ends_with("&") {
                         proxied.push_str(if proxied.contains('?') { "&" } else { "?" });
@@ -946,46 +1055,6 @@ fn rewrite_playlist(
         .join("\n")
 }
 
-fn is_segment_origin_allowed(stream_url: &str, target: &Url) -> bool {
-    let Ok(stream_origin) = Url::parse(stream_url) else {
-        return false;
-    };
-    if stream_origin.origin().ascii_serialization() == target.origin().ascii_serialization() {
-        return true;
-    }
-    let Some(stream_host) = stream_origin.host_str() else {
-        return false;
-    };
-    let Some(target_host) = target.host_str() else {
-        return false;
-    };
-    if stream_host == target_host {
-        return true;
-    }
-    if let Some(parent) = stream_host.split_once('.').map(|(_, suffix)| suffix) {
-        if target_host == parent || target_host.ends_with(&format!(".{parent}")) {
-            return true;
-        }
-    }
-    match (base_domain(stream_host), base_domain(target_host)) {
-        (Some(stream_base), Some(target_base)) => stream_base == target_base,
-        _ => false,
-    }
-}
-
-fn base_domain(host: &str) -> Option<String> {
-    let parts: Vec<&str> = host.split('.').filter(|part| !part.is_empty()).collect();
-    if parts.len() < 2 {
-        return None;
-    }
-    let base = parts[parts.len() - 2..].join(".");
-    if base.is_empty() {
-        None
-    } else {
-        Some(base)
-    }
-}
-
 fn parse_bool(value: Option<String>) -> bool {
     matches!(
         value
@@ -1033,56 +1102,294 @@ async fn get_stations(
     let processed = state
         .ensure_processed(&processed_key, &load.payload.stations)
         .await;
+
+    let health = state.health_scores().await;
+
+    let query_key = normalized_query.cache_key();
+    let random_seed = processed.random_seed().to_string();
+    let mut etag_parts = vec![
+        processed_key.as_str(),
+        query_key.as_str(),
+        health.version.as_str(),
+    ];
+    if matches!(normalized_query.sort, Some((StationSort::Random, _))) {
+        etag_parts.push(&random_seed);
+    }
+    let last_modified = health.updated_at.map_or(load.payload.updated_at, |health| {
+        health.max(load.payload.updated_at)
+    });
+    let validators = Validators::new(&etag_parts, Some(last_modified));
+    if validators.matches(&headers) {
+        let mut reply = validators.not_modified();
+        reply.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(STATIONS_CACHE_CONTROL),
+        );
+        apply_rate_limit_headers(reply.headers_mut(), &rate);
+        return Ok(reply);
+    }
+
     let response = project_stations(
         &load.payload,
         &processed,
+        &health,
         &normalized_query,
         state.config.api.max_page_size,
-        &load.cache_source,
     );
     let mut reply = Json(response).into_response();
     reply.headers_mut().insert(
         header::CACHE_CONTROL,
-        HeaderValue::from_static("public, max-age=30, stale-while-revalidate=120"),
+        HeaderValue::from_static(STATIONS_CACHE_CONTROL),
     );
+    // A header rather than body metadata: memory and database hits share the strong ETag, so
+    // their bodies must be byte-identical.
+    if let Ok(value) = HeaderValue::from_str(&load.cache_source) {
+        reply
+            .headers_mut()
+            .insert(header::HeaderName::from_static("x-cache-source"), value);
+    }
+    validators.apply(reply.headers_mut());
     apply_rate_limit_headers(reply.headers_mut(), &rate);
     Ok(reply)
 }
 
-async fn get_favorites(State(state): State<AppState>, headers: HeaderMap) -> ApiResponse {
+const DEFAULT_SIMILAR_LIMIT: usize = 12;
+const MAX_SIMILAR_LIMIT: usize = 50;
+
+#[derive(Deserialize, Default)]
+struct SimilarStationsQuery {
+    limit: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SimilarStationsResponse {
+    meta: SimilarStationsMeta,
+    items: Vec<StationListItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimilarStationsMeta {
+    station_id: String,
+    limit: usize,
+    updated_at: String,
+}
+
+async fn similar_stations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(station_id): Path<String>,
+    Query(query): Query<SimilarStationsQuery>,
+) -> ApiResponse {
+    let station_id = station_id.trim();
+    if station_id.is_empty() {
+        return Err(ApiError::BadRequest("Station identifier is required."));
+    }
     let rate = enforce_rate_limit(&state, &headers).await?;
-    let session = extract_session_token(&headers)?;
-    let favorites_session = extract_favorites_session(&headers);
-    let key = build_favorites_key(&session, favorites_session.as_deref());
+    let mut errors = Vec::new();
+    let limit = parse_integer(query.limit, MAX_LIMIT_DIGITS, "limit", &mut errors)
+        .unwrap_or(DEFAULT_SIMILAR_LIMIT);
+    if limit == 0 {
+        errors.push("limit must be a whole number".into());
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::BadRequestWithDetails {
+            message: INVALID_QUERY_ERROR,
+            details: errors,
+        });
+    }
+    let limit = limit.min(MAX_SIMILAR_LIMIT);
 
-    let mut load = state
-        .load_stations(false)
+    let (payload, processed) = load_catalogue(&state).await?;
+    let idx = processed
+        .station_index(station_id)
+        .filter(|idx| *idx < payload.stations.len())
+        .ok_or(ApiError::NotFound("Station not found"))?;
+    let health = state.health_scores().await;
+
+    let processed_key = payload.processed_cache_key().map_err(ApiError::internal)?;
+    let limit_key = limit.to_string();
+    let validators = Validators::new(
+        &[
+            processed_key.as_str(),
+            "similar",
+            station_id,
+            limit_key.as_str(),
+            health.version.as_str(),
+        ],
+        Some(payload.updated_at),
+    );
+    if validators.matches(&headers) {
+        let mut reply = validators.not_modified();
+        reply.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(STATIONS_CACHE_CONTROL),
+        );
+        apply_rate_limit_headers(reply.headers_mut(), &rate);
+        return Ok(reply);
+    }
+
+    let items = processed
+        .similar(idx, limit)
+        .into_iter()
+        .filter_map(|(other, score)| {
+            payload.stations.get(other).map(|station| StationListItem {
+                similarity: Some((f64::from(score) * 1000.0).round() / 1000.0),
+                health: health.get(&station.id),
+                ..project_station_for_client(station)
+            })
+        })
+        .collect();
+
+    let mut reply = Json(SimilarStationsResponse {
+        meta: SimilarStationsMeta {
+            station_id: payload.stations[idx].id.clone(),
+            limit,
+            updated_at: payload.updated_at.to_rfc3339(),
+        },
+        items,
+    })
+    .into_response();
+    reply.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(STATIONS_CACHE_CONTROL),
+    );
+    validators.apply(reply.headers_mut());
+    apply_rate_limit_headers(reply.headers_mut(), &rate);
+    Ok(reply)
+}
+
+#[derive(Debug, Deserialize)]
+struct StationChangesParams {
+    since: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StationChangesResponse {
+    meta: StationChangesMeta,
+    upserted: Vec<StationListItem>,
+    removed: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct StationChangesMeta {
+    since: String,
+    fingerprint: Option<String>,
+    #[serde(rename = "changeSets")]
+    change_sets: usize,
+}
+
+async fn get_station_changes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<StationChangesParams>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let since = params
+        .since
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or(ApiError::BadRequest("since is required."))?;
+    let cursor = parse_change_cursor(since).ok_or(ApiError::BadRequest(
+        "since must be a payload fingerprint, an RFC 3339 timestamp or epoch milliseconds.",
+    ))?;
+
+    let mut changes = state
+        .stations
+        .load_changes_since(&cursor)
         .await
-        .map_err(ApiError::internal)?;
-    load.payload
-        .ensure_fingerprint()
-        .map_err(ApiError::internal)?;
-    let processed_key = load
-        .payload
-        .processed_cache_key()
-        .map_err(ApiError::internal)?;
-    let processed = state
-        .ensure_processed(&processed_key, &load.payload.stations)
-        .await;
-    let payload = load.payload;
+        .map_err(|err| ApiError::internal(err.into()))?
+        .ok_or(ApiError::Gone(
+            "Change history does not reach back that far; reload /stations.",
+        ))?;
+    let overrides = state
+        .stations
+        .load_overrides()
+        .await
+        .map_err(|err| ApiError::internal(err.into()))?;
+    apply_change_overrides(&mut changes, &overrides);
+
+    let response = StationChangesResponse {
+        meta: StationChangesMeta {
+            since: since.to_string(),
+            fingerprint: changes.fingerprint,
+            change_sets: changes.change_sets,
+        },
+        upserted: changes
+            .upserted
+            .iter()
+            .map(project_station_for_client)
+            .collect(),
+        removed: changes.removed,
+    };
+    let mut reply = Json(response).into_response();
+    reply
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    apply_rate_limit_headers(reply.headers_mut(), &rate);
+    Ok(reply)
+}
+
+/// `since` is an RFC 3339 timestamp, epoch milliseconds, or the fingerprint of the payload
+/// the client last loaded.
+fn parse_change_cursor(value: &str) -> Option<ChangeCursor> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(ChangeCursor::Timestamp(time.with_timezone(&Utc)));
+    }
+    if value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return value
+            .parse::<i64>()
+            .ok()
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(ChangeCursor::Timestamp);
+    }
+    if value.len() <= MAX_FINGERPRINT_LENGTH
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+    {
+        return Some(ChangeCursor::Fingerprint(value.to_string()));
+    }
+    None
+}
+
+async fn get_favorites(State(state): State<AppState>, headers: HeaderMap) -> ApiResponse {
+    respond_with_collection(&state, &headers, DEFAULT_COLLECTION_ID).await
+}
+
+async fn get_favorite_collection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(collection_id): Path<String>,
+) -> ApiResponse {
+    let collection_id = parse_collection_id(&collection_id)?;
+    respond_with_collection(&state, &headers, &collection_id).await
+}
+
+async fn respond_with_collection(
+    state: &AppState,
+    headers: &HeaderMap,
+    collection_id: &str,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(state, headers).await?;
+    let key = favorites_key(headers)?;
+    let (payload, processed) = load_catalogue(state).await?;
 
-    let favorites = state
+    let mut collections = state
         .favorites
         .read(&key)
         .await
         .map_err(ApiError::internal)?;
-    let (response, persist, updated_entries) =
-        build_favorites_response(&payload, &processed, favorites);
+    let index = collection_index(&collections, collection_id)?;
+    let (response, persist, updated) =
+        build_favorites_response(&payload, &processed, collections[index].clone());
 
     if persist {
+        collections[index] = updated;
         state
             .favorites
-            .write(&key, &updated_entries)
+            .write(&key, &collections)
             .await
             .map_err(ApiError::internal)?;
     } else {
@@ -1093,7 +1400,24 @@ async fn get_favorites(State(state): State<AppState>, headers: HeaderMap) -> Api
             .map_err(ApiError::internal)?;
     }
 
-    let mut resp = Json(response).into_response();
+    // Favorites are per session, so the ETag is taken over the rendered body itself.
+    let body = serde_json::to_vec(&response).map_err(|err| ApiError::internal(err.into()))?;
+    let validators = Validators::for_body(&body);
+    let mut resp = if validators.matches(headers) {
+        validators.not_modified()
+    } else {
+        let mut resp = Response::new(Body::from(body));
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        validators.apply(resp.headers_mut());
+        resp
+    };
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-cache"),
+    );
     apply_rate_limit_headers(resp.headers_mut(), &rate);
     Ok(resp)
 }
@@ -1118,51 +1442,58 @@ async fn upsert_favorite(
     Path(station_id): Path<String>,
     body: Option<Json<UpsertFavoriteBody>>,
 ) -> ApiResponse {
-    let rate = enforce_rate_limit(&state, &headers).await?;
     let body = body.map(|Json(payload)| payload).unwrap_or_default();
-    let session = extract_session_token(&headers)?;
-    let favorites_session = extract_favorites_session(&headers);
-    let key = build_favorites_key(&session, favorites_session.as_deref());
+    save_favorite(&state, &headers, DEFAULT_COLLECTION_ID, &station_id, body).await
+}
+
+async fn upsert_collection_favorite(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((collection_id, station_id)): Path<(String, String)>,
+    body: Option<Json<UpsertFavoriteBody>>,
+) -> ApiResponse {
+    let collection_id = parse_collection_id(&collection_id)?;
+    let body = body.map(|Json(payload)| payload).unwrap_or_default();
+    save_favorite(&state, &headers, &collection_id, &station_id, body).await
+}
+
+async fn save_favorite(
+    state: &AppState,
+    headers: &HeaderMap,
+    collection_id: &str,
+    station_id: &str,
+    body: UpsertFavoriteBody,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(state, headers).await?;
+    let key = favorites_key(headers)?;
 
-    let sanitized_station_id = sanitize_station_id(&station_id)
+    let sanitized_station_id = sanitize_station_id(station_id)
         .ok_or(ApiError::BadRequest("Invalid station identifier"))?;
 
+    let (payload, processed) = load_catalogue(state).await?;
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let index = collection_index(&collections, collection_id)?;
+    let mut collection = collections[index].clone();
+
     if let Some(slot) = body.slot {
-        if slot >= MAX_FAVORITES {
+        if slot >= collection.max_slots {
             return Err(ApiError::BadRequest("Invalid slot index"));
         }
     }
 
-    let mut load = state
-        .load_stations(false)
-        .await
-        .map_err(ApiError::internal)?;
-    load.payload
-        .ensure_fingerprint()
-        .map_err(ApiError::internal)?;
-    let processed_key = load
-        .payload
-        .processed_cache_key()
-        .map_err(ApiError::internal)?;
-    let processed = state
-        .ensure_processed(&processed_key, &load.payload.stations)
-        .await;
-    let payload = load.payload;
-
     let station = get_station_by_id(&payload, &processed, &sanitized_station_id)
         .ok_or(ApiError::NotFound("Station not found"))?;
 
-    let mut favorites = state
-        .favorites
-        .read(&key)
-        .await
-        .map_err(ApiError::internal)?;
-
-    if let Some(index) = favorites
+    if let Some(index) = collection
+        .entries
         .iter()
         .position(|entry| entry.id == sanitized_station_id)
     {
-        favorites.remove(index);
+        collection.entries.remove(index);
     }
 
     let projected = project_station(&station);
@@ -1172,28 +1503,33 @@ async fn upsert_favorite(
         station: Some(projected),
     };
 
+    let favorites = &mut collection.entries;
     if let Some(slot) = body.slot {
         if slot < favorites.len() {
             favorites.insert(slot, new_entry);
         } else {
-            if favorites.len() >= MAX_FAVORITES {
+            if favorites.len() >= collection.max_slots {
                 return Err(ApiError::Conflict("All favorite slots are already filled"));
             }
             favorites.push(new_entry);
         }
     } else {
-        if favorites.len() >= MAX_FAVORITES {
+        if favorites.len() >= collection.max_slots {
             return Err(ApiError::Conflict("All favorite slots are already filled"));
         }
         favorites.push(new_entry);
     }
 
-    let favorites = dedupe_entries(favorites);
-    let (response, _, updated_entries) = build_favorites_response(&payload, &processed, favorites);
+    collection.entries = dedupe_entries(
+        std::mem::take(&mut collection.entries),
+        collection.max_slots,
+    );
+    let (response, _, updated) = build_favorites_response(&payload, &processed, collection);
+    collections[index] = updated;
 
     state
         .favorites
-        .write(&key, &updated_entries)
+        .write(&key, &collections)
         .await
         .map_err(ApiError::internal)?;
 
@@ -1207,14 +1543,445 @@ async fn delete_favorite(
     headers: HeaderMap,
     Path(station_id): Path<String>,
 ) -> ApiResponse {
-    let rate = enforce_rate_limit(&state, &headers).await?;
-    let session = extract_session_token(&headers)?;
-    let favorites_session = extract_favorites_session(&headers);
-    let key = build_favorites_key(&session, favorites_session.as_deref());
+    remove_favorite(&state, &headers, DEFAULT_COLLECTION_ID, &station_id).await
+}
 
-    let sanitized_station_id = sanitize_station_id(&station_id)
+async fn delete_collection_favorite(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((collection_id, station_id)): Path<(String, String)>,
+) -> ApiResponse {
+    let collection_id = parse_collection_id(&collection_id)?;
+    remove_favorite(&state, &headers, &collection_id, &station_id).await
+}
+
+async fn remove_favorite(
+    state: &AppState,
+    headers: &HeaderMap,
+    collection_id: &str,
+    station_id: &str,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(state, headers).await?;
+    let key = favorites_key(headers)?;
+
+    let sanitized_station_id = sanitize_station_id(station_id)
         .ok_or(ApiError::BadRequest("Invalid station identifier"))?;
 
+    let (payload, processed) = load_catalogue(state).await?;
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let index = collection_index(&collections, collection_id)?;
+    let mut collection = collections[index].clone();
+    let removed = collection
+        .entries
+        .iter()
+        .any(|entry| entry.id == sanitized_station_id);
+    collection
+        .entries
+        .retain(|entry| entry.id != sanitized_station_id);
+
+    let (response, persist, updated) = build_favorites_response(&payload, &processed, collection);
+
+    if persist || removed {
+        collections[index] = updated;
+        state
+            .favorites
+            .write(&key, &collections)
+            .await
+            .map_err(ApiError::internal)?;
+    } else {
+        state
+            .favorites
+            .refresh_ttl(&key)
+            .await
+            .map_err(ApiError::internal)?;
+    }
+
+    let mut resp = Json(response).into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+#[derive(Serialize)]
+struct FavoriteCollectionsResponse {
+    meta: FavoriteCollectionsMeta,
+    items: Vec<FavoriteCollectionSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transfer: Option<TransferResult>,
+}
+
+#[derive(Serialize)]
+struct TransferResult {
+    mode: TransferMode,
+    #[serde(flatten)]
+    summary: TransferSummary,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FavoriteCollectionsMeta {
+    max_collections: usize,
+    max_collection_slots: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FavoriteCollectionSummary {
+    id: String,
+    name: String,
+    max_slots: usize,
+    count: usize,
+}
+
+impl From<&FavoriteCollection> for FavoriteCollectionSummary {
+    fn from(collection: &FavoriteCollection) -> Self {
+        Self {
+            id: collection.id.clone(),
+            name: collection.name.clone(),
+            max_slots: collection.max_slots,
+            count: collection.entries.len(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CreateCollectionBody {
+    name: String,
+    max_slots: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct UpdateCollectionBody {
+    name: Option<String>,
+    max_slots: Option<usize>,
+    /// The collection's station ids in their new order.
+    order: Option<Vec<String>>,
+}
+
+async fn list_favorite_collections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    state
+        .favorites
+        .refresh_ttl(&key)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let limits = state.favorites.limits();
+    let mut resp = Json(FavoriteCollectionsResponse {
+        meta: FavoriteCollectionsMeta {
+            max_collections: limits.max_collections,
+            max_collection_slots: limits.max_collection_slots,
+        },
+        items: collections.iter().map(Into::into).collect(),
+        transfer: None,
+    })
+    .into_response();
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-cache"),
+    );
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn create_favorite_collection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Option<Json<CreateCollectionBody>>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let Some(Json(body)) = body else {
+        return Err(ApiError::BadRequest("A collection name is required."));
+    };
+    let name = sanitize_collection_name(&body.name)
+        .ok_or(ApiError::BadRequest("Invalid collection name."))?;
+    let limits = state.favorites.limits();
+    let max_slots = parse_collection_slots(
+        body.max_slots
+            .unwrap_or(MAX_FAVORITES.min(limits.max_collection_slots)),
+        limits.max_collection_slots,
+    )?;
+
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    if collections.len() >= limits.max_collections {
+        return Err(ApiError::Conflict(
+            "All favorite collections are already in use",
+        ));
+    }
+    let collection =
+        FavoriteCollection::new(new_collection_id(&name, &collections), name, max_slots);
+    let summary = FavoriteCollectionSummary::from(&collection);
+    collections.push(collection);
+    state
+        .favorites
+        .write(&key, &collections)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let mut resp = (StatusCode::CREATED, Json(summary)).into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn update_favorite_collection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(collection_id): Path<String>,
+    body: Option<Json<UpdateCollectionBody>>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let collection_id = parse_collection_id(&collection_id)?;
+    let Some(Json(body)) = body else {
+        return Err(ApiError::BadRequest("A collection update is required."));
+    };
+
+    let (payload, processed) = load_catalogue(&state).await?;
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let index = collection_index(&collections, &collection_id)?;
+    let mut collection = collections[index].clone();
+
+    if let Some(name) = body.name {
+        collection.name = sanitize_collection_name(&name)
+            .ok_or(ApiError::BadRequest("Invalid collection name."))?;
+    }
+    if let Some(max_slots) = body.max_slots {
+        let max_slots =
+            parse_collection_slots(max_slots, state.favorites.limits().max_collection_slots)?;
+        if max_slots < collection.entries.len() {
+            return Err(ApiError::Conflict(
+                "Remove stations from the collection before shrinking it",
+            ));
+        }
+        collection.max_slots = max_slots;
+    }
+    if let Some(order) = body.order {
+        let current: HashSet<&str> = collection
+            .entries
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect();
+        let requested: HashSet<&str> = order.iter().map(String::as_str).collect();
+        if order.len() != collection.entries.len() || requested != current {
+            return Err(ApiError::BadRequest(
+                "The order must list every station in the collection exactly once.",
+            ));
+        }
+        let mut entries = std::mem::take(&mut collection.entries);
+        for station_id in &order {
+            if let Some(position) = entries.iter().position(|entry| &entry.id == station_id) {
+                collection.entries.push(entries.swap_remove(position));
+            }
+        }
+    }
+
+    let (response, _, updated) = build_favorites_response(&payload, &processed, collection);
+    collections[index] = updated;
+    state
+        .favorites
+        .write(&key, &collections)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let mut resp = Json(response).into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn delete_favorite_collection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(collection_id): Path<String>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let collection_id = parse_collection_id(&collection_id)?;
+    if collection_id == DEFAULT_COLLECTION_ID {
+        return Err(ApiError::BadRequest(
+            "The default collection cannot be deleted.",
+        ));
+    }
+
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let index = collection_index(&collections, &collection_id)?;
+    collections.remove(index);
+    state
+        .favorites
+        .write(&key, &collections)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let mut resp = StatusCode::NO_CONTENT.into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RedeemTransferBody {
+    code: String,
+    #[serde(default)]
+    mode: TransferMode,
+}
+
+async fn create_favorites_transfer(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    enforce_transfer_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let transfer = state
+        .favorites
+        .create_transfer(&key)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or(ApiError::NotFound("No favorites to transfer"))?;
+
+    let mut resp = (StatusCode::CREATED, Json(transfer)).into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn redeem_favorites_transfer(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Option<Json<RedeemTransferBody>>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    enforce_transfer_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let Some(Json(body)) = body else {
+        return Err(ApiError::BadRequest("A transfer code is required."));
+    };
+    let code =
+        sanitize_transfer_code(&body.code).ok_or(ApiError::BadRequest("Invalid transfer code."))?;
+    let source_key = match state
+        .favorites
+        .redeem_transfer(&code, &key)
+        .await
+        .map_err(ApiError::internal)?
+    {
+        TransferRedemption::Redeemed(source_key) => source_key,
+        TransferRedemption::OwnCode => {
+            return Err(ApiError::BadRequest(
+                "A transfer code cannot be redeemed by the session that created it.",
+            ));
+        }
+        TransferRedemption::Missing => {
+            return Err(ApiError::NotFound("Transfer code not found or expired"));
+        }
+    };
+
+    let source = state
+        .favorites
+        .read(&source_key)
+        .await
+        .map_err(ApiError::internal)?;
+    state
+        .favorites
+        .refresh_ttl(&source_key)
+        .await
+        .map_err(ApiError::internal)?;
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let limits = state.favorites.limits();
+    let summary = apply_transfer(&mut collections, source, body.mode, limits);
+    state
+        .favorites
+        .write(&key, &collections)
+        .await
+        .map_err(ApiError::internal)?;
+    logger().info(
+        "favorites.transfer_redeemed",
+        json!({
+            "mode": body.mode,
+            "added": summary.added,
+            "skipped": summary.skipped,
+        }),
+    );
+
+    let mut resp = Json(FavoriteCollectionsResponse {
+        meta: FavoriteCollectionsMeta {
+            max_collections: limits.max_collections,
+            max_collection_slots: limits.max_collection_slots,
+        },
+        items: collections.iter().map(Into::into).collect(),
+        transfer: Some(TransferResult {
+            mode: body.mode,
+            summary,
+        }),
+    })
+    .into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+fn favorites_key(headers: &HeaderMap) -> Result<String, ApiError> {
+    let session = extract_session_token(headers)?;
+    let favorites_session = extract_favorites_session(headers);
+    Ok(build_favorites_key(&session, favorites_session.as_deref()))
+}
+
+fn parse_collection_id(value: &str) -> Result<String, ApiError> {
+    sanitize_collection_id(value).ok_or(ApiError::BadRequest("Invalid collection identifier"))
+}
+
+fn parse_collection_slots(value: usize, max: usize) -> Result<usize, ApiError> {
+    if value == 0 || value > max {
+        return Err(ApiError::BadRequest("Invalid collection size."));
+    }
+    Ok(value)
+}
+
+fn collection_index(
+    collections: &[FavoriteCollection],
+    collection_id: &str,
+) -> Result<usize, ApiError> {
+    collections
+        .iter()
+        .position(|collection| collection.id == collection_id)
+        .ok_or(ApiError::NotFound("Collection not found"))
+}
+
+/// The current catalogue and its indexes, for handlers that resolve favorites.
+async fn load_catalogue(
+    state: &AppState,
+) -> Result<(StationsPayload, ProcessedStations), ApiError> {
     let mut load = state
         .load_stations(false)
         .await
@@ -1229,42 +1996,231 @@ async fn delete_favorite(
     let processed = state
         .ensure_processed(&processed_key, &load.payload.stations)
         .await;
-    let payload = load.payload;
+    Ok((load.payload, processed))
+}
 
-    let favorites = state
+const MAX_IMPORT_ENTRIES: usize = 500;
+
+#[derive(Deserialize, Default)]
+struct FavoritesFormatQuery {
+    format: Option<String>,
+    /// Collection to export from or import into; the default collection when absent.
+    collection: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FavoritesImportResponse {
+    #[serde(flatten)]
+    favorites: FavoritesResponse,
+    import: FavoritesImportSummary,
+}
+
+#[derive(Serialize)]
+struct FavoritesImportSummary {
+    format: &'static str,
+    entries: usize,
+    added: Vec<String>,
+    /// Matched stations that were already favorites or found no free slot.
+    skipped: Vec<String>,
+    unmatched: Vec<UnmatchedImportEntry>,
+}
+
+#[derive(Serialize)]
+struct UnmatchedImportEntry {
+    title: Option<String>,
+    url: String,
+    reason: UnmatchedReason,
+}
+
+async fn export_favorites(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<FavoritesFormatQuery>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let format = match query.format.as_deref() {
+        None => PlaylistFormat::M3u,
+        Some(raw) => PlaylistFormat::parse(raw)
+            .ok_or(ApiError::BadRequest("Unsupported playlist format."))?,
+    };
+    let collection_id = match query.collection.as_deref() {
+        None => DEFAULT_COLLECTION_ID.to_string(),
+        Some(raw) => parse_collection_id(raw)?,
+    };
+
+    let (payload, processed) = load_catalogue(&state).await?;
+    let mut collections = state
         .favorites
         .read(&key)
         .await
         .map_err(ApiError::internal)?;
-    let removed = favorites
+    let index = collection_index(&collections, &collection_id)?;
+    let (response, _, _) =
+        build_favorites_response(&payload, &processed, collections.swap_remove(index));
+
+    let base = public_base_url(&headers, &state.config.api.public_base_url);
+    let entries: Vec<PlaylistEntry> = response
+        .items
         .iter()
-        .any(|entry| entry.id == sanitized_station_id);
-    let next: Vec<FavoriteEntry> = favorites
-        .into_iter()
-        .filter(|entry| entry.id != sanitized_station_id)
+        .map(|station| {
+            let mut attributes = vec![("tvg-id".to_string(), station.id.clone())];
+            if let Some(favicon) = &station.favicon {
+                attributes.push(("tvg-logo".to_string(), favicon.clone()));
+            }
+            PlaylistEntry {
+                url: format!(
+                    "{base}/stations/{}/stream",
+                    urlencoding::encode(&station.id)
+                ),
+                title: Some(station.name.clone()),
+                attributes,
+            }
+        })
         .collect();
 
-    let (response, persist, updated_entries) = build_favorites_response(&payload, &processed, next);
+    let mut resp = Response::new(Body::from(format.render(&entries)));
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"favorites.{}\"",
+        format.extension()
+    )) {
+        resp.headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
 
-    if persist || removed {
-        state
-            .favorites
-            .write(&key, &updated_entries)
-            .await
-            .map_err(ApiError::internal)?;
-    } else {
+async fn import_favorites(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<FavoritesFormatQuery>,
+    body: String,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let collection_id = match query.collection.as_deref() {
+        None => DEFAULT_COLLECTION_ID.to_string(),
+        Some(raw) => parse_collection_id(raw)?,
+    };
+    if body.trim().is_empty() {
+        return Err(ApiError::BadRequest("A playlist body is required."));
+    }
+    let format = match query.format.as_deref() {
+        None => PlaylistFormat::detect(&body),
+        Some(raw) => PlaylistFormat::parse(raw)
+            .ok_or(ApiError::BadRequest("Unsupported playlist format."))?,
+    };
+    let entries = format.parse_entries(&body);
+    if entries.is_empty() {
+        return Err(ApiError::BadRequest("The playlist has no entries."));
+    }
+    if entries.len() > MAX_IMPORT_ENTRIES {
+        return Err(ApiError::BadRequest("The playlist has too many entries."));
+    }
+
+    let (payload, processed) = load_catalogue(&state).await?;
+    let matched = match_playlist_entries(&entries, &payload.stations);
+    let mut collections = state
+        .favorites
+        .read(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let index = collection_index(&collections, &collection_id)?;
+    let mut collection = collections[index].clone();
+    let max_slots = collection.max_slots;
+    let favorites = &mut collection.entries;
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+    for station_id in matched.station_ids {
+        if favorites.len() >= max_slots || favorites.iter().any(|entry| entry.id == station_id) {
+            skipped.push(station_id);
+            continue;
+        }
+        let Some(station) = get_station_by_id(&payload, &processed, &station_id) else {
+            skipped.push(station_id);
+            continue;
+        };
+        favorites.push(FavoriteEntry {
+            id: station_id.clone(),
+            saved_at: current_timestamp(),
+            station: Some(project_station(&station)),
+        });
+        added.push(station_id);
+    }
+
+    let (response, persist, updated) = build_favorites_response(&payload, &processed, collection);
+    if persist || !added.is_empty() {
+        collections[index] = updated;
         state
             .favorites
-            .refresh_ttl(&key)
+            .write(&key, &collections)
             .await
             .map_err(ApiError::internal)?;
     }
 
-    let mut resp = Json(response).into_response();
+    let mut resp = Json(FavoritesImportResponse {
+        favorites: response,
+        import: FavoritesImportSummary {
+            format: format.extension(),
+            entries: entries.len(),
+            added,
+            skipped,
+            unmatched: matched
+                .unmatched
+                .into_iter()
+                .map(|(entry, reason)| UnmatchedImportEntry {
+                    title: entry.title,
+                    url: entry.url,
+                    reason,
+                })
+                .collect(),
+        },
+    })
+    .into_response();
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
     apply_rate_limit_headers(resp.headers_mut(), &rate);
     Ok(resp)
 }
 
+/// Completes a path-only public base with the gateway's forwarded host, so exported playlists
+/// work outside the browser. Without a trustworthy host the path is kept as is.
+fn public_base_url(headers: &HeaderMap, configured: &str) -> String {
+    if !configured.starts_with('/') {
+        return configured.to_string();
+    }
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let Some(host) = header("x-forwarded-host").filter(|host| {
+        host.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+    }) else {
+        return configured.to_string();
+    };
+    let scheme = match header("x-forwarded-proto") {
+        Some(proto) if proto.eq_ignore_ascii_case("http") => "http",
+        _ => "https",
+    };
+    format!("{scheme}://{host}{configured}")
+}
+
 async fn stream_station(
     State(state): State<AppState>,
     Path(station_id): Path<String>,
@@ -1277,20 +2233,41 @@ async fn stream_station(
     }
     let rate = enforce_rate_limit(&state, &headers).await?;
 
-    let station = load_station(&state, station_id).await?;
-    let request = state
-        .http_client
-        .get(&station.stream_url)
-        .headers(pick_forward_headers(&headers, &["user-agent", "accept"]));
-
-    let response = timeout(
-        Duration::from_millis(state.config.stream_proxy.timeout_ms),
-        request.send(),
-    )
-    .await
-    .map_err(|_| ApiError::ServiceUnavailable("Stream request timed out"))?
-    .map_err(|_| ApiError::ServiceUnavailable("Failed to reach stream URL."))?;
+    let mut station = load_station(&state, station_id).await?;
+    // Validation stores resolved URLs, but catalogues loaded without it can still hold
+    // PLS, M3U or ASX wrappers that browsers cannot play.
+    if StreamPlaylist::detect(&station.stream_url, "").is_some() {
+        station = state
+            .stream_validator
+            .resolve_station(station, &state.postgres)
+            .await
+            .map_err(|_| ApiError::ServiceUnavailable("Failed to resolve station playlist."))?;
+    }
+    if state.stream_relay.enabled()
+        && !station.hls
+        && !should_treat_as_playlist(&station.stream_url, "")
+    {
+        match state.stream_relay.join(&station).await {
+            Ok(listener) => return Ok(with_rate_limit(relay_stream_response(listener), &rate)),
+            Err(RelayError::Timeout) => {
+                return Err(ApiError::ServiceUnavailable("Stream request timed out"))
+            }
+            Err(RelayError::Network) => {
+                return Err(ApiError::ServiceUnavailable("Failed to reach stream URL."))
+            }
+            Err(RelayError::Upstream(status)) => {
+                let message = format!("Upstream returned {}", status.as_u16());
+                return Ok(with_rate_limit(
+                    upstream_error_response(status, message),
+                    &rate,
+                ));
+            }
+            // Playlists and relays over capacity are served with a dedicated connection.
+            Err(RelayError::AtCapacity | RelayError::NotRelayable) => {}
+        }
+    }
 
+    let response = send_stream_request(&state, &station.stream_url, &headers).await?;
     if !response.status().is_success() {
         let status = response.status();
         let message = format!("Upstream returned {}", status.as_u16());
@@ -1312,10 +2289,30 @@ async fn stream_station(
         return Ok(with_rate_limit(forward_stream_response(response), &rate));
     }
 
+    let wrapper = StreamPlaylist::detect(&station.stream_url, content_type);
     let playlist = response
         .text()
         .await
         .map_err(|_| ApiError::ServiceUnavailable("Failed to read playlist from upstream."))?;
+    if !is_hls_playlist(&playlist) {
+        // Served as a playlist without HLS tags: a wrapper only the content type gave away.
+        let kind = wrapper.unwrap_or_else(|| StreamPlaylist::sniff(&playlist));
+        let resolved_url = state
+            .stream_validator
+            .resolve_playlist_entries(&kind.parse(&playlist))
+            .await
+            .map_err(|_| ApiError::ServiceUnavailable("Failed to resolve station playlist."))?;
+        let response = send_stream_request(&state, &resolved_url, &headers).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = format!("Upstream returned {}", status.as_u16());
+            return Ok(with_rate_limit(
+                upstream_error_response(status, message),
+                &rate,
+            ));
+        }
+        return Ok(with_rate_limit(forward_stream_response(response), &rate));
+    }
     let rewritten = rewrite_playlist(
         &station.stream_url,
         &playlist,
@@ -1334,6 +2331,24 @@ async fn stream_station(
     Ok(with_rate_limit(response, &rate))
 }
 
+async fn send_stream_request(
+    state: &AppState,
+    url: &str,
+    headers: &HeaderMap,
+) -> Result<reqwest::Response, ApiError> {
+    let request = state
+        .http_client
+        .get(url)
+        .headers(pick_forward_headers(headers, &["user-agent", "accept"]));
+    timeout(
+        Duration::from_millis(state.config.stream_proxy.timeout_ms),
+        request.send(),
+    )
+    .await
+    .map_err(|_| ApiError::ServiceUnavailable("Stream request timed out"))?
+    .map_err(|_| ApiError::ServiceUnavailable("Failed to reach stream URL."))
+}
+
 async fn stream_segment(
     State(state): State<AppState>,
     Path(station_id): Path<String>,
@@ -1403,49 +2418,245 @@ async fn stream_segment(
     }
     let csrf_params = resolve_csrf_params(&headers, &query_map);
 
-    if !should_treat_as_playlist(target.as_str(), content_type) {
-        return Ok(with_rate_limit(forward_stream_response(response), &rate));
+    if !should_treat_as_playlist(target.as_str(), content_type) {
+        return Ok(with_rate_limit(forward_stream_response(response), &rate));
+    }
+
+    let playlist = response
+        .text()
+        .await
+        .map_err(|_| ApiError::ServiceUnavailable("Failed to read playlist from upstream."))?;
+    // When rewriting nested playlists, keep the path relative to the segment handler
+    // so we don't accumulate extra "/stream" segments as the player walks deeper.
+    let rewritten = rewrite_playlist(target.as_str(), &playlist, &csrf_params, "segment");
+
+    Ok(with_rate_limit(
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/vnd.apple.mpegurl")
+            .header("Cache-Control", "no-store")
+            .body(Body::from(rewritten))
+            .map_err(|err| ApiError::internal(anyhow::anyhow!(err)))?,
+        &rate,
+    ))
+}
+
+#[derive(Serialize)]
+struct ClickResponse {
+    status: &'static str,
+}
+
+async fn record_click(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(station_id): Path<String>,
+) -> ApiResponse {
+    let station_id = station_id.trim();
+    if station_id.is_empty() {
+        return Err(ApiError::BadRequest("Station identifier is required"));
+    }
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    // Clicks without a session still count upstream; they just have no history to land in.
+    if let Ok(key) = favorites_key(&headers) {
+        record_history(&state, &key, station_id).await;
+    }
+    state
+        .record_station_click(station_id)
+        .await
+        .map_err(ApiError::internal)?;
+    let mut resp = (StatusCode::ACCEPTED, Json(ClickResponse { status: "ok" })).into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+/// History is a convenience, so a failure to record it never fails the click.
+async fn record_history(state: &AppState, key: &str, station_id: &str) {
+    let Ok(station) = load_station(state, station_id).await else {
+        return;
+    };
+    if let Err(error) = state.history.record(key, &project_station(&station)).await {
+        logger().warn(
+            "history.record_error",
+            json!({
+                "stationId": station.id,
+                "error": format!("{:?}", error),
+            }),
+        );
+    }
+}
+
+const DEFAULT_HISTORY_PAGE_SIZE: usize = 20;
+const MAX_HISTORY_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize, Default)]
+struct HistoryQuery {
+    limit: Option<String>,
+    offset: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HistoryResponse {
+    meta: HistoryMeta,
+    items: Vec<HistoryEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryMeta {
+    total: usize,
+    limit: usize,
+    offset: usize,
+    has_more: bool,
+}
+
+async fn get_history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    let mut errors = Vec::new();
+    let limit = parse_integer(query.limit, MAX_LIMIT_DIGITS, "limit", &mut errors)
+        .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE);
+    let offset =
+        parse_integer(query.offset, MAX_PAGINATION_DIGITS, "offset", &mut errors).unwrap_or(0);
+    if limit == 0 {
+        errors.push("limit must be a whole number".into());
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::BadRequestWithDetails {
+            message: INVALID_QUERY_ERROR,
+            details: errors,
+        });
+    }
+    let limit = limit.min(MAX_HISTORY_PAGE_SIZE);
+
+    let page = state
+        .history
+        .read(&key, limit, offset)
+        .await
+        .map_err(ApiError::internal)?;
+    state
+        .history
+        .refresh_ttl(&key)
+        .await
+        .map_err(ApiError::internal)?;
+
+    // Prefer the current catalogue entry; the play-time snapshot covers removed stations.
+    let (payload, processed) = load_catalogue(&state).await?;
+    let items = page
+        .entries
+        .into_iter()
+        .map(|entry| HistoryEntry {
+            station: get_station_by_id(&payload, &processed, &entry.station_id)
+                .map(|station| project_station(&station))
+                .or(entry.station),
+            ..entry
+        })
+        .collect();
+
+    let mut resp = Json(HistoryResponse {
+        meta: HistoryMeta {
+            total: page.total,
+            limit,
+            offset,
+            has_more: offset.saturating_add(limit) < page.total,
+        },
+        items,
+    })
+    .into_response();
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-cache"),
+    );
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn clear_history(State(state): State<AppState>, headers: HeaderMap) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    let key = favorites_key(&headers)?;
+    state
+        .history
+        .clear(&key)
+        .await
+        .map_err(ApiError::internal)?;
+    let mut resp = StatusCode::NO_CONTENT.into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn now_playing(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(station_id): Path<String>,
+) -> ApiResponse {
+    let station_id = station_id.trim();
+    if station_id.is_empty() {
+        return Err(ApiError::BadRequest("Station identifier is required."));
     }
+    let rate = enforce_rate_limit(&state, &headers).await?;
 
-    let playlist = response
-        .text()
+    let station = load_station(&state, station_id).await?;
+    let now_playing = state
+        .now_playing
+        .lookup(&station)
         .await
-        .map_err(|_| ApiError::ServiceUnavailable("Failed to read playlist from upstream."))?;
-    // When rewriting nested playlists, keep the path relative to the segment handler
-    // so we don't accumulate extra "/stream" segments as the player walks deeper.
-    let rewritten = rewrite_playlist(target.as_str(), &playlist, &csrf_params, "segment");
-
-    Ok(with_rate_limit(
-        Response::builder()
-            .status(StatusCode::OK)
-            .header("Content-Type", "application/vnd.apple.mpegurl")
-            .header("Cache-Control", "no-store")
-            .body(Body::from(rewritten))
-            .map_err(|err| ApiError::internal(anyhow::anyhow!(err)))?,
-        &rate,
-    ))
-}
+        .map_err(|error| match error {
+            NowPlayingError::Timeout => {
+                ApiError::ServiceUnavailable("Now playing request timed out")
+            }
+            NowPlayingError::Network | NowPlayingError::Upstream(_) => {
+                ApiError::ServiceUnavailable("Failed to read stream metadata.")
+            }
+        })?;
 
-#[derive(Serialize)]
-struct ClickResponse {
-    status: &'static str,
+    let mut resp = Json(now_playing).into_response();
+    let max_age = state.now_playing.cache_ttl().as_secs();
+    if let Ok(value) = HeaderValue::from_str(&format!("public, max-age={max_age}")) {
+        resp.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
 }
 
-async fn record_click(
+async fn now_playing_events(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(station_id): Path<String>,
 ) -> ApiResponse {
     let station_id = station_id.trim();
     if station_id.is_empty() {
-        return Err(ApiError::BadRequest("Station identifier is required"));
+        return Err(ApiError::BadRequest("Station identifier is required."));
     }
     let rate = enforce_rate_limit(&state, &headers).await?;
-    state
-        .record_station_click(station_id)
-        .await
-        .map_err(ApiError::internal)?;
-    let mut resp = (StatusCode::ACCEPTED, Json(ClickResponse { status: "ok" })).into_response();
+
+    let station = load_station(&state, station_id).await?;
+    let mut subscription = state
+        .now_playing_hub
+        .subscribe(&station)
+        .map_err(|_| ApiError::ServiceUnavailable("Too many live metadata feeds are active."))?;
+
+    let initial = subscription.latest.take();
+    // The subscription travels with the stream, so dropping the client connection
+    // releases it and lets the hub stop the upstream watcher.
+    let updates = stream::unfold(subscription, |mut subscription| async move {
+        subscription.next().await.map(|value| (value, subscription))
+    });
+    let events = stream::iter(initial)
+        .chain(updates)
+        .map(|value| Event::default().event("now-playing").json_data(value));
+
+    let mut resp = Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    resp.headers_mut().insert(
+        header::HeaderName::from_static("x-accel-buffering"),
+        HeaderValue::from_static("no"),
+    );
     apply_rate_limit_headers(resp.headers_mut(), &rate);
     Ok(resp)
 }
@@ -1482,6 +2693,235 @@ async fn refresh_stations(State(state): State<AppState>, headers: HeaderMap) ->
     Ok(resp)
 }
 
+#[derive(Serialize)]
+struct PayloadHistoryResponse {
+    items: Vec<PayloadHistoryEntry>,
+}
+
+async fn list_station_payloads(State(state): State<AppState>, headers: HeaderMap) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    ensure_refresh_authorized(&headers, &state.config.refresh_token)?;
+    let items = state
+        .stations
+        .list_payload_history()
+        .await
+        .map_err(|err| ApiError::internal(err.into()))?;
+    let mut resp = Json(PayloadHistoryResponse { items }).into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn rollback_station_payload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(history_id): Path<String>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    ensure_refresh_authorized(&headers, &state.config.refresh_token)?;
+    let history_id: i64 = history_id
+        .parse()
+        .map_err(|_| ApiError::BadRequest("Invalid payload id."))?;
+    let payload = match state
+        .rollback_stations(history_id)
+        .await
+        .map_err(ApiError::internal)?
+    {
+        RollbackOutcome::Restored(payload) => payload,
+        RollbackOutcome::NotFound => return Err(ApiError::NotFound("Payload not found.")),
+        RollbackOutcome::Busy => {
+            return Err(ApiError::Conflict(
+                "A station refresh is in progress; retry the rollback shortly.",
+            ))
+        }
+    };
+    let mut resp = Json(RefreshResponse {
+        meta: RefreshMeta {
+            total: payload.total,
+            updated_at: payload.updated_at.to_rfc3339(),
+            cache_source: "history".into(),
+            origin: payload.source.clone(),
+        },
+    })
+    .into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+const MAX_OVERRIDE_TEXT_LENGTH: usize = 200;
+const MAX_OVERRIDE_LIST_ITEMS: usize = 32;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct StationOverrideBody {
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    patch: StationPatch,
+}
+
+#[derive(Serialize)]
+struct StationOverridesResponse {
+    items: Vec<StationOverride>,
+}
+
+async fn list_station_overrides(State(state): State<AppState>, headers: HeaderMap) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    ensure_refresh_authorized(&headers, &state.config.refresh_token)?;
+    let items = state
+        .stations
+        .load_overrides()
+        .await
+        .map_err(|err| ApiError::internal(err.into()))?;
+    let mut resp = Json(StationOverridesResponse { items }).into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn put_station_override(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(station_id): Path<String>,
+    body: Option<Json<StationOverrideBody>>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    ensure_refresh_authorized(&headers, &state.config.refresh_token)?;
+    let station_id = sanitize_station_id(&station_id)
+        .ok_or(ApiError::BadRequest("Invalid station identifier"))?;
+    let Some(Json(body)) = body else {
+        return Err(ApiError::BadRequest("Override body is required."));
+    };
+    let patch = sanitize_station_patch(
+        body.patch,
+        state.config.radio_browser.enforce_https_streams,
+        state.config.allow_insecure_transports,
+    )?;
+    if !body.hidden && !body.pinned && patch == StationPatch::default() {
+        return Err(ApiError::BadRequest(
+            "An override must hide, pin or patch the station.",
+        ));
+    }
+
+    let entry = state
+        .stations
+        .upsert_override(&station_id, body.hidden, body.pinned, &patch)
+        .await
+        .map_err(|err| ApiError::internal(err.into()))?;
+    logger().info(
+        "stations.override_saved",
+        json!({
+            "stationId": entry.station_id,
+            "hidden": entry.hidden,
+            "pinned": entry.pinned,
+        }),
+    );
+    let mut resp = Json(entry).into_response();
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+async fn delete_station_override(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(station_id): Path<String>,
+) -> ApiResponse {
+    let rate = enforce_rate_limit(&state, &headers).await?;
+    ensure_refresh_authorized(&headers, &state.config.refresh_token)?;
+    let station_id = sanitize_station_id(&station_id)
+        .ok_or(ApiError::BadRequest("Invalid station identifier"))?;
+    let removed = state
+        .stations
+        .delete_override(&station_id)
+        .await
+        .map_err(|err| ApiError::internal(err.into()))?;
+    if !removed {
+        return Err(ApiError::NotFound("Override not found."));
+    }
+    logger().info(
+        "stations.override_removed",
+        json!({ "stationId": station_id }),
+    );
+    let mut resp = StatusCode::NO_CONTENT.into_response();
+    apply_rate_limit_headers(resp.headers_mut(), &rate);
+    Ok(resp)
+}
+
+/// Applies the same normalisation upstream stations get, so a patch cannot smuggle in an
+/// insecure or blocked URL.
+fn sanitize_station_patch(
+    patch: StationPatch,
+    enforce_https: bool,
+    allow_insecure: bool,
+) -> Result<StationPatch, ApiError> {
+    let text = |value: Option<String>, message: &'static str| match value {
+        None => Ok(None),
+        Some(raw) => {
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.chars().count() > MAX_OVERRIDE_TEXT_LENGTH {
+                Err(ApiError::BadRequest(message))
+            } else {
+                Ok(Some(trimmed.to_string()))
+            }
+        }
+    };
+    let url = |value: Option<String>, message: &'static str| match value {
+        None => Ok(None),
+        Some(raw) => sanitize_station_url(Some(&raw), enforce_https, allow_insecure)
+            .map(Some)
+            .ok_or(ApiError::BadRequest(message)),
+    };
+    let list = |value: Option<Vec<String>>, message: &'static str| match value {
+        None => Ok(None),
+        Some(items) => {
+            let mut seen = HashSet::new();
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty())
+                .filter(|item| seen.insert(item.to_ascii_lowercase()))
+                .map(str::to_string)
+                .collect();
+            if items.len() > MAX_OVERRIDE_LIST_ITEMS
+                || items
+                    .iter()
+                    .any(|item| item.chars().count() > MAX_OVERRIDE_TEXT_LENGTH)
+            {
+                Err(ApiError::BadRequest(message))
+            } else {
+                Ok(Some(items))
+            }
+        }
+    };
+    let country_code = match patch.country_code {
+        None => None,
+        Some(raw) => {
+            let code = raw.trim().to_ascii_uppercase();
+            if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(ApiError::BadRequest("Invalid countryCode override."));
+            }
+            Some(code)
+        }
+    };
+
+    Ok(StationPatch {
+        name: text(patch.name, "Invalid name override.")?,
+        homepage: url(patch.homepage, "Invalid homepage override.")?,
+        favicon: url(patch.favicon, "Invalid favicon override.")?,
+        country: text(patch.country, "Invalid country override.")?,
+        country_code,
+        state: text(patch.state, "Invalid state override.")?,
+        languages: list(patch.languages, "Invalid languages override.")?,
+        tags: list(patch.tags, "Invalid tags override.")?,
+    })
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -1508,8 +2948,16 @@ async fn shutdown_signal() {
 const MAX_LIMIT_DIGITS: usize = 5;
 const MAX_PAGINATION_DIGITS: usize = 6;
 const MAX_FILTER_LENGTH: usize = 128;
+const MAX_FILTER_VALUES: usize = 16;
+const MAX_BITRATE_DIGITS: usize = 5;
 const MAX_SEARCH_LENGTH: usize = 160;
+const MAX_FINGERPRINT_LENGTH: usize = 128;
 const INVALID_QUERY_ERROR: &str = "Invalid query parameters supplied.";
+const STATIONS_CACHE_CONTROL: &str = "public, max-age=30, stale-while-revalidate=120";
+const DEFAULT_RADIUS_KM: f64 = 100.0;
+const MAX_RADIUS_KM: f64 = 2000.0;
+const MAX_COORDINATE_LENGTH: usize = 32;
+const MAX_RELIABILITY_LENGTH: usize = 8;
 
 #[derive(Debug, Default, Deserialize)]
 struct StationsQueryParams {
@@ -1528,7 +2976,39 @@ struct StationsQueryParams {
     #[serde(default)]
     genre: Option<String>,
     #[serde(default)]
+    codec: Option<String>,
+    #[serde(default, rename = "-country")]
+    exclude_country: Option<String>,
+    #[serde(default, rename = "-language")]
+    exclude_language: Option<String>,
+    #[serde(default, rename = "-tag")]
+    exclude_tag: Option<String>,
+    #[serde(default, rename = "-genre")]
+    exclude_genre: Option<String>,
+    #[serde(default, rename = "-codec")]
+    exclude_codec: Option<String>,
+    #[serde(default, rename = "minBitrate")]
+    min_bitrate: Option<String>,
+    #[serde(default, rename = "maxBitrate")]
+    max_bitrate: Option<String>,
+    #[serde(default)]
+    hls: Option<String>,
+    #[serde(default, rename = "isOnline")]
+    is_online: Option<String>,
+    #[serde(default, rename = "minReliability")]
+    min_reliability: Option<String>,
+    #[serde(default)]
     search: Option<String>,
+    #[serde(default)]
+    lat: Option<String>,
+    #[serde(default)]
+    lon: Option<String>,
+    #[serde(default, rename = "radiusKm")]
+    radius_km: Option<String>,
+    #[serde(default)]
+    sort: Option<String>,
+    #[serde(default)]
+    order: Option<String>,
     #[serde(rename = "forceRefresh")]
     force_refresh: Option<String>,
     #[serde(default)]
@@ -1549,7 +3029,23 @@ impl StationsQueryParams {
             language,
             tag,
             genre,
+            codec,
+            exclude_country,
+            exclude_language,
+            exclude_tag,
+            exclude_genre,
+            exclude_codec,
+            min_bitrate,
+            max_bitrate,
+            hls,
+            is_online,
+            min_reliability,
             search,
+            lat,
+            lon,
+            radius_km,
+            sort,
+            order,
             force_refresh,
             refresh,
         } = self;
@@ -1592,11 +3088,33 @@ impl StationsQueryParams {
             1
         };
 
-        let country = normalize_filter_value(country, "country", MAX_FILTER_LENGTH, &mut errors);
-        let language = normalize_filter_value(language, "language", MAX_FILTER_LENGTH, &mut errors);
-        let tag = normalize_filter_value(tag, "tag", MAX_FILTER_LENGTH, &mut errors);
-        let genre = normalize_filter_value(genre, "genre", MAX_FILTER_LENGTH, &mut errors);
+        let include = FilterValues {
+            country: normalize_filter_values(country, "country", &mut errors),
+            language: normalize_filter_values(language, "language", &mut errors),
+            tag: normalize_filter_values(tag, "tag", &mut errors),
+            genre: normalize_filter_values(genre, "genre", &mut errors),
+            codec: normalize_filter_values(codec, "codec", &mut errors),
+        };
+        let exclude = FilterValues {
+            country: normalize_filter_values(exclude_country, "-country", &mut errors),
+            language: normalize_filter_values(exclude_language, "-language", &mut errors),
+            tag: normalize_filter_values(exclude_tag, "-tag", &mut errors),
+            genre: normalize_filter_values(exclude_genre, "-genre", &mut errors),
+            codec: normalize_filter_values(exclude_codec, "-codec", &mut errors),
+        };
+        let min_bitrate = parse_bitrate(min_bitrate, "minBitrate", &mut errors);
+        let max_bitrate = parse_bitrate(max_bitrate, "maxBitrate", &mut errors);
+        if let (Some(min), Some(max)) = (min_bitrate, max_bitrate) {
+            if min > max {
+                errors.push("minBitrate must not be greater than maxBitrate".into());
+            }
+        }
+        let hls = parse_optional_bool(hls, "hls", &mut errors);
+        let is_online = parse_optional_bool(is_online, "isOnline", &mut errors);
+        let min_reliability = parse_reliability(min_reliability, &mut errors);
         let search = normalize_search_value(search, &mut errors);
+        let near = normalize_geo_query(lat, lon, radius_km, &mut errors);
+        let sort = normalize_sort(sort, order, &mut errors);
 
         if !errors.is_empty() {
             return Err(errors);
@@ -1609,11 +3127,16 @@ impl StationsQueryParams {
             offset,
             page,
             requested_limit,
-            country,
-            language,
-            tag,
-            genre,
+            include,
+            exclude,
+            min_bitrate,
+            max_bitrate,
+            hls,
+            is_online,
+            min_reliability,
             search,
+            near,
+            sort,
             force_refresh,
         })
     }
@@ -1625,14 +3148,101 @@ struct NormalizedStationsQuery {
     offset: usize,
     page: usize,
     requested_limit: Option<RequestedLimit>,
-    country: Option<String>,
-    language: Option<String>,
-    tag: Option<String>,
-    genre: Option<String>,
+    include: FilterValues,
+    exclude: FilterValues,
+    min_bitrate: Option<i32>,
+    max_bitrate: Option<i32>,
+    hls: Option<bool>,
+    is_online: Option<bool>,
+    min_reliability: Option<f64>,
     search: Option<String>,
+    near: Option<GeoQuery>,
+    sort: Option<(StationSort, SortOrder)>,
     force_refresh: bool,
 }
 
+impl NormalizedStationsQuery {
+    /// Every field that shapes the response, so equivalent queries share an ETag.
+    /// `force_refresh` only changes where the data is loaded from.
+    fn cache_key(&self) -> String {
+        let requested_limit = self.requested_limit.as_ref().map(|limit| match limit {
+            RequestedLimit::Number(value) => json!(value),
+            RequestedLimit::All => json!("all"),
+        });
+        json!({
+            "limit": self.limit,
+            "offset": self.offset,
+            "page": self.page,
+            "requestedLimit": requested_limit,
+            "include": self.include.cache_key(),
+            "exclude": self.exclude.cache_key(),
+            "minBitrate": self.min_bitrate,
+            "maxBitrate": self.max_bitrate,
+            "hls": self.hls,
+            "online": self.is_online,
+            "minReliability": self.min_reliability,
+            "search": self.search,
+            "near": self
+                .near
+                .as_ref()
+                .map(|near| [near.lat, near.lon, near.radius_km]),
+            "sort": self
+                .sort
+                .map(|(sort, order)| [sort.as_str(), order.as_str()]),
+        })
+        .to_string()
+    }
+}
+
+/// Comma-separated filter values. Values within a field are OR'ed; fields are AND'ed.
+#[derive(Debug, Default)]
+struct FilterValues {
+    country: Vec<String>,
+    language: Vec<String>,
+    tag: Vec<String>,
+    genre: Vec<String>,
+    codec: Vec<String>,
+}
+
+type IndexLookup = for<'a> fn(&'a ProcessedStations, &str) -> Option<&'a [usize]>;
+
+impl FilterValues {
+    fn cache_key(&self) -> serde_json::Value {
+        json!([
+            self.country,
+            self.language,
+            self.tag,
+            self.genre,
+            self.codec,
+        ])
+    }
+
+    /// One merged candidate list per non-empty field.
+    fn index_lists(&self, processed: &ProcessedStations) -> Vec<Vec<usize>> {
+        let fields: [(&[String], IndexLookup); 5] = [
+            (&self.country, ProcessedStations::indexes_for_country),
+            (&self.language, ProcessedStations::indexes_for_language),
+            (&self.tag, ProcessedStations::indexes_for_tag),
+            (&self.genre, ProcessedStations::indexes_for_tag),
+            (&self.codec, ProcessedStations::indexes_for_codec),
+        ];
+        fields
+            .into_iter()
+            .filter(|(values, _)| !values.is_empty())
+            .map(|(values, lookup)| {
+                union_lists(values.iter().filter_map(|value| lookup(processed, value)))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GeoQuery {
+    lat: f64,
+    lon: f64,
+    radius_km: f64,
+}
+
 #[derive(Debug, Clone)]
 enum RequestedLimit {
     Number(usize),
@@ -1685,21 +3295,168 @@ fn parse_integer(
     None
 }
 
-fn normalize_filter_value(
+fn normalize_filter_values(
     value: Option<String>,
     field: &'static str,
-    max_len: usize,
     errors: &mut Vec<String>,
-) -> Option<String> {
-    if let Some(raw) = normalize_raw_value(value) {
-        if raw.chars().count() > max_len {
-            errors.push(format!("{field} must be at most {max_len} characters"));
+) -> Vec<String> {
+    let Some(raw) = normalize_raw_value(value) else {
+        return Vec::new();
+    };
+    let mut values: Vec<String> = Vec::new();
+    for part in raw
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        if part.chars().count() > MAX_FILTER_LENGTH {
+            errors.push(format!(
+                "{field} values must be at most {MAX_FILTER_LENGTH} characters"
+            ));
+            return Vec::new();
+        }
+        let normalized = part.to_lowercase();
+        if !values.contains(&normalized) {
+            values.push(normalized);
+        }
+    }
+    if values.len() > MAX_FILTER_VALUES {
+        errors.push(format!(
+            "{field} accepts at most {MAX_FILTER_VALUES} values"
+        ));
+        return Vec::new();
+    }
+    values
+}
+
+fn parse_bitrate(
+    value: Option<String>,
+    field: &'static str,
+    errors: &mut Vec<String>,
+) -> Option<i32> {
+    parse_integer(value, MAX_BITRATE_DIGITS, field, errors).map(|value| value as i32)
+}
+
+fn parse_reliability(value: Option<String>, errors: &mut Vec<String>) -> Option<f64> {
+    let raw = normalize_raw_value(value)?;
+    match raw.parse::<f64>() {
+        Ok(value) if raw.len() <= MAX_RELIABILITY_LENGTH && (0.0..=100.0).contains(&value) => {
+            Some(value)
+        }
+        _ => {
+            errors.push("minReliability must be a number between 0 and 100".into());
             None
-        } else {
-            Some(raw.to_lowercase())
         }
-    } else {
-        None
+    }
+}
+
+fn parse_optional_bool(
+    value: Option<String>,
+    field: &'static str,
+    errors: &mut Vec<String>,
+) -> Option<bool> {
+    let raw = normalize_raw_value(value)?;
+    match raw.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => {
+            errors.push(format!("{field} must be true or false"));
+            None
+        }
+    }
+}
+
+fn normalize_sort(
+    sort: Option<String>,
+    order: Option<String>,
+    errors: &mut Vec<String>,
+) -> Option<(StationSort, SortOrder)> {
+    let order = normalize_raw_value(order).and_then(|raw| {
+        let parsed = SortOrder::parse(&raw);
+        if parsed.is_none() {
+            errors.push("order must be one of asc, desc".into());
+        }
+        parsed
+    });
+    let Some(raw) = normalize_raw_value(sort) else {
+        if order.is_some() {
+            errors.push("order requires sort".into());
+        }
+        return None;
+    };
+    let Some(sort) = StationSort::parse(&raw) else {
+        errors.push(
+            "sort must be one of votes, clickTrend, bitrate, name, lastChanged, random".into(),
+        );
+        return None;
+    };
+    Some((sort, order.unwrap_or_else(|| sort.default_order())))
+}
+
+fn normalize_geo_query(
+    lat: Option<String>,
+    lon: Option<String>,
+    radius_km: Option<String>,
+    errors: &mut Vec<String>,
+) -> Option<GeoQuery> {
+    let lat = parse_coordinate(lat, "lat", 90.0, errors);
+    let lon = parse_coordinate(lon, "lon", 180.0, errors);
+    let radius_km = normalize_raw_value(radius_km).and_then(|raw| match raw.parse::<f64>() {
+        Ok(value)
+            if raw.len() <= MAX_COORDINATE_LENGTH
+                && value.is_finite()
+                && value > 0.0
+                && value <= MAX_RADIUS_KM =>
+        {
+            Some(value)
+        }
+        _ => {
+            errors.push(format!(
+                "radiusKm must be a number greater than 0 and at most {MAX_RADIUS_KM}"
+            ));
+            None
+        }
+    });
+
+    match (lat, lon) {
+        (Some(Ok(lat)), Some(Ok(lon))) => Some(GeoQuery {
+            lat,
+            lon,
+            radius_km: radius_km.unwrap_or(DEFAULT_RADIUS_KM),
+        }),
+        (None, None) => {
+            if radius_km.is_some() {
+                errors.push("radiusKm requires lat and lon".into());
+            }
+            None
+        }
+        (Some(Err(())), _) | (_, Some(Err(()))) => None,
+        _ => {
+            errors.push("lat and lon must be supplied together".into());
+            None
+        }
+    }
+}
+
+fn parse_coordinate(
+    value: Option<String>,
+    field: &'static str,
+    bound: f64,
+    errors: &mut Vec<String>,
+) -> Option<Result<f64, ()>> {
+    let raw = normalize_raw_value(value)?;
+    match raw.parse::<f64>() {
+        Ok(value)
+            if raw.len() <= MAX_COORDINATE_LENGTH && value.is_finite() && value.abs() <= bound =>
+        {
+            Some(Ok(value))
+        }
+        _ => {
+            errors.push(format!(
+                "{field} must be a number between -{bound} and {bound}"
+            ));
+            Some(Err(()))
+        }
     }
 }
 
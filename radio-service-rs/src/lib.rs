@@ -1,11 +1,22 @@
 pub mod app_state;
+pub mod conditional;
 pub mod config;
 pub mod database;
 pub mod favorites;
+pub mod history;
+pub mod hls;
 pub mod http;
 pub mod logging;
 pub mod migrations;
+pub mod now_playing;
+pub mod now_playing_hub;
+pub mod playlist;
 pub mod radio_browser;
 pub mod refresh;
+pub mod revalidation;
+pub mod sources;
 pub mod stations;
+pub mod stream_codec;
+pub mod stream_health;
+pub mod stream_relay;
 pub mod stream_validation;
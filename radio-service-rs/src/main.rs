@@ -1,13 +1,24 @@
 mod app_state;
+mod conditional;
 mod config;
 mod database;
 mod favorites;
+mod history;
+mod hls;
 mod http;
 mod logging;
 mod migrations;
+mod now_playing;
+mod now_playing_hub;
+mod playlist;
 mod radio_browser;
 mod refresh;
+mod revalidation;
+mod sources;
 mod stations;
+mod stream_codec;
+mod stream_health;
+mod stream_relay;
 mod stream_validation;
 
 use anyhow::Context;
@@ -53,6 +64,9 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    state.spawn_refresh_scheduler();
+    state.spawn_revalidation_scheduler();
+
     logger.info(
         "server.initialized",
         json!({
@@ -0,0 +1,668 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use futures_util::StreamExt;
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::{sync::Mutex, time::timeout};
+use url::Url;
+
+use crate::{
+    config::NowPlayingConfig,
+    hls::{first_variant_uri, last_media_segment},
+    stations::{is_blocked_domain, Station},
+};
+
+const MAX_METAINT: usize = 256 * 1024;
+const MAX_PLAYLIST_BYTES: usize = 256 * 1024;
+const ID3_PROBE_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Error)]
+pub enum NowPlayingError {
+    #[error("now playing lookup timed out")]
+    Timeout,
+    #[error("failed to reach stream URL")]
+    Network,
+    #[error("upstream returned {0}")]
+    Upstream(StatusCode),
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NowPlaying {
+    #[serde(rename = "stationId")]
+    pub station_id: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    #[serde(rename = "streamTitle")]
+    pub stream_title: Option<String>,
+    pub source: Option<&'static str>,
+    #[serde(rename = "fetchedAt")]
+    pub fetched_at: String,
+}
+
+impl NowPlaying {
+    pub fn empty(station_id: &str) -> Self {
+        Self {
+            station_id: station_id.to_string(),
+            title: None,
+            artist: None,
+            stream_title: None,
+            source: None,
+            fetched_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn from_stream_title(station_id: &str, raw: &str, source: &'static str) -> Self {
+        let stream_title = raw.trim().to_string();
+        let (artist, title) = split_artist_title(&stream_title);
+        Self {
+            station_id: station_id.to_string(),
+            title,
+            artist,
+            stream_title: Some(stream_title).filter(|value| !value.is_empty()),
+            source: Some(source),
+            fetched_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+pub enum MetadataSource {
+    Icy {
+        metaint: usize,
+        response: reqwest::Response,
+    },
+    Hls {
+        base_url: Url,
+        playlist: String,
+    },
+    Unavailable,
+}
+
+#[derive(Clone)]
+struct CachedNowPlaying {
+    stream_url: String,
+    value: NowPlaying,
+    expires_at: Instant,
+}
+
+#[derive(Clone)]
+pub struct NowPlayingService {
+    client: Client,
+    config: NowPlayingConfig,
+    cache: Arc<Mutex<HashMap<String, CachedNowPlaying>>>,
+}
+
+impl NowPlayingService {
+    pub fn new(config: NowPlayingConfig, client: Client) -> Self {
+        Self {
+            client,
+            config,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.config.cache_ttl_seconds)
+    }
+
+    pub async fn lookup(&self, station: &Station) -> Result<NowPlaying, NowPlayingError> {
+        {
+            let cache = self.cache.lock().await;
+            if let Some(entry) = cache.get(&station.id) {
+                if entry.stream_url == station.stream_url && entry.expires_at > Instant::now() {
+                    return Ok(entry.value.clone());
+                }
+            }
+        }
+
+        let value = timeout(
+            Duration::from_millis(self.config.timeout_ms),
+            self.fetch(station),
+        )
+        .await
+        .map_err(|_| NowPlayingError::Timeout)??;
+        self.remember(station, &value).await;
+        Ok(value)
+    }
+
+    /// Seeds the lookup cache, so values pushed by live watchers also serve plain requests.
+    pub async fn remember(&self, station: &Station, value: &NowPlaying) {
+        let mut cache = self.cache.lock().await;
+        let now = Instant::now();
+        cache.retain(|_, entry| entry.expires_at > now);
+        cache.insert(
+            station.id.clone(),
+            CachedNowPlaying {
+                stream_url: station.stream_url.clone(),
+                value: value.clone(),
+                expires_at: now + self.cache_ttl(),
+            },
+        );
+    }
+
+    /// Opens the station stream asking for interleaved ICY metadata.
+    pub async fn open(&self, station: &Station) -> Result<MetadataSource, NowPlayingError> {
+        let response = self
+            .client
+            .get(&station.stream_url)
+            .header("icy-metadata", "1")
+            .header("accept", "*/*")
+            .send()
+            .await
+            .map_err(|_| NowPlayingError::Network)?;
+        if !response.status().is_success() {
+            return Err(NowPlayingError::Upstream(response.status()));
+        }
+
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        if station.hls || content_type.contains("mpegurl") {
+            let base_url = response.url().clone();
+            let playlist = read_limited(response, MAX_PLAYLIST_BYTES).await?;
+            return Ok(MetadataSource::Hls {
+                base_url,
+                playlist: String::from_utf8_lossy(&playlist).into_owned(),
+            });
+        }
+
+        let metaint = response
+            .headers()
+            .get("icy-metaint")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|value| *value > 0 && *value <= MAX_METAINT);
+        Ok(match metaint {
+            Some(metaint) => MetadataSource::Icy { metaint, response },
+            None => MetadataSource::Unavailable,
+        })
+    }
+
+    async fn fetch(&self, station: &Station) -> Result<NowPlaying, NowPlayingError> {
+        let source = self.open(station).await?;
+        self.read(station, source).await
+    }
+
+    /// Reads the current title from a source returned by `open`.
+    pub async fn read(
+        &self,
+        station: &Station,
+        source: MetadataSource,
+    ) -> Result<NowPlaying, NowPlayingError> {
+        let (metaint, response) = match source {
+            MetadataSource::Icy { metaint, response } => (metaint, response),
+            MetadataSource::Hls { base_url, playlist } => {
+                return self.fetch_hls(station, base_url, &playlist).await;
+            }
+            MetadataSource::Unavailable => return Ok(NowPlaying::empty(&station.id)),
+        };
+
+        // The first metadata block carries the current title; an empty block means the
+        // server has nothing to report, so give up after a couple of intervals.
+        let max_bytes = metaint * 2 + 2 * (1 + 255 * 16);
+        let mut parser = IcyMetadataParser::new(metaint);
+        let mut consumed = 0usize;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|_| NowPlayingError::Network)?;
+            consumed += chunk.len();
+            for block in parser.push(&chunk) {
+                if let Some(title) = parse_stream_title(&block) {
+                    return Ok(NowPlaying::from_stream_title(&station.id, &title, "icy"));
+                }
+            }
+            if consumed >= max_bytes {
+                break;
+            }
+        }
+        Ok(NowPlaying::empty(&station.id))
+    }
+
+    async fn fetch_hls(
+        &self,
+        station: &Station,
+        base_url: Url,
+        playlist: &str,
+    ) -> Result<NowPlaying, NowPlayingError> {
+        let (media_url, media_playlist) =
+            match first_variant_uri(playlist).and_then(|uri| base_url.join(&uri).ok()) {
+                Some(variant_url) => {
+                    if variant_url.scheme() != "https" || is_blocked_domain(variant_url.as_str()) {
+                        return Ok(NowPlaying::empty(&station.id));
+                    }
+                    let response = self
+                        .client
+                        .get(variant_url.clone())
+                        .send()
+                        .await
+                        .map_err(|_| NowPlayingError::Network)?;
+                    if !response.status().is_success() {
+                        return Err(NowPlayingError::Upstream(response.status()));
+                    }
+                    let body = read_limited(response, MAX_PLAYLIST_BYTES).await?;
+                    (variant_url, String::from_utf8_lossy(&body).into_owned())
+                }
+                None => (base_url, playlist.to_string()),
+            };
+
+        let Some(segment) = last_media_segment(&media_playlist) else {
+            return Ok(NowPlaying::empty(&station.id));
+        };
+        if let Some(info) = segment.info.as_deref().and_then(parse_extinf_title) {
+            return Ok(info.into_now_playing(&station.id, "hls"));
+        }
+
+        let Ok(segment_url) = media_url.join(&segment.uri) else {
+            return Ok(NowPlaying::empty(&station.id));
+        };
+        if segment_url.scheme() != "https" || is_blocked_domain(segment_url.as_str()) {
+            return Ok(NowPlaying::empty(&station.id));
+        }
+        let response = self
+            .client
+            .get(segment_url)
+            .header("range", format!("bytes=0-{}", ID3_PROBE_BYTES - 1))
+            .send()
+            .await
+            .map_err(|_| NowPlayingError::Network)?;
+        if !(response.status().is_success() || response.status() == StatusCode::PARTIAL_CONTENT) {
+            return Err(NowPlayingError::Upstream(response.status()));
+        }
+        let probe = read_limited(response, ID3_PROBE_BYTES).await?;
+        Ok(parse_id3_title(&probe)
+            .map(|info| info.into_now_playing(&station.id, "id3"))
+            .unwrap_or_else(|| NowPlaying::empty(&station.id)))
+    }
+}
+
+async fn read_limited(
+    response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<Vec<u8>, NowPlayingError> {
+    let mut buffer = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|_| NowPlayingError::Network)?;
+        let remaining = max_bytes.saturating_sub(buffer.len());
+        buffer.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        if buffer.len() >= max_bytes {
+            break;
+        }
+    }
+    Ok(buffer)
+}
+
+enum IcyState {
+    Audio { remaining: usize },
+    Length,
+    Metadata { remaining: usize, block: Vec<u8> },
+}
+
+/// Incremental splitter for ICY streams: audio bytes are skipped and every non-empty
+/// metadata block is returned as text once it has been fully received.
+pub struct IcyMetadataParser {
+    metaint: usize,
+    state: IcyState,
+}
+
+impl IcyMetadataParser {
+    pub fn new(metaint: usize) -> Self {
+        Self {
+            metaint,
+            state: IcyState::Audio { remaining: metaint },
+        }
+    }
+
+    pub fn push(&mut self, mut chunk: &[u8]) -> Vec<String> {
+        let mut blocks = Vec::new();
+        while !chunk.is_empty() {
+            match &mut self.state {
+                IcyState::Audio { remaining } => {
+                    let skip = (*remaining).min(chunk.len());
+                    *remaining -= skip;
+                    chunk = &chunk[skip..];
+                    if *remaining == 0 {
+                        self.state = IcyState::Length;
+                    }
+                }
+                IcyState::Length => {
+                    let length = chunk[0] as usize * 16;
+                    chunk = &chunk[1..];
+                    self.state = if length == 0 {
+                        IcyState::Audio {
+                            remaining: self.metaint,
+                        }
+                    } else {
+                        IcyState::Metadata {
+                            remaining: length,
+                            block: Vec::with_capacity(length),
+                        }
+                    };
+                }
+                IcyState::Metadata { remaining, block } => {
+                    let take = (*remaining).min(chunk.len());
+                    block.extend_from_slice(&chunk[..take]);
+                    *remaining -= take;
+                    chunk = &chunk[take..];
+                    if *remaining == 0 {
+                        let text = decode_text(block);
+                        let text = text.trim_end_matches('\0').trim();
+                        if !text.is_empty() {
+                            blocks.push(text.to_string());
+                        }
+                        self.state = IcyState::Audio {
+                            remaining: self.metaint,
+                        };
+                    }
+                }
+            }
+        }
+        blocks
+    }
+}
+
+/// Most servers send UTF-8, but plenty of older encoders still emit Latin-1.
+fn decode_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+pub fn parse_stream_title(block: &str) -> Option<String> {
+    const KEY: &str = "StreamTitle='";
+    let start = block.find(KEY)? + KEY.len();
+    let rest = &block[start..];
+    let end = rest
+        .find("';")
+        .unwrap_or_else(|| rest.rfind('\'').unwrap_or(rest.len()));
+    let title = rest[..end].trim();
+    if title.is_empty() {
+        None
+    } else {
+        Some(title.to_string())
+    }
+}
+
+pub fn split_artist_title(raw: &str) -> (Option<String>, Option<String>) {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return (None, None);
+    }
+    match trimmed.split_once(" - ") {
+        Some((artist, title)) if !artist.trim().is_empty() && !title.trim().is_empty() => (
+            Some(artist.trim().to_string()),
+            Some(title.trim().to_string()),
+        ),
+        _ => (None, Some(trimmed.to_string())),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TrackInfo {
+    artist: Option<String>,
+    title: Option<String>,
+}
+
+impl TrackInfo {
+    fn into_now_playing(self, station_id: &str, source: &'static str) -> NowPlaying {
+        let stream_title = match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => Some(format!("{artist} - {title}")),
+            (None, Some(title)) => Some(title.clone()),
+            (Some(artist), None) => Some(artist.clone()),
+            (None, None) => None,
+        };
+        NowPlaying {
+            station_id: station_id.to_string(),
+            title: self.title,
+            artist: self.artist,
+            stream_title,
+            source: Some(source),
+            fetched_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Handles both `#EXTINF:10,Artist - Title` and the attribute form
+/// `#EXTINF:10,title="Title",artist="Artist"` used by several HLS radio encoders.
+fn parse_extinf_title(info: &str) -> Option<TrackInfo> {
+    let title_part = info.split_once(',').map(|(_, rest)| rest).unwrap_or("");
+    let attribute_title = extract_quoted_attribute(info, "title");
+    let attribute_artist = extract_quoted_attribute(info, "artist");
+    if attribute_title.is_some() || attribute_artist.is_some() {
+        return Some(TrackInfo {
+            artist: attribute_artist,
+            title: attribute_title,
+        });
+    }
+    let (artist, title) = split_artist_title(title_part);
+    if artist.is_none() && title.is_none() {
+        return None;
+    }
+    Some(TrackInfo { artist, title })
+}
+
+fn extract_quoted_attribute(info: &str, name: &str) -> Option<String> {
+    let needle = format!("{name}=\"");
+    let mut search_from = 0;
+    while let Some(found) = info[search_from..].find(&needle) {
+        let start = search_from + found;
+        let preceded_by_word = info[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-');
+        let value_start = start + needle.len();
+        if !preceded_by_word {
+            let end = info[value_start..].find('"')? + value_start;
+            let value = info[value_start..end].trim();
+            return Some(value.to_string()).filter(|value| !value.is_empty());
+        }
+        search_from = value_start;
+    }
+    None
+}
+
+fn parse_id3_title(data: &[u8]) -> Option<TrackInfo> {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return None;
+    }
+    let version = data[3];
+    if !(3..=4).contains(&version) {
+        return None;
+    }
+    let flags = data[5];
+    let tag_size = syncsafe(&data[6..10]);
+    let end = (10 + tag_size).min(data.len());
+    let mut pos = 10;
+    if flags & 0x40 != 0 && data.len() >= pos + 4 {
+        let extended = if version == 4 {
+            syncsafe(&data[pos..pos + 4])
+        } else {
+            u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize
+                + 4
+        };
+        pos += extended;
+    }
+
+    let mut info = TrackInfo {
+        artist: None,
+        title: None,
+    };
+    while pos + 10 <= end {
+        let id = &data[pos..pos + 4];
+        if id[0] == 0 {
+            break;
+        }
+        let size_bytes = &data[pos + 4..pos + 8];
+        let size = if version == 4 {
+            syncsafe(size_bytes)
+        } else {
+            u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]])
+                as usize
+        };
+        let body_start = pos + 10;
+        let body_end = body_start.saturating_add(size);
+        if body_end > end {
+            break;
+        }
+        let body = &data[body_start..body_end];
+        match id {
+            b"TIT2" => info.title = decode_id3_text(body),
+            b"TPE1" => info.artist = decode_id3_text(body),
+            _ => {}
+        }
+        pos = body_end;
+    }
+
+    if info.artist.is_none() && info.title.is_none() {
+        None
+    } else {
+        Some(info)
+    }
+}
+
+fn syncsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0usize, |acc, byte| (acc << 7) | (*byte as usize & 0x7f))
+}
+
+fn decode_id3_text(body: &[u8]) -> Option<String> {
+    let (&encoding, text) = body.split_first()?;
+    let decoded = match encoding {
+        0 => text.iter().map(|&byte| byte as char).collect(),
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xfe, 0xff, rest @ ..] => (true, rest),
+                [0xff, 0xfe, rest @ ..] => (false, rest),
+                _ => (encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|pair| {
+                    if big_endian {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(text).into_owned(),
+        _ => return None,
+    };
+    let trimmed = decoded.trim_matches('\0').trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icy_parser_extracts_blocks_across_chunk_boundaries() {
+        let metadata = b"StreamTitle='Daft Punk - One More Time';";
+        let padded_len = metadata.len().div_ceil(16) * 16;
+        let mut stream = vec![0u8; 8];
+        stream.push((padded_len / 16) as u8);
+        stream.extend_from_slice(metadata);
+        stream.resize(8 + 1 + padded_len, 0);
+        stream.extend_from_slice(&[1u8; 8]);
+        stream.push(0);
+
+        let mut parser = IcyMetadataParser::new(8);
+        let mut blocks = Vec::new();
+        for chunk in stream.chunks(5) {
+            blocks.extend(parser.push(chunk));
+        }
+        assert_eq!(blocks.len(), 1);
+        let title = parse_stream_title(&blocks[0]).unwrap();
+        assert_eq!(title, "Daft Punk - One More Time");
+        assert_eq!(
+            split_artist_title(&title),
+            (Some("Daft Punk".into()), Some("One More Time".into()))
+        );
+    }
+
+    #[test]
+    fn stream_title_handles_latin1_and_apostrophes() {
+        assert_eq!(decode_text(&[b'B', 0xe9, b'b', b'e']), "Bébe");
+        assert_eq!(
+            parse_stream_title("StreamTitle='Guns N' Roses - Don't Cry';StreamUrl='';"),
+            Some("Guns N' Roses - Don't Cry".into())
+        );
+        assert_eq!(parse_stream_title("StreamTitle='';"), None);
+        assert_eq!(split_artist_title("Jingle"), (None, Some("Jingle".into())));
+    }
+
+    #[test]
+    fn extinf_titles_support_plain_and_attribute_forms() {
+        let playlist = "#EXTM3U\n#EXTINF:10,Old - Song\nseg1.aac\n#EXTINF:10,title=\"Song\",artist=\"Band\"\nseg2.aac\n";
+        let segment = last_media_segment(playlist).unwrap();
+        assert_eq!(segment.uri, "seg2.aac");
+        assert_eq!(
+            parse_extinf_title(segment.info.as_deref().unwrap()),
+            Some(TrackInfo {
+                artist: Some("Band".into()),
+                title: Some("Song".into()),
+            })
+        );
+        assert_eq!(
+            parse_extinf_title("10,Old - Song"),
+            Some(TrackInfo {
+                artist: Some("Old".into()),
+                title: Some("Song".into()),
+            })
+        );
+        assert_eq!(parse_extinf_title("10,"), None);
+        assert_eq!(
+            first_variant_uri("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nlow/index.m3u8\n"),
+            Some("low/index.m3u8".into())
+        );
+    }
+
+    #[test]
+    fn id3_frames_are_decoded() {
+        fn frame(id: &[u8; 4], encoding: u8, text: &[u8]) -> Vec<u8> {
+            let mut frame = id.to_vec();
+            frame.extend_from_slice(&((text.len() + 1) as u32).to_be_bytes());
+            frame.extend_from_slice(&[0, 0, encoding]);
+            frame.extend_from_slice(text);
+            frame
+        }
+        let mut frames = frame(b"TIT2", 3, "Señorita".as_bytes());
+        frames.extend(frame(b"TPE1", 1, &[0xff, 0xfe, b'A', 0, b'B', 0]));
+        let size = frames.len();
+        let mut tag = b"ID3\x03\x00\x00".to_vec();
+        tag.extend_from_slice(&[
+            ((size >> 21) & 0x7f) as u8,
+            ((size >> 14) & 0x7f) as u8,
+            ((size >> 7) & 0x7f) as u8,
+            (size & 0x7f) as u8,
+        ]);
+        tag.extend(frames);
+
+        assert_eq!(
+            parse_id3_title(&tag),
+            Some(TrackInfo {
+                artist: Some("AB".into()),
+                title: Some("Señorita".into()),
+            })
+        );
+        assert_eq!(parse_id3_title(b"not an id3 tag"), None);
+    }
+}
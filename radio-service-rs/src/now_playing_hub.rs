@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use serde_json::json;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+    time::{sleep, timeout},
+};
+
+use crate::logging::logger;
+use crate::{
+    config::NowPlayingConfig,
+    now_playing::{
+        parse_stream_title, IcyMetadataParser, MetadataSource, NowPlaying, NowPlayingError,
+        NowPlayingService,
+    },
+    stations::Station,
+};
+
+const CHANNEL_CAPACITY: usize = 16;
+const MIN_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+type LatestValue = Arc<Mutex<Option<NowPlaying>>>;
+
+struct Watcher {
+    sender: broadcast::Sender<NowPlaying>,
+    latest: LatestValue,
+    subscribers: usize,
+    task: JoinHandle<()>,
+}
+
+/// Keeps a single upstream metadata watcher per station while at least one client is
+/// subscribed, and fans title changes out to every subscriber.
+#[derive(Clone)]
+pub struct NowPlayingHub {
+    service: NowPlayingService,
+    config: NowPlayingConfig,
+    watchers: Arc<Mutex<HashMap<String, Watcher>>>,
+}
+
+#[derive(Debug)]
+pub struct HubFull;
+
+pub struct NowPlayingSubscription {
+    pub latest: Option<NowPlaying>,
+    receiver: broadcast::Receiver<NowPlaying>,
+    _guard: SubscriberGuard,
+}
+
+struct SubscriberGuard {
+    station_id: String,
+    watchers: Arc<Mutex<HashMap<String, Watcher>>>,
+}
+
+impl NowPlayingHub {
+    pub fn new(config: NowPlayingConfig, service: NowPlayingService) -> Self {
+        Self {
+            service,
+            config,
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn watcher_count(&self) -> usize {
+        self.watchers.lock().map(|guard| guard.len()).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self, station: &Station) -> Result<NowPlayingSubscription, HubFull> {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(watcher) = watchers.get_mut(&station.id) {
+            watcher.subscribers += 1;
+            // Subscribe before reading the latest value so a concurrent publish is never lost.
+            let receiver = watcher.sender.subscribe();
+            let latest = watcher
+                .latest
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone();
+            return Ok(NowPlayingSubscription {
+                latest,
+                receiver,
+                _guard: self.guard(&station.id),
+            });
+        }
+
+        if watchers.len() >= self.config.max_watchers {
+            return Err(HubFull);
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        let latest: LatestValue = Arc::new(Mutex::new(None));
+        let task = tokio::spawn(run_watcher(
+            self.service.clone(),
+            station.clone(),
+            sender.clone(),
+            latest.clone(),
+            self.config.clone(),
+        ));
+        watchers.insert(
+            station.id.clone(),
+            Watcher {
+                sender,
+                latest,
+                subscribers: 1,
+                task,
+            },
+        );
+        logger().info(
+            "now_playing.watcher_started",
+            json!({ "stationId": station.id, "watchers": watchers.len() }),
+        );
+
+        Ok(NowPlayingSubscription {
+            latest: None,
+            receiver,
+            _guard: self.guard(&station.id),
+        })
+    }
+
+    fn guard(&self, station_id: &str) -> SubscriberGuard {
+        SubscriberGuard {
+            station_id: station_id.to_string(),
+            watchers: self.watchers.clone(),
+        }
+    }
+}
+
+impl NowPlayingSubscription {
+    /// Waits for the next title change. Slow subscribers skip straight to newer values.
+    pub async fn next(&mut self) -> Option<NowPlaying> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(value) => return Some(value),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|err| err.into_inner());
+        let Some(watcher) = watchers.get_mut(&self.station_id) else {
+            return;
+        };
+        watcher.subscribers = watcher.subscribers.saturating_sub(1);
+        if watcher.subscribers == 0 {
+            if let Some(watcher) = watchers.remove(&self.station_id) {
+                watcher.task.abort();
+            }
+            logger().info(
+                "now_playing.watcher_stopped",
+                json!({ "stationId": self.station_id, "watchers": watchers.len() }),
+            );
+        }
+    }
+}
+
+async fn run_watcher(
+    service: NowPlayingService,
+    station: Station,
+    sender: broadcast::Sender<NowPlaying>,
+    latest: LatestValue,
+    config: NowPlayingConfig,
+) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match watch_once(&service, &station, &sender, &latest, &config).await {
+            Ok(()) => backoff = MIN_BACKOFF,
+            Err(error) => {
+                logger().warn(
+                    "now_playing.watcher_failed",
+                    json!({
+                        "stationId": station.id,
+                        "error": error.to_string(),
+                        "retryInMs": backoff.as_millis() as u64,
+                    }),
+                );
+            }
+        }
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+async fn watch_once(
+    service: &NowPlayingService,
+    station: &Station,
+    sender: &broadcast::Sender<NowPlaying>,
+    latest: &LatestValue,
+    config: &NowPlayingConfig,
+) -> Result<(), NowPlayingError> {
+    let idle_timeout = Duration::from_millis(config.timeout_ms);
+    let source = timeout(idle_timeout, service.open(station))
+        .await
+        .map_err(|_| NowPlayingError::Timeout)??;
+
+    let (metaint, response) = match source {
+        MetadataSource::Icy { metaint, response } => (metaint, response),
+        source => {
+            // HLS playlists and streams without ICY metadata have to be polled; the source
+            // just opened answers the first poll.
+            let interval = Duration::from_secs(config.poll_interval_seconds);
+            let mut value = timeout(idle_timeout, service.read(station, source))
+                .await
+                .map_err(|_| NowPlayingError::Timeout)??;
+            loop {
+                publish(service, station, sender, latest, value).await;
+                sleep(interval).await;
+                value = service.lookup(station).await?;
+            }
+        }
+    };
+
+    let mut parser = IcyMetadataParser::new(metaint);
+    let mut stream = response.bytes_stream();
+    loop {
+        let chunk = match timeout(idle_timeout, stream.next()).await {
+            Err(_) => return Err(NowPlayingError::Timeout),
+            Ok(None) => return Ok(()),
+            Ok(Some(chunk)) => chunk.map_err(|_| NowPlayingError::Network)?,
+        };
+        for block in parser.push(&chunk) {
+            if let Some(title) = parse_stream_title(&block) {
+                let value = NowPlaying::from_stream_title(&station.id, &title, "icy");
+                publish(service, station, sender, latest, value).await;
+            }
+        }
+    }
+}
+
+async fn publish(
+    service: &NowPlayingService,
+    station: &Station,
+    sender: &broadcast::Sender<NowPlaying>,
+    latest: &LatestValue,
+    value: NowPlaying,
+) {
+    {
+        let mut current = latest.lock().unwrap_or_else(|err| err.into_inner());
+        let unchanged = current.as_ref().is_some_and(|existing| {
+            existing.stream_title == value.stream_title && existing.source == value.source
+        });
+        if unchanged {
+            return;
+        }
+        *current = Some(value.clone());
+    }
+    service.remember(station, &value).await;
+    let _ = sender.send(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    fn hub(max_watchers: usize) -> NowPlayingHub {
+        let config = NowPlayingConfig {
+            timeout_ms: 100,
+            cache_ttl_seconds: 1,
+            poll_interval_seconds: 1,
+            max_watchers,
+        };
+        let service = NowPlayingService::new(config.clone(), reqwest::Client::new());
+        NowPlayingHub::new(config, service)
+    }
+
+    #[tokio::test]
+    async fn watcher_is_shared_and_stops_with_last_subscriber() {
+        crate::logging::init_logger("radio-service-test");
+        let hub = hub(1);
+        let [one, two] = ["one", "two"].map(|id| Station {
+            stream_url: "https://127.0.0.1:9/stream".into(),
+            ..test_station(id)
+        });
+        let first = hub.subscribe(&one).unwrap();
+        let second = hub.subscribe(&one).unwrap();
+        assert_eq!(hub.watcher_count(), 1);
+        assert!(hub.subscribe(&two).is_err());
+
+        drop(first);
+        assert_eq!(hub.watcher_count(), 1);
+        drop(second);
+        assert_eq!(hub.watcher_count(), 0);
+        assert!(hub.subscribe(&two).is_ok());
+    }
+}
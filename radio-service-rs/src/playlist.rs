@@ -0,0 +1,664 @@
+use serde_json::{json, Value};
+use url::Url;
+
+/// One entry of an M3U, PLS, ASX, XSPF or JSON playlist.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistEntry {
+    pub url: String,
+    pub title: Option<String>,
+    /// `key="value"` pairs from an extended M3U `#EXTINF` line, e.g. `tvg-logo`.
+    pub attributes: Vec<(String, String)>,
+}
+
+impl PlaylistEntry {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Parses plain and extended M3U. Each `#EXTINF` line describes the next URL line.
+pub fn parse_m3u(text: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending: Option<PlaylistEntry> = None;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending = Some(parse_extinf(info));
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut entry = pending.take().unwrap_or_default();
+        entry.url = line.to_string();
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Parses a PLS playlist, pairing `FileN` with `TitleN` and keeping the file order.
+pub fn parse_pls(text: &str) -> Vec<PlaylistEntry> {
+    let mut files: Vec<(u32, String)> = Vec::new();
+    let mut titles: Vec<(u32, String)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+        if let Some(index) = key.strip_prefix("file").and_then(|n| n.parse().ok()) {
+            files.push((index, value));
+        } else if let Some(index) = key.strip_prefix("title").and_then(|n| n.parse().ok()) {
+            titles.push((index, value));
+        }
+    }
+    files.sort_by_key(|(index, _)| *index);
+    files
+        .into_iter()
+        .filter(|(_, url)| !url.is_empty())
+        .map(|(index, url)| PlaylistEntry {
+            url,
+            title: titles
+                .iter()
+                .find(|(title_index, _)| *title_index == index)
+                .map(|(_, title)| title.clone())
+                .filter(|title| !title.is_empty()),
+            attributes: Vec::new(),
+        })
+        .collect()
+}
+
+/// Reads the `<ref href="…">` URLs of an ASX playlist, titled by their `<entry>`'s `<title>`.
+/// Tag and attribute names are matched case-insensitively, as files in the wild mix both.
+pub fn parse_asx(text: &str) -> Vec<PlaylistEntry> {
+    // ASCII lowercasing keeps byte offsets, so positions found in `lower` index `text` too.
+    let lower = text.to_ascii_lowercase();
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+    while let Some(found) = lower[cursor..].find("<entry") {
+        let start = cursor + found;
+        let after = start + "<entry".len();
+        if !lower[after..].starts_with(|c: char| c == '>' || c.is_ascii_whitespace()) {
+            // `<entryref>` points at another playlist; only direct entries are followed.
+            cursor = after;
+            continue;
+        }
+        let end = lower[after..]
+            .find("</entry>")
+            .map_or(lower.len(), |len| after + len);
+        let block = &text[after..end];
+        let block_lower = &lower[after..end];
+        let title = asx_title(block, block_lower);
+        for url in asx_refs(block, block_lower) {
+            entries.push(PlaylistEntry {
+                url,
+                title: title.clone(),
+                attributes: Vec::new(),
+            });
+        }
+        cursor = end;
+    }
+    entries
+}
+
+/// Playlist files that stations link to instead of the stream itself. Browsers cannot play
+/// them, so they are resolved to the first working stream they list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamPlaylist {
+    M3u,
+    Pls,
+    Asx,
+}
+
+impl StreamPlaylist {
+    /// Recognises a wrapper playlist from its content type or URL extension. `.m3u8` and HLS
+    /// content types are left to the HLS proxy; a plain `.m3u` can still turn out to be HLS,
+    /// which only the body tells (see `is_hls_playlist`). The extension only counts when the
+    /// content type is missing, generic or playlist-like: an `audio/mpeg` stream mounted at
+    /// `/live.pls` is a stream.
+    pub fn detect(url: &str, content_type: &str) -> Option<Self> {
+        let content_type = content_type.to_ascii_lowercase();
+        if content_type.contains("scpls") {
+            return Some(Self::Pls);
+        }
+        if ["x-ms-asf", "x-ms-wax", "x-ms-wvx"]
+            .iter()
+            .any(|kind| content_type.contains(kind))
+        {
+            return Some(Self::Asx);
+        }
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        let generic = matches!(
+            essence,
+            "" | "text/plain" | "application/octet-stream" | "binary/octet-stream"
+        ) || essence.contains("mpegurl")
+            || essence.ends_with("xml");
+        if !generic {
+            return None;
+        }
+        let path = Url::parse(url).ok()?.path().to_ascii_lowercase();
+        if path.ends_with(".pls") {
+            Some(Self::Pls)
+        } else if path.ends_with(".asx") || path.ends_with(".wax") || path.ends_with(".wvx") {
+            Some(Self::Asx)
+        } else if path.ends_with(".m3u") {
+            Some(Self::M3u)
+        } else {
+            None
+        }
+    }
+
+    /// Guesses the format from the body when neither the URL nor the content type did.
+    pub fn sniff(text: &str) -> Self {
+        let head = text.trim_start_matches('\u{feff}').trim_start();
+        if head
+            .get(..10)
+            .is_some_and(|start| start.eq_ignore_ascii_case("[playlist]"))
+        {
+            Self::Pls
+        } else if head.starts_with('<') {
+            Self::Asx
+        } else {
+            Self::M3u
+        }
+    }
+
+    pub fn parse(self, text: &str) -> Vec<PlaylistEntry> {
+        match self {
+            Self::M3u => parse_m3u(text),
+            Self::Pls => parse_pls(text),
+            Self::Asx => parse_asx(text),
+        }
+    }
+}
+
+/// HLS media and master playlists carry `#EXT-X-` tags; wrapper M3U files never do.
+pub fn is_hls_playlist(text: &str) -> bool {
+    text.contains("#EXT-X-")
+}
+
+/// Playlist file formats favorites can be exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    M3u,
+    Pls,
+    Xspf,
+    Json,
+}
+
+impl PlaylistFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "pls" => Some(Self::Pls),
+            "xspf" => Some(Self::Xspf),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of an uploaded file from its first meaningful characters.
+    pub fn detect(text: &str) -> Self {
+        let head = text.trim_start_matches('\u{feff}').trim_start();
+        let lower: String = head
+            .chars()
+            .take(10)
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if lower.starts_with("[playlist]") {
+            Self::Pls
+        } else if head.starts_with('{') || head.starts_with('[') {
+            Self::Json
+        } else if head.starts_with('<') {
+            Self::Xspf
+        } else {
+            Self::M3u
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::M3u => "audio/x-mpegurl",
+            Self::Pls => "audio/x-scpls",
+            Self::Xspf => "application/xspf+xml",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::M3u => "m3u",
+            Self::Pls => "pls",
+            Self::Xspf => "xspf",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn parse_entries(self, text: &str) -> Vec<PlaylistEntry> {
+        match self {
+            Self::M3u => parse_m3u(text),
+            Self::Pls => parse_pls(text),
+            Self::Xspf => parse_xspf(text),
+            Self::Json => parse_json(text),
+        }
+    }
+
+    /// Writes entries in this format. `tvg-id` and `tvg-logo` attributes carry the station id
+    /// and favicon wherever the format has room for them.
+    pub fn render(self, entries: &[PlaylistEntry]) -> String {
+        match self {
+            Self::M3u => render_m3u(entries),
+            Self::Pls => render_pls(entries),
+            Self::Xspf => render_xspf(entries),
+            Self::Json => render_json(entries),
+        }
+    }
+}
+
+/// Reads the `<track>` elements of an XSPF playlist; anything else in the document is ignored.
+pub fn parse_xspf(text: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<track") {
+        let after = &rest[start + "<track".len()..];
+        let Some(end) = after.find("</track>") else {
+            break;
+        };
+        let track = &after[..end];
+        rest = &after[end + "</track>".len()..];
+        let Some(url) = xml_element(track, "location") else {
+            continue;
+        };
+        let mut attributes = Vec::new();
+        if let Some(id) = xml_element(track, "identifier") {
+            attributes.push(("tvg-id".to_string(), id));
+        }
+        if let Some(image) = xml_element(track, "image") {
+            attributes.push(("tvg-logo".to_string(), image));
+        }
+        entries.push(PlaylistEntry {
+            url,
+            title: xml_element(track, "title"),
+            attributes,
+        });
+    }
+    entries
+}
+
+/// Accepts the JSON export (`{"items": [...]}`), a favorites response, or a bare array of
+/// objects with `streamUrl`/`url` and `name`/`title`.
+pub fn parse_json(text: &str) -> Vec<PlaylistEntry> {
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return Vec::new();
+    };
+    let items = match &value {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(map) => map
+            .get("items")
+            .or_else(|| map.get("entries"))
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        _ => &[],
+    };
+    let field = |item: &Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| item.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let item = item
+                .get("station")
+                .filter(|s| s.is_object())
+                .unwrap_or(item);
+            let url = field(item, &["streamUrl", "url", "location"]);
+            let id = field(item, &["id"]);
+            if url.is_none() && id.is_none() {
+                return None;
+            }
+            let mut attributes = Vec::new();
+            if let Some(id) = id {
+                attributes.push(("tvg-id".to_string(), id));
+            }
+            if let Some(logo) = field(item, &["favicon", "image"]) {
+                attributes.push(("tvg-logo".to_string(), logo));
+            }
+            Some(PlaylistEntry {
+                url: url.unwrap_or_default(),
+                title: field(item, &["name", "title"]),
+                attributes,
+            })
+        })
+        .collect()
+}
+
+fn render_m3u(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        out.push_str("#EXTINF:-1");
+        for (key, value) in &entry.attributes {
+            // Attribute values cannot escape quotes, so drop them rather than break the line.
+            out.push_str(&format!(" {key}=\"{}\"", value.replace('"', "")));
+        }
+        out.push(',');
+        out.push_str(&single_line(entry.title.as_deref().unwrap_or(&entry.url)));
+        out.push('\n');
+        out.push_str(&single_line(&entry.url));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_pls(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (index, entry) in entries.iter().enumerate() {
+        let n = index + 1;
+        out.push_str(&format!("File{n}={}\n", single_line(&entry.url)));
+        if let Some(title) = &entry.title {
+            out.push_str(&format!("Title{n}={}\n", single_line(title)));
+        }
+        out.push_str(&format!("Length{n}=-1\n"));
+    }
+    out.push_str(&format!("NumberOfEntries={}\nVersion=2\n", entries.len()));
+    out
+}
+
+fn render_xspf(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n",
+    );
+    for entry in entries {
+        out.push_str("    <track>\n");
+        out.push_str(&format!(
+            "      <location>{}</location>\n",
+            xml_escape(&entry.url)
+        ));
+        if let Some(title) = &entry.title {
+            out.push_str(&format!("      <title>{}</title>\n", xml_escape(title)));
+        }
+        if let Some(id) = entry.attribute("tvg-id") {
+            out.push_str(&format!(
+                "      <identifier>{}</identifier>\n",
+                xml_escape(id)
+            ));
+        }
+        if let Some(logo) = entry.attribute("tvg-logo") {
+            out.push_str(&format!("      <image>{}</image>\n", xml_escape(logo)));
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+fn render_json(entries: &[PlaylistEntry]) -> String {
+    let items: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "id": entry.attribute("tvg-id"),
+                "name": entry.title,
+                "streamUrl": entry.url,
+                "favicon": entry.attribute("tvg-logo"),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&json!({ "version": 1, "items": items })).unwrap_or_default()
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn xml_element(fragment: &str, name: &str) -> Option<String> {
+    let open = format!("<{name}>");
+    let close = format!("</{name}>");
+    let start = fragment.find(&open)? + open.len();
+    let len = fragment[start..].find(&close)?;
+    let raw = fragment[start..start + len].trim();
+    let raw = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|inner| inner.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| xml_unescape(raw));
+    Some(raw.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn asx_title(block: &str, block_lower: &str) -> Option<String> {
+    let start = block_lower.find("<title>")? + "<title>".len();
+    let len = block_lower[start..].find("</title>")?;
+    let title = xml_unescape(block[start..start + len].trim());
+    Some(title).filter(|title| !title.is_empty())
+}
+
+fn asx_refs(block: &str, block_lower: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut cursor = 0;
+    while let Some(found) = block_lower[cursor..].find("<ref") {
+        let start = cursor + found + "<ref".len();
+        let end = block_lower[start..]
+            .find('>')
+            .map_or(block_lower.len(), |len| start + len);
+        cursor = end;
+        if !block_lower[start..].starts_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let Some(href) = block_lower[start..end].find("href") else {
+            continue;
+        };
+        let value = block[start + href + "href".len()..end]
+            .trim_start()
+            .strip_prefix('=')
+            .map(str::trim_start);
+        let Some(value) = value else {
+            continue;
+        };
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        if let Some(len) = value[1..].find(quote) {
+            let url = xml_unescape(value[1..1 + len].trim());
+            if !url.is_empty() {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// `-1 tvg-logo="https://…" group-title="Jazz",Station name`
+fn parse_extinf(info: &str) -> PlaylistEntry {
+    let (head, title) = split_extinf_title(info);
+    let mut attributes = Vec::new();
+    let mut rest = head;
+    while let Some(eq) = rest.find("=\"") {
+        let key = rest[..eq].rsplit(' ').next().unwrap_or_default().trim();
+        let value_start = eq + 2;
+        let Some(value_len) = rest[value_start..].find('"') else {
+            break;
+        };
+        if !key.is_empty() {
+            attributes.push((
+                key.to_string(),
+                rest[value_start..value_start + value_len].to_string(),
+            ));
+        }
+        rest = &rest[value_start + value_len + 1..];
+    }
+    PlaylistEntry {
+        url: String::new(),
+        title: Some(title.trim().to_string()).filter(|title| !title.is_empty()),
+        attributes,
+    }
+}
+
+/// The title follows the first comma that is not inside a quoted attribute value.
+fn split_extinf_title(info: &str) -> (&str, &str) {
+    let mut quoted = false;
+    for (idx, ch) in info.char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            ',' if !quoted => return (&info[..idx], &info[idx + 1..]),
+            _ => {}
+        }
+    }
+    (info, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extended_m3u_and_pls() {
+        let m3u = "#EXTM3U\n\
+            #EXTINF:-1 tvg-logo=\"https://example.com/logo.png\" group-title=\"Jazz, Soul\",Smooth FM\n\
+            https://smooth.example.com/live\n\
+            \n\
+            https://bare.example.com/stream\n";
+        let entries = parse_m3u(m3u);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title.as_deref(), Some("Smooth FM"));
+        assert_eq!(entries[0].attribute("group-title"), Some("Jazz, Soul"));
+        assert_eq!(
+            entries[0].attribute("TVG-LOGO"),
+            Some("https://example.com/logo.png")
+        );
+        assert_eq!(entries[1].url, "https://bare.example.com/stream");
+        assert_eq!(entries[1].title, None);
+
+        let pls = "[playlist]\nTitle2=Backup\nFile2=https://b.example.com\nFile1=https://a.example.com\nTitle1=Main\nNumberOfEntries=2\n";
+        let entries = parse_pls(pls);
+        let urls: Vec<_> = entries.iter().map(|entry| entry.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://a.example.com", "https://b.example.com"]);
+        assert_eq!(entries[1].title.as_deref(), Some("Backup"));
+    }
+
+    #[test]
+    fn rendered_playlists_parse_back() {
+        let entries = vec![
+            PlaylistEntry {
+                url: "https://radio.example.com/api/radio/stations/abc/stream".into(),
+                title: Some("Rock & <Roll>, \"Live\"".into()),
+                attributes: vec![
+                    ("tvg-id".into(), "abc".into()),
+                    ("tvg-logo".into(), "https://example.com/logo.png".into()),
+                ],
+            },
+            PlaylistEntry {
+                url: "https://radio.example.com/api/radio/stations/def/stream".into(),
+                title: Some("Jazz".into()),
+                attributes: vec![("tvg-id".into(), "def".into())],
+            },
+        ];
+        for format in [
+            PlaylistFormat::M3u,
+            PlaylistFormat::Pls,
+            PlaylistFormat::Xspf,
+            PlaylistFormat::Json,
+        ] {
+            let rendered = format.render(&entries);
+            assert_eq!(PlaylistFormat::detect(&rendered), format);
+            let parsed = format.parse_entries(&rendered);
+            let urls: Vec<_> = parsed.iter().map(|entry| entry.url.as_str()).collect();
+            assert_eq!(
+                urls,
+                vec![entries[0].url.as_str(), entries[1].url.as_str()],
+                "{format:?}"
+            );
+            assert_eq!(parsed[1].title.as_deref(), Some("Jazz"), "{format:?}");
+            if format != PlaylistFormat::Pls {
+                assert_eq!(parsed[0].attribute("tvg-id"), Some("abc"), "{format:?}");
+                assert_eq!(parsed[0].title, entries[0].title, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn parses_asx_and_recognises_wrapper_playlists() {
+        let asx = r#"<ASX version="3.0">
+            <Title>Ignored</Title>
+            <ENTRYREF HREF="https://other.example.com/list.asx" />
+            <Entry>
+              <Title>Main &amp; Co</Title>
+              <Ref href = 'https://a.example.com/live?x=1&amp;y=2' />
+              <REF HREF="http://a.example.com/fallback"/>
+            </Entry>
+            <entry><ref href="https://b.example.com/live"></ref></entry>
+        </ASX>"#;
+        let entries = parse_asx(asx);
+        let urls: Vec<_> = entries.iter().map(|entry| entry.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://a.example.com/live?x=1&y=2",
+                "http://a.example.com/fallback",
+                "https://b.example.com/live",
+            ]
+        );
+        assert_eq!(entries[0].title.as_deref(), Some("Main & Co"));
+        assert_eq!(entries[2].title, None);
+
+        assert_eq!(
+            StreamPlaylist::detect("https://x.example.com/listen.PLS", ""),
+            Some(StreamPlaylist::Pls)
+        );
+        assert_eq!(
+            StreamPlaylist::detect("https://x.example.com/listen", "video/x-ms-asf"),
+            Some(StreamPlaylist::Asx)
+        );
+        assert_eq!(
+            StreamPlaylist::detect("https://x.example.com/live.m3u", "audio/mpegurl"),
+            Some(StreamPlaylist::M3u)
+        );
+        assert_eq!(
+            StreamPlaylist::detect("https://x.example.com/live.m3u8", "audio/mpegurl"),
+            None
+        );
+        assert_eq!(
+            StreamPlaylist::detect("https://x.example.com/live.pls", "audio/mpeg"),
+            None
+        );
+        assert_eq!(
+            StreamPlaylist::detect(
+                "https://x.example.com/live.pls",
+                "text/plain; charset=utf-8"
+            ),
+            Some(StreamPlaylist::Pls)
+        );
+        assert_eq!(
+            StreamPlaylist::sniff("[Playlist]\nFile1=x"),
+            StreamPlaylist::Pls
+        );
+        assert!(is_hls_playlist("#EXTM3U\n#EXT-X-VERSION:3\nchunk.ts"));
+        assert!(!is_hls_playlist("#EXTM3U\nhttps://a.example.com/live"));
+    }
+}
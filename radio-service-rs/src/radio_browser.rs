@@ -1,4 +1,5 @@
 use chrono::Utc;
+use futures_util::future::BoxFuture;
 use reqwest::{Client, Url};
 use serde::Deserialize;
 use std::sync::{
@@ -8,6 +9,7 @@ use std::sync::{
 
 use crate::{
     config::RadioBrowserConfig,
+    sources::StationSource,
     stations::{
         is_blocked_domain, sanitize_station_url, sanitize_stream_url, Station, StationCoordinates,
         StationsPayload, STATIONS_SCHEMA_VERSION,
@@ -217,6 +219,16 @@ impl RadioBrowserClient {
     }
 }
 
+impl StationSource for RadioBrowserClient {
+    fn name(&self) -> &'static str {
+        "radio-browser"
+    }
+
+    fn fetch(&self) -> BoxFuture<'_, anyhow::Result<StationsPayload>> {
+        Box::pin(self.fetch_payload())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct RadioBrowserStation {
     stationuuid: String,
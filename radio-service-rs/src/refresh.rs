@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use crate::logging::logger;
-use crate::{app_state::AppState, stations::StationsPayload};
+use crate::{app_state::AppState, sources, stations::StationsPayload};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use serde_json::json;
 
 pub struct RefreshResult {
@@ -11,7 +15,22 @@ pub struct RefreshResult {
 }
 
 pub async fn run_refresh(state: &AppState) -> anyhow::Result<RefreshResult> {
-    let mut payload = state.radio_browser.fetch_payload().await?;
+    let fetched = sources::fetch_all(&state.sources).await?;
+    // Persisting is a diff against the live catalogue, so a partial fetch would delete every
+    // station of the failed source. Keep serving the old catalogue until all sources answer.
+    if !fetched.failed.is_empty()
+        && state
+            .stations
+            .has_live_payload()
+            .await
+            .map_err(|err| anyhow::anyhow!(err))?
+    {
+        anyhow::bail!(
+            "station sources failed: {}; keeping the current catalogue",
+            fetched.failed.join(", ")
+        );
+    }
+    let mut payload = fetched.payload;
     let validation = state
         .stream_validator
         .validate(payload.stations.clone(), &state.postgres)
@@ -26,14 +45,35 @@ pub async fn run_refresh(state: &AppState) -> anyhow::Result<RefreshResult> {
         );
     }
     payload.stations = validation.stations;
+    if let Err(error) = state
+        .stations
+        .replace_quarantine(&validation.dropped_stations)
+        .await
+    {
+        logger().warn(
+            "stream.quarantine_error",
+            json!({ "error": error.to_string() }),
+        );
+    }
     let fingerprint = payload.ensure_fingerprint()?.to_string();
     let updated_at = payload.updated_at.to_rfc3339();
 
-    state
+    let outcome = state
         .stations
-        .persist_payload(&payload)
+        .persist_payload(&payload, true)
         .await
         .map_err(|err| anyhow::anyhow!(err))?;
+    if outcome.changed {
+        logger().info(
+            "stations.persisted",
+            json!({
+                "payloadId": outcome.payload_id,
+                "added": outcome.added,
+                "updated": outcome.updated,
+                "removed": outcome.removed,
+            }),
+        );
+    }
 
     Ok(RefreshResult {
         payload,
@@ -41,3 +81,56 @@ pub async fn run_refresh(state: &AppState) -> anyhow::Result<RefreshResult> {
         updated_at,
     })
 }
+
+/// What the refresh scheduler last did on this replica, for `/internal/status`.
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerStatus {
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_outcome: Option<SchedulerOutcome>,
+    pub last_error: Option<String>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SchedulerOutcome {
+    Refreshed,
+    /// Another replica held the refresh lock.
+    SkippedLocked,
+    /// Another replica refreshed recently enough that this run was not needed.
+    SkippedRecent,
+    Failed,
+}
+
+/// Interval plus a random share of `jitter`, so replicas started together spread their
+/// attempts instead of all contending for the lock at once.
+pub fn jittered_delay(interval: Duration, jitter: Duration, seed: u64) -> Duration {
+    let jitter_ms = u64::try_from(jitter.as_millis()).unwrap_or(u64::MAX);
+    if jitter_ms == 0 {
+        return interval;
+    }
+    let offset = jitter_ms.checked_add(1).map_or(seed, |span| seed % span);
+    interval.saturating_add(Duration::from_millis(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let interval = Duration::from_secs(60);
+        let jitter = Duration::from_secs(10);
+        for seed in [0, 1, 9_999, 10_000, 10_001, u64::MAX] {
+            let delay = jittered_delay(interval, jitter, seed);
+            assert!(delay >= interval && delay <= interval + jitter);
+        }
+        assert_eq!(jittered_delay(interval, Duration::ZERO, 42), interval);
+        let huge = Duration::from_millis(u64::MAX);
+        assert!(jittered_delay(interval, huge, u64::MAX) >= interval);
+        assert_ne!(
+            jittered_delay(interval, jitter, 1),
+            jittered_delay(interval, jitter, 2)
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::logging::logger;
+use crate::{
+    app_state::AppState,
+    stations::{Station, StationsPayload},
+};
+use serde_json::json;
+
+#[derive(Default)]
+pub struct RevalidationResult {
+    /// The updated live payload, when the pass dropped, restored or rewrote any station.
+    pub payload: Option<StationsPayload>,
+    /// Stations validated in this pass; each recorded a health check.
+    pub checked: usize,
+    pub dropped: usize,
+    pub restored: usize,
+}
+
+/// Re-checks the live and quarantined stations whose validation cache entry has expired, in
+/// one batch of `revalidation_batch_size`, and persists the resulting catalogue.
+pub async fn run_revalidation(
+    state: &AppState,
+    live: &StationsPayload,
+) -> anyhow::Result<RevalidationResult> {
+    let quarantined = state
+        .stations
+        .load_quarantined()
+        .await
+        .map_err(|err| anyhow::anyhow!(err))?;
+
+    let mut seen_urls = HashSet::new();
+    let candidate_urls: Vec<String> = live
+        .stations
+        .iter()
+        .chain(&quarantined)
+        .filter(|station| seen_urls.insert(station.stream_url.as_str()))
+        .map(|station| station.stream_url.clone())
+        .collect();
+    let expired: HashSet<String> = state
+        .stream_validator
+        .expired_stream_urls(
+            &state.postgres,
+            &candidate_urls,
+            state.config.stream_validation.revalidation_batch_size,
+        )
+        .await?
+        .into_iter()
+        .collect();
+    if expired.is_empty() {
+        return Ok(RevalidationResult::default());
+    }
+
+    // Live stations win over a stale quarantine entry with the same id.
+    let mut seen_ids = HashSet::new();
+    let batch: Vec<Station> = live
+        .stations
+        .iter()
+        .chain(&quarantined)
+        .filter(|station| expired.contains(&station.stream_url))
+        .filter(|station| seen_ids.insert(station.id.as_str()))
+        .cloned()
+        .collect();
+    let checked = batch.len();
+    let validation = state
+        .stream_validator
+        .validate(batch, &state.postgres)
+        .await?;
+
+    let merged = merge_revalidated(
+        &live.stations,
+        validation.stations,
+        &validation.dropped_stations,
+    );
+    state
+        .stations
+        .update_quarantine(&validation.dropped_stations, &merged.restored)
+        .await
+        .map_err(|err| anyhow::anyhow!(err))?;
+
+    let mut result = RevalidationResult {
+        payload: None,
+        checked,
+        dropped: merged.removed,
+        restored: merged.restored.len(),
+    };
+    if !merged.changed {
+        return Ok(result);
+    }
+
+    let mut payload = StationsPayload {
+        stations: merged.stations,
+        fingerprint: None,
+        ..live.clone()
+    };
+    payload.ensure_fingerprint()?;
+    let outcome = state
+        .stations
+        .persist_payload(&payload, false)
+        .await
+        .map_err(|err| anyhow::anyhow!(err))?;
+    logger().info(
+        "stream.revalidation",
+        json!({
+            "checked": checked,
+            "dropped": result.dropped,
+            "restored": result.restored,
+            "updated": outcome.updated,
+        }),
+    );
+    result.payload = Some(payload);
+    Ok(result)
+}
+
+struct MergedStations {
+    stations: Vec<Station>,
+    restored: Vec<String>,
+    removed: usize,
+    changed: bool,
+}
+
+/// Applies a validation batch to the live list: dropped stations leave it, re-validated ones
+/// take their (possibly redirected) stream URL, and accepted stations that were not live are
+/// restored at the end.
+fn merge_revalidated(
+    live: &[Station],
+    accepted: Vec<Station>,
+    dropped: &[(Station, String)],
+) -> MergedStations {
+    let dropped_ids: HashSet<&str> = dropped
+        .iter()
+        .map(|(station, _)| station.id.as_str())
+        .collect();
+    let mut accepted: HashMap<String, Station> = accepted
+        .into_iter()
+        .map(|station| (station.id.clone(), station))
+        .collect();
+
+    let mut stations = Vec::with_capacity(live.len() + accepted.len());
+    let mut removed = 0;
+    let mut changed = false;
+    for station in live {
+        if dropped_ids.contains(station.id.as_str()) {
+            removed += 1;
+            continue;
+        }
+        match accepted.remove(&station.id) {
+            Some(validated) => {
+                changed |=
+                    validated.stream_url != station.stream_url || validated.hls != station.hls;
+                stations.push(validated);
+            }
+            None => stations.push(station.clone()),
+        }
+    }
+
+    let mut restored: Vec<Station> = accepted.into_values().collect();
+    restored.sort_by(|a, b| a.id.cmp(&b.id));
+    let restored_ids = restored.iter().map(|station| station.id.clone()).collect();
+    changed |= removed > 0 || !restored.is_empty();
+    stations.extend(restored);
+
+    MergedStations {
+        stations,
+        restored: restored_ids,
+        removed,
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    #[test]
+    fn drops_failures_and_restores_recoveries() {
+        let live = ["a", "b", "c"].map(test_station);
+        let moved = Station {
+            stream_url: "https://a.example.com/moved".into(),
+            ..test_station("a")
+        };
+        let merged = merge_revalidated(
+            &live,
+            vec![moved, test_station("q")],
+            &[(test_station("b"), "timeout".into())],
+        );
+
+        let ids: Vec<_> = merged.stations.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "q"]);
+        assert_eq!(merged.stations[0].stream_url, "https://a.example.com/moved");
+        assert_eq!(merged.restored, vec!["q"]);
+        assert_eq!(merged.removed, 1);
+        assert!(merged.changed);
+
+        let unchanged = merge_revalidated(&live, vec![live[1].clone()], &[]);
+        assert_eq!(unchanged.stations.len(), 3);
+        assert!(!unchanged.changed);
+    }
+}
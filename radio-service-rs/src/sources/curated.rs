@@ -0,0 +1,275 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use super::StationSource;
+use crate::{
+    logging::logger,
+    playlist::{parse_m3u, parse_pls, PlaylistEntry},
+    stations::{
+        is_blocked_domain, sanitize_station_url, sanitize_stream_url, Station, StationsPayload,
+        STATIONS_SCHEMA_VERSION,
+    },
+};
+
+const CURATED_ID_PREFIX: &str = "curated-";
+
+/// Hand-picked stations read from `*.json`, `*.m3u`/`*.m3u8` and `*.pls` files in a local
+/// directory. The directory is re-read on every refresh, so edits ship without a restart.
+#[derive(Clone)]
+pub struct CuratedSource {
+    dir: PathBuf,
+    enforce_https: bool,
+    allow_insecure: bool,
+}
+
+/// A station in a curated JSON file. Only `name` and `streamUrl` are required.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CuratedStation {
+    id: Option<String>,
+    name: String,
+    stream_url: String,
+    homepage: Option<String>,
+    favicon: Option<String>,
+    country: Option<String>,
+    country_code: Option<String>,
+    state: Option<String>,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    bitrate: Option<i32>,
+    codec: Option<String>,
+    hls: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CuratedFile {
+    Many(Vec<CuratedStation>),
+    One(Box<CuratedStation>),
+}
+
+impl CuratedSource {
+    pub fn new(dir: impl Into<PathBuf>, enforce_https: bool, allow_insecure: bool) -> Self {
+        Self {
+            dir: dir.into(),
+            enforce_https,
+            allow_insecure,
+        }
+    }
+
+    fn load(&self) -> anyhow::Result<StationsPayload> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        let mut stations = Vec::new();
+        let mut requests = Vec::new();
+        for path in paths {
+            let Some(extension) = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(str::to_ascii_lowercase)
+            else {
+                continue;
+            };
+            let parsed = match extension.as_str() {
+                "json" => self.load_json(&path),
+                "m3u" | "m3u8" => fs::read_to_string(&path)
+                    .map(|text| self.playlist_stations(parse_m3u(&text)))
+                    .map_err(Into::into),
+                "pls" => fs::read_to_string(&path)
+                    .map(|text| self.playlist_stations(parse_pls(&text)))
+                    .map_err(Into::into),
+                _ => continue,
+            };
+            // One broken file should not take the rest of the directory down with it.
+            let parsed = match parsed {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    logger().warn(
+                        "stations.curated_file_error",
+                        json!({
+                            "path": path.display().to_string(),
+                            "error": error.to_string(),
+                        }),
+                    );
+                    continue;
+                }
+            };
+            requests.push(path.display().to_string());
+            stations.extend(parsed);
+        }
+
+        Ok(StationsPayload {
+            schema_version: Some(STATIONS_SCHEMA_VERSION),
+            updated_at: Utc::now(),
+            source: Some(format!("curated:{}", self.dir.display())),
+            requests,
+            total: stations.len(),
+            stations,
+            fingerprint: None,
+        })
+    }
+
+    fn load_json(&self, path: &Path) -> anyhow::Result<Vec<Station>> {
+        let file: CuratedFile = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+        let entries = match file {
+            CuratedFile::Many(entries) => entries,
+            CuratedFile::One(entry) => vec![*entry],
+        };
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| self.normalize(entry))
+            .collect())
+    }
+
+    fn playlist_stations(&self, entries: Vec<PlaylistEntry>) -> Vec<Station> {
+        entries
+            .into_iter()
+            .filter_map(|entry| {
+                let name = entry.title.clone().unwrap_or_else(|| entry.url.clone());
+                self.normalize(CuratedStation {
+                    id: entry.attribute("tvg-id").map(str::to_string),
+                    name,
+                    favicon: entry.attribute("tvg-logo").map(str::to_string),
+                    country: entry.attribute("tvg-country").map(str::to_string),
+                    languages: split_list(entry.attribute("tvg-language")),
+                    tags: split_list(entry.attribute("group-title")),
+                    stream_url: entry.url,
+                    homepage: None,
+                    country_code: None,
+                    state: None,
+                    bitrate: None,
+                    codec: None,
+                    hls: None,
+                })
+            })
+            .collect()
+    }
+
+    fn normalize(&self, entry: CuratedStation) -> Option<Station> {
+        let stream_url = sanitize_stream_url(&entry.stream_url)?;
+        if is_blocked_domain(&stream_url) || entry.name.trim().is_empty() {
+            return None;
+        }
+        let hls = entry
+            .hls
+            .unwrap_or_else(|| stream_url.to_ascii_lowercase().contains(".m3u8"));
+        Some(Station {
+            id: curated_id(entry.id.as_deref(), &stream_url),
+            name: entry.name.trim().to_string(),
+            homepage: sanitize_station_url(
+                entry.homepage.as_deref(),
+                self.enforce_https,
+                self.allow_insecure,
+            ),
+            favicon: sanitize_station_url(
+                entry.favicon.as_deref(),
+                self.enforce_https,
+                self.allow_insecure,
+            ),
+            stream_url,
+            country: entry.country,
+            country_code: entry.country_code.map(|code| code.to_ascii_uppercase()),
+            state: entry.state,
+            languages: entry.languages,
+            tags: entry.tags,
+            coordinates: None,
+            bitrate: entry.bitrate,
+            codec: entry.codec,
+            hls,
+            is_online: true,
+            last_checked_at: None,
+            last_changed_at: None,
+            click_count: 0,
+            click_trend: 0,
+            votes: 0,
+        })
+    }
+}
+
+impl StationSource for CuratedSource {
+    fn name(&self) -> &'static str {
+        "curated"
+    }
+
+    fn fetch(&self) -> BoxFuture<'_, anyhow::Result<StationsPayload>> {
+        let source = self.clone();
+        Box::pin(async move { tokio::task::spawn_blocking(move || source.load()).await? })
+    }
+}
+
+/// Curated ids are prefixed so they can never collide with Radio Browser UUIDs. Without an
+/// explicit id the stream URL keeps the id stable across edits to the rest of the entry.
+fn curated_id(explicit: Option<&str>, stream_url: &str) -> String {
+    let slug: String = explicit
+        .map(str::trim)
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+        .take(100)
+        .collect();
+    if slug.is_empty() {
+        let digest = hex::encode(Sha256::digest(stream_url.as_bytes()));
+        return format!("{CURATED_ID_PREFIX}{}", &digest[..16]);
+    }
+    format!("{CURATED_ID_PREFIX}{}", slug.to_ascii_lowercase())
+}
+
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_json_and_playlists_from_the_directory() {
+        let dir = std::env::temp_dir().join(format!("curated-source-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("picks.json"),
+            r#"[{"id": "Night Jazz", "name": "Night Jazz", "streamUrl": "https://jazz.example.com/live", "tags": ["jazz"]},
+                {"name": "Broken", "streamUrl": "ftp://broken.example.com"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("extra.m3u"),
+            "#EXTM3U\n#EXTINF:-1 group-title=\"News;Talk\",Talk Radio\nhttps://talk.example.com/stream.m3u8\n",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        fs::write(dir.join("typo.json"), "[{\"name\": ").unwrap();
+
+        let payload = CuratedSource::new(&dir, true, false).load().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let ids: Vec<_> = payload.stations.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids[0].starts_with(CURATED_ID_PREFIX));
+        assert_eq!(ids[1], "curated-nightjazz");
+        assert_eq!(payload.stations[0].tags, vec!["News", "Talk"]);
+        assert!(payload.stations[0].hls);
+        assert_eq!(payload.requests.len(), 2);
+    }
+}
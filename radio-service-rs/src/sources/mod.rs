@@ -0,0 +1,155 @@
+mod curated;
+
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::Utc;
+use futures_util::future::{join_all, BoxFuture};
+use serde_json::json;
+
+use crate::logging::logger;
+use crate::stations::{StationsPayload, STATIONS_SCHEMA_VERSION};
+
+pub use curated::CuratedSource;
+
+/// A provider of stations for a refresh. Sources are queried concurrently and merged in the
+/// order they are configured, so earlier sources win duplicates.
+pub trait StationSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn fetch(&self) -> BoxFuture<'_, anyhow::Result<StationsPayload>>;
+}
+
+pub type StationSources = Arc<Vec<Arc<dyn StationSource>>>;
+
+pub struct SourcesFetch {
+    pub payload: StationsPayload,
+    /// Names of the sources that failed; their stations are missing from `payload`.
+    pub failed: Vec<&'static str>,
+}
+
+/// Fetches every source and merges the results. Fails only when no source produced
+/// any station.
+pub async fn fetch_all(sources: &[Arc<dyn StationSource>]) -> anyhow::Result<SourcesFetch> {
+    let results = join_all(sources.iter().map(|source| source.fetch())).await;
+    let mut payloads = Vec::new();
+    let mut failed = Vec::new();
+    let mut last_error = None;
+    for (source, result) in sources.iter().zip(results) {
+        match result {
+            Ok(payload) => payloads.push(payload),
+            Err(error) => {
+                logger().warn(
+                    "stations.source_error",
+                    json!({
+                        "source": source.name(),
+                        "error": format!("{:?}", error),
+                    }),
+                );
+                failed.push(source.name());
+                last_error = Some(error);
+            }
+        }
+    }
+
+    let payload = merge_payloads(payloads);
+    if payload.stations.is_empty() {
+        return Err(
+            last_error.unwrap_or_else(|| anyhow::anyhow!("no station sources returned stations"))
+        );
+    }
+    Ok(SourcesFetch { payload, failed })
+}
+
+/// Concatenates payloads in priority order, keeping the first station for each id and for
+/// each stream URL.
+fn merge_payloads(payloads: Vec<StationsPayload>) -> StationsPayload {
+    if payloads.len() == 1 {
+        return payloads.into_iter().next().unwrap_or_else(empty_payload);
+    }
+
+    let mut merged = empty_payload();
+    let mut sources = Vec::new();
+    let mut seen_ids = HashSet::new();
+    let mut seen_urls = HashSet::new();
+    for payload in payloads {
+        sources.extend(payload.source);
+        merged.requests.extend(payload.requests);
+        for station in payload.stations {
+            let url_key = stream_url_key(&station.stream_url);
+            if seen_ids.contains(&station.id) || seen_urls.contains(&url_key) {
+                continue;
+            }
+            seen_ids.insert(station.id.clone());
+            seen_urls.insert(url_key);
+            merged.stations.push(station);
+        }
+    }
+    merged.source = (!sources.is_empty()).then(|| sources.join(" + "));
+    merged.total = merged.stations.len();
+    merged
+}
+
+fn empty_payload() -> StationsPayload {
+    StationsPayload {
+        schema_version: Some(STATIONS_SCHEMA_VERSION),
+        updated_at: Utc::now(),
+        source: None,
+        requests: Vec::new(),
+        total: 0,
+        stations: Vec::new(),
+        fingerprint: None,
+    }
+}
+
+/// Stream URLs that only differ in scheme case, host case or a trailing slash are the same
+/// stream.
+pub fn stream_url_key(stream_url: &str) -> String {
+    match url::Url::parse(stream_url) {
+        Ok(parsed) => parsed.as_str().trim_end_matches('/').to_string(),
+        Err(_) => stream_url.trim().trim_end_matches('/').to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::{test_station, Station};
+
+    fn payload(source: &str, stations: Vec<Station>) -> StationsPayload {
+        StationsPayload {
+            source: Some(source.into()),
+            total: stations.len(),
+            stations,
+            ..empty_payload()
+        }
+    }
+
+    #[test]
+    fn earlier_sources_win_duplicates() {
+        let merged = merge_payloads(vec![
+            payload(
+                "curated",
+                vec![Station {
+                    stream_url: "https://Jazz.example.com/live/".into(),
+                    ..test_station("curated-jazz")
+                }],
+            ),
+            payload(
+                "radio-browser",
+                vec![
+                    Station {
+                        stream_url: "https://jazz.example.com/live".into(),
+                        ..test_station("uuid-1")
+                    },
+                    test_station("curated-jazz"),
+                    test_station("uuid-2"),
+                ],
+            ),
+        ]);
+
+        let ids: Vec<_> = merged.stations.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["curated-jazz", "uuid-2"]);
+        assert_eq!(merged.total, 2);
+        assert_eq!(merged.source.as_deref(), Some("curated + radio-browser"));
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::Station;
+
+const MAX_FACET_VALUES: usize = 50;
+
+/// Bucket label with its inclusive bounds, so a client can turn a bucket straight into
+/// `minBitrate`/`maxBitrate` filters.
+const BITRATE_BUCKETS: [(&str, Option<i32>, Option<i32>); 5] = [
+    ("<64", None, Some(63)),
+    ("64-127", Some(64), Some(127)),
+    ("128-191", Some(128), Some(191)),
+    ("192-255", Some(192), Some(255)),
+    ("256+", Some(256), None),
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FacetCounts {
+    pub countries: Vec<FacetValue>,
+    pub languages: Vec<FacetValue>,
+    pub tags: Vec<FacetValue>,
+    pub codecs: Vec<FacetValue>,
+    pub bitrates: Vec<BitrateFacet>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BitrateFacet {
+    pub value: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i32>,
+    pub count: usize,
+}
+
+#[derive(Default)]
+struct Dimension {
+    labels: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Dimension {
+    fn intern(&mut self, value: &str) -> Option<u32> {
+        let label = value.trim();
+        if label.is_empty() {
+            return None;
+        }
+        let key = label.to_lowercase();
+        if let Some(id) = self.ids.get(&key) {
+            return Some(*id);
+        }
+        let id = self.labels.len() as u32;
+        self.labels.push(label.to_string());
+        self.ids.insert(key, id);
+        Some(id)
+    }
+
+    fn ranked(&self, counts: HashMap<u32, usize>) -> Vec<FacetValue> {
+        let mut values: Vec<FacetValue> = counts
+            .into_iter()
+            .map(|(id, count)| FacetValue {
+                value: self.labels[id as usize].clone(),
+                count,
+            })
+            .collect();
+        values.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        values.truncate(MAX_FACET_VALUES);
+        values
+    }
+}
+
+#[derive(Default)]
+struct StationFacets {
+    country: Option<u32>,
+    languages: Vec<u32>,
+    tags: Vec<u32>,
+    codec: Option<u32>,
+    bitrate_bucket: Option<usize>,
+}
+
+/// Per-station facet ids interned once per payload, so counting a result set is a walk over
+/// small integers rather than re-normalizing strings.
+#[derive(Default)]
+pub struct FacetIndex {
+    countries: Dimension,
+    languages: Dimension,
+    tags: Dimension,
+    codecs: Dimension,
+    stations: Vec<StationFacets>,
+    global: FacetCounts,
+}
+
+impl FacetIndex {
+    pub fn build(stations: &[Station]) -> Self {
+        let mut index = Self::default();
+        for station in stations {
+            let mut languages: Vec<u32> = station
+                .languages
+                .iter()
+                .filter_map(|language| index.languages.intern(language))
+                .collect();
+            languages.sort_unstable();
+            languages.dedup();
+            let mut tags: Vec<u32> = station
+                .tags
+                .iter()
+                .filter_map(|tag| index.tags.intern(tag))
+                .collect();
+            tags.sort_unstable();
+            tags.dedup();
+            let facets = StationFacets {
+                country: station
+                    .country
+                    .as_deref()
+                    .and_then(|country| index.countries.intern(country)),
+                languages,
+                tags,
+                codec: station
+                    .codec
+                    .as_deref()
+                    .and_then(|codec| index.codecs.intern(codec)),
+                bitrate_bucket: station.bitrate.and_then(bitrate_bucket),
+            };
+            index.stations.push(facets);
+        }
+        let all: Vec<usize> = (0..stations.len()).collect();
+        index.global = index.count_indexes(&all);
+        index
+    }
+
+    /// Facet counts over the given result set. The unfiltered set reuses the counts computed
+    /// at build time.
+    pub fn count(&self, indexes: &[usize]) -> FacetCounts {
+        if indexes.len() == self.stations.len() {
+            return self.global.clone();
+        }
+        self.count_indexes(indexes)
+    }
+
+    fn count_indexes(&self, indexes: &[usize]) -> FacetCounts {
+        let mut countries: HashMap<u32, usize> = HashMap::new();
+        let mut languages: HashMap<u32, usize> = HashMap::new();
+        let mut tags: HashMap<u32, usize> = HashMap::new();
+        let mut codecs: HashMap<u32, usize> = HashMap::new();
+        let mut bitrates = [0usize; BITRATE_BUCKETS.len()];
+
+        for facets in indexes.iter().filter_map(|idx| self.stations.get(*idx)) {
+            if let Some(country) = facets.country {
+                *countries.entry(country).or_default() += 1;
+            }
+            for language in &facets.languages {
+                *languages.entry(*language).or_default() += 1;
+            }
+            for tag in &facets.tags {
+                *tags.entry(*tag).or_default() += 1;
+            }
+            if let Some(codec) = facets.codec {
+                *codecs.entry(codec).or_default() += 1;
+            }
+            if let Some(bucket) = facets.bitrate_bucket {
+                bitrates[bucket] += 1;
+            }
+        }
+
+        FacetCounts {
+            countries: self.countries.ranked(countries),
+            languages: self.languages.ranked(languages),
+            tags: self.tags.ranked(tags),
+            codecs: self.codecs.ranked(codecs),
+            bitrates: BITRATE_BUCKETS
+                .iter()
+                .zip(bitrates)
+                .filter(|(_, count)| *count > 0)
+                .map(|((value, min, max), count)| BitrateFacet {
+                    value,
+                    min: *min,
+                    max: *max,
+                    count,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn bitrate_bucket(bitrate: i32) -> Option<usize> {
+    if bitrate <= 0 {
+        return None;
+    }
+    BITRATE_BUCKETS.iter().position(|(_, min, max)| {
+        min.is_none_or(|min| bitrate >= min) && max.is_none_or(|max| bitrate <= max)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    #[test]
+    fn counts_only_the_given_result_set() {
+        let index = FacetIndex::build(&[
+            Station {
+                country: Some("Sweden".into()),
+                tags: vec!["Jazz".into(), "pop".into()],
+                bitrate: Some(128),
+                codec: Some("MP3".into()),
+                ..test_station("se-1")
+            },
+            Station {
+                country: Some("sweden".into()),
+                tags: vec!["jazz".into()],
+                bitrate: Some(320),
+                codec: Some("MP3".into()),
+                ..test_station("se-2")
+            },
+            Station {
+                country: Some("Norway".into()),
+                tags: vec!["rock".into()],
+                codec: Some("MP3".into()),
+                ..test_station("no-1")
+            },
+        ]);
+
+        let facets = index.count(&[0, 1]);
+        assert_eq!(facets.countries.len(), 1);
+        assert_eq!(facets.countries[0].value, "Sweden");
+        assert_eq!(facets.countries[0].count, 2);
+        assert_eq!(facets.tags[0].value, "Jazz");
+        assert_eq!(facets.tags[0].count, 2);
+        assert!(facets.tags.iter().all(|tag| tag.value != "rock"));
+        let buckets: Vec<_> = facets.bitrates.iter().map(|bucket| bucket.value).collect();
+        assert_eq!(buckets, vec!["128-191", "256+"]);
+
+        let global = index.count(&[0, 1, 2]);
+        assert_eq!(global.countries.len(), 2);
+        assert_eq!(global.codecs[0].count, 3);
+        assert!(index.count(&[]).tags.is_empty());
+    }
+}
@@ -21,6 +21,13 @@ pub fn build_stations_fingerprint(stations: &[Station]) -> Result<String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
+/// Content hash of a single station, stored alongside it so refreshes can tell which rows
+/// actually changed.
+pub fn build_station_content_signature(station: &Station) -> Result<String> {
+    let serialized = serde_json::to_vec(station)?;
+    Ok(hex::encode(Sha256::digest(serialized)))
+}
+
 /// Build a fingerprint that is sensitive to the ordering of `stations`.
 ///
 /// This is intentionally different from [`build_stations_fingerprint`]. We use it to
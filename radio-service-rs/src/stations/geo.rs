@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use super::Station;
+
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+const KM_PER_DEGREE: f64 = EARTH_RADIUS_KM * std::f64::consts::PI / 180.0;
+const CELL_DEGREES: f64 = 1.0;
+const LON_CELLS: i32 = (360.0 / CELL_DEGREES) as i32;
+
+/// Fixed-size lat/lon grid over station coordinates. A radius query only measures the stations
+/// in cells overlapping the query's bounding box instead of every station in the payload.
+#[derive(Default)]
+pub struct GeoIndex {
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    points: HashMap<usize, (f64, f64)>,
+}
+
+impl GeoIndex {
+    pub fn build(stations: &[Station]) -> Self {
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        let mut points = HashMap::new();
+        for (idx, station) in stations.iter().enumerate() {
+            let Some(coordinates) = &station.coordinates else {
+                continue;
+            };
+            let (lat, lon) = (coordinates.lat, coordinates.lon);
+            if !is_valid_point(lat, lon) {
+                continue;
+            }
+            cells.entry(cell_for(lat, lon)).or_default().push(idx);
+            points.insert(idx, (lat, lon));
+        }
+        Self { cells, points }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns `(station index, distance in km)` for every station within `radius_km` of the
+    /// given point, nearest first.
+    pub fn within(&self, lat: f64, lon: f64, radius_km: f64) -> Vec<(usize, f64)> {
+        let lat_span = radius_km / KM_PER_DEGREE;
+        let min_lat = (lat - lat_span).max(-90.0);
+        let max_lat = (lat + lat_span).min(90.0);
+        let lat_cells = lat_cell(min_lat)..=lat_cell(max_lat);
+
+        // Longitude degrees shrink towards the poles, so widen the box by the narrowest latitude
+        // it touches. Near a pole every longitude is in range.
+        let widest_lat = min_lat.abs().max(max_lat.abs());
+        let lon_cells: Vec<i32> = if widest_lat >= 89.0 {
+            (0..LON_CELLS).collect()
+        } else {
+            let lon_span = lat_span / widest_lat.to_radians().cos();
+            if lon_span >= 180.0 {
+                (0..LON_CELLS).collect()
+            } else {
+                let first = ((lon - lon_span) / CELL_DEGREES).floor() as i32;
+                let last = ((lon + lon_span) / CELL_DEGREES).floor() as i32;
+                let mut cells: Vec<i32> = (first..=last).map(wrap_lon_cell).collect();
+                cells.sort_unstable();
+                cells.dedup();
+                cells
+            }
+        };
+
+        let mut matches = Vec::new();
+        for lat_cell in lat_cells {
+            for lon_cell in &lon_cells {
+                let Some(indexes) = self.cells.get(&(lat_cell, *lon_cell)) else {
+                    continue;
+                };
+                for idx in indexes {
+                    let (station_lat, station_lon) = self.points[idx];
+                    let distance = haversine_km(lat, lon, station_lat, station_lon);
+                    if distance <= radius_km {
+                        matches.push((*idx, distance));
+                    }
+                }
+            }
+        }
+        matches.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        matches
+    }
+}
+
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+fn is_valid_point(lat: f64, lon: f64) -> bool {
+    // Radio Browser reports 0,0 for a fair number of stations that never set a location.
+    lat.is_finite()
+        && lon.is_finite()
+        && (-90.0..=90.0).contains(&lat)
+        && (-180.0..=180.0).contains(&lon)
+        && !(lat == 0.0 && lon == 0.0)
+}
+
+fn cell_for(lat: f64, lon: f64) -> (i32, i32) {
+    (
+        lat_cell(lat),
+        wrap_lon_cell((lon / CELL_DEGREES).floor() as i32),
+    )
+}
+
+fn lat_cell(lat: f64) -> i32 {
+    (lat / CELL_DEGREES).floor() as i32
+}
+
+fn wrap_lon_cell(cell: i32) -> i32 {
+    cell.rem_euclid(LON_CELLS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::{test_station, StationCoordinates};
+
+    #[test]
+    fn haversine_matches_known_distance() {
+        // Stockholm to Gothenburg is roughly 398 km as the crow flies.
+        let distance = haversine_km(59.3293, 18.0686, 57.7089, 11.9746);
+        assert!((distance - 398.0).abs() < 5.0, "{distance}");
+    }
+
+    #[test]
+    fn finds_nearest_first_and_across_the_antimeridian() {
+        let index = GeoIndex::build(&[
+            Station {
+                coordinates: Some(StationCoordinates {
+                    lat: 57.7089,
+                    lon: 11.9746,
+                }),
+                ..test_station("gothenburg")
+            },
+            Station {
+                coordinates: Some(StationCoordinates {
+                    lat: 59.8586,
+                    lon: 17.6389,
+                }),
+                ..test_station("uppsala")
+            },
+            Station {
+                coordinates: Some(StationCoordinates { lat: 0.0, lon: 0.0 }),
+                ..test_station("null-island")
+            },
+            Station {
+                coordinates: Some(StationCoordinates {
+                    lat: -17.8,
+                    lon: 179.9,
+                }),
+                ..test_station("fiji-east")
+            },
+            Station {
+                coordinates: Some(StationCoordinates {
+                    lat: -17.8,
+                    lon: -179.9,
+                }),
+                ..test_station("fiji-west")
+            },
+        ]);
+        assert_eq!(index.len(), 4);
+
+        let near_stockholm: Vec<_> = index
+            .within(59.3293, 18.0686, 500.0)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(near_stockholm, vec![1, 0]);
+        assert_eq!(index.within(59.3293, 18.0686, 100.0).len(), 1);
+
+        let fiji: Vec<_> = index
+            .within(-17.8, 179.95, 50.0)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(fiji.len(), 2);
+        assert!(index.within(0.0, 0.0, 10.0).is_empty());
+    }
+}
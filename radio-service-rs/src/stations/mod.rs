@@ -1,18 +1,58 @@
 #![allow(dead_code)]
+mod facets;
 mod fingerprint;
+mod geo;
 mod models;
+mod ordering;
+mod overrides;
 mod persisted;
 mod processed;
 mod sanitize;
+mod search;
+mod similar;
 mod storage;
 
+pub use facets::FacetCounts;
+pub use fingerprint::build_station_content_signature;
 pub use fingerprint::build_stations_fingerprint;
 pub use fingerprint::build_stations_order_fingerprint;
 pub use models::{Station, StationCoordinates, StationsPayload, STATIONS_SCHEMA_VERSION};
+pub use ordering::{SortOrder, StationSort};
+pub use overrides::{
+    apply_change_overrides, apply_station_overrides, StationOverride, StationPatch,
+};
 pub use persisted::sanitize_persisted_payload;
-pub use processed::{intersect_lists, ProcessedStations};
+pub use processed::{intersect_lists, union_lists, ProcessedStations};
 pub use sanitize::{is_blocked_domain, sanitize_station_url, sanitize_stream_url};
-pub use storage::StationStorage;
+pub use storage::{ChangeCursor, PayloadHistoryEntry, StationStorage};
+
+/// A minimal online station for tests; set the fields a test cares about with struct-update
+/// syntax.
+#[cfg(test)]
+pub(crate) fn test_station(id: &str) -> Station {
+    Station {
+        id: id.into(),
+        name: id.into(),
+        stream_url: format!("https://{id}.example.com/stream"),
+        homepage: None,
+        favicon: None,
+        country: None,
+        country_code: None,
+        state: None,
+        languages: vec![],
+        tags: vec![],
+        coordinates: None,
+        bitrate: None,
+        codec: None,
+        hls: false,
+        is_online: true,
+        last_checked_at: None,
+        last_changed_at: None,
+        click_count: 0,
+        click_trend: 0,
+        votes: 0,
+    }
+}
 
 pub fn build_station_signature(station: &Station) -> String {
     format!(
@@ -0,0 +1,256 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+};
+
+use super::{search::fold, Station};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StationSort {
+    Votes,
+    ClickTrend,
+    Bitrate,
+    Name,
+    LastChanged,
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+const ALL_SORTS: [StationSort; 6] = [
+    StationSort::Votes,
+    StationSort::ClickTrend,
+    StationSort::Bitrate,
+    StationSort::Name,
+    StationSort::LastChanged,
+    StationSort::Random,
+];
+
+impl StationSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "votes" => Some(Self::Votes),
+            "clicktrend" => Some(Self::ClickTrend),
+            "bitrate" => Some(Self::Bitrate),
+            "name" => Some(Self::Name),
+            "lastchanged" => Some(Self::LastChanged),
+            "random" => Some(Self::Random),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Votes => "votes",
+            Self::ClickTrend => "clickTrend",
+            Self::Bitrate => "bitrate",
+            Self::Name => "name",
+            Self::LastChanged => "lastChanged",
+            Self::Random => "random",
+        }
+    }
+
+    /// Names read A→Z; everything else is most useful biggest/newest first.
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            Self::Name => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+}
+
+/// Rank of every station under each sort key and direction, computed once per payload. Sorting
+/// a filtered page is then an integer sort over the ranks instead of re-comparing stations.
+/// Stations missing the sort value (no bitrate, never changed) rank last in both directions.
+#[derive(Default)]
+pub struct StationOrderings {
+    ranks: HashMap<(StationSort, SortOrder), Vec<u32>>,
+    random_seed: u64,
+}
+
+impl StationOrderings {
+    pub fn build(stations: &[Station]) -> Self {
+        let mut ranks = HashMap::new();
+        let state = RandomState::new();
+        let random_seed = state.hash_one("random-order");
+        for sort in ALL_SORTS {
+            if sort == StationSort::Random {
+                // Shuffled once per payload so pagination stays consistent between requests.
+                let keys: Vec<u64> = stations
+                    .iter()
+                    .map(|station| state.hash_one(&station.id))
+                    .collect();
+                let ascending = rank_by(stations.len(), |a, b| keys[a].cmp(&keys[b]));
+                let last = ascending.len().saturating_sub(1) as u32;
+                let descending = ascending.iter().map(|rank| last - rank).collect();
+                ranks.insert((sort, SortOrder::Desc), descending);
+                ranks.insert((sort, SortOrder::Asc), ascending);
+                continue;
+            }
+            let names: Vec<String> = if sort == StationSort::Name {
+                stations
+                    .iter()
+                    .map(|station| fold(station.name.trim()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            for order in [SortOrder::Asc, SortOrder::Desc] {
+                let ranked = rank_by(stations.len(), |a, b| {
+                    let (left, right) = (&stations[a], &stations[b]);
+                    match sort {
+                        StationSort::Votes => directed(left.votes.cmp(&right.votes), order),
+                        StationSort::ClickTrend => {
+                            directed(left.click_trend.cmp(&right.click_trend), order)
+                        }
+                        StationSort::Bitrate => {
+                            missing_last(left.bitrate, right.bitrate, order, |a, b| a.cmp(&b))
+                        }
+                        StationSort::Name => directed(names[a].cmp(&names[b]), order),
+                        StationSort::LastChanged => missing_last(
+                            left.last_changed_at.as_deref(),
+                            right.last_changed_at.as_deref(),
+                            order,
+                            |a, b| a.cmp(b),
+                        ),
+                        StationSort::Random => Ordering::Equal,
+                    }
+                });
+                ranks.insert((sort, order), ranked);
+            }
+        }
+        Self { ranks, random_seed }
+    }
+
+    /// Identifies this build's random shuffle, which differs between rebuilds and replicas.
+    pub fn random_seed(&self) -> u64 {
+        self.random_seed
+    }
+
+    /// Reorders `indexes` by the precomputed rank. Equal values keep payload order.
+    pub fn sort(&self, sort: StationSort, order: SortOrder, indexes: &mut [usize]) {
+        let Some(ranks) = self.ranks.get(&(sort, order)) else {
+            return;
+        };
+        indexes.sort_by_key(|idx| ranks.get(*idx).copied().unwrap_or(u32::MAX));
+    }
+}
+
+fn rank_by(len: usize, compare: impl Fn(usize, usize) -> Ordering) -> Vec<u32> {
+    let mut order: Vec<usize> = (0..len).collect();
+    order.sort_by(|a, b| compare(*a, *b).then(a.cmp(b)));
+    invert(&order.into_iter().map(|idx| idx as u32).collect::<Vec<_>>())
+}
+
+/// Turns a position→index list into an index→position list.
+fn invert(order: &[u32]) -> Vec<u32> {
+    let mut ranks = vec![0; order.len()];
+    for (position, idx) in order.iter().enumerate() {
+        ranks[*idx as usize] = position as u32;
+    }
+    ranks
+}
+
+fn directed(ordering: Ordering, order: SortOrder) -> Ordering {
+    match order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    }
+}
+
+fn missing_last<T>(
+    left: Option<T>,
+    right: Option<T>,
+    order: SortOrder,
+    compare: impl Fn(T, T) -> Ordering,
+) -> Ordering {
+    match (left, right) {
+        (Some(a), Some(b)) => directed(compare(a, b), order),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    #[test]
+    fn sorts_by_rank_with_missing_values_last() {
+        let stations = [
+            Station {
+                name: "Zeta".into(),
+                votes: 5,
+                ..test_station("a")
+            },
+            Station {
+                name: "Ålborg FM".into(),
+                bitrate: Some(128),
+                votes: 50,
+                ..test_station("b")
+            },
+            Station {
+                name: "alpha".into(),
+                bitrate: Some(320),
+                votes: 5,
+                ..test_station("c")
+            },
+        ];
+        let orderings = StationOrderings::build(&stations);
+        let sorted = |sort, order| {
+            let mut indexes = vec![0, 1, 2];
+            orderings.sort(sort, order, &mut indexes);
+            indexes
+        };
+
+        assert_eq!(sorted(StationSort::Votes, SortOrder::Desc), vec![1, 0, 2]);
+        assert_eq!(sorted(StationSort::Votes, SortOrder::Asc), vec![0, 2, 1]);
+        assert_eq!(sorted(StationSort::Bitrate, SortOrder::Desc), vec![2, 1, 0]);
+        assert_eq!(sorted(StationSort::Bitrate, SortOrder::Asc), vec![1, 2, 0]);
+        assert_eq!(sorted(StationSort::Name, SortOrder::Asc), vec![1, 2, 0]);
+
+        let mut random = sorted(StationSort::Random, SortOrder::Asc);
+        assert_eq!(random, sorted(StationSort::Random, SortOrder::Asc));
+        random.sort_unstable();
+        assert_eq!(random, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn parses_query_values() {
+        assert_eq!(
+            StationSort::parse("clickTrend"),
+            Some(StationSort::ClickTrend)
+        );
+        assert_eq!(
+            StationSort::parse("LASTCHANGED"),
+            Some(StationSort::LastChanged)
+        );
+        assert_eq!(StationSort::parse("popularity"), None);
+        assert_eq!(SortOrder::parse("Desc"), Some(SortOrder::Desc));
+        assert_eq!(StationSort::Name.default_order(), SortOrder::Asc);
+    }
+}
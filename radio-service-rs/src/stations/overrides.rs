@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{storage::StationChanges, Station, StationsPayload};
+
+/// Fields an admin can correct on a station. Unset fields keep the upstream value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StationPatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl StationPatch {
+    fn apply(&self, station: &mut Station) {
+        if let Some(name) = &self.name {
+            station.name = name.clone();
+        }
+        if let Some(homepage) = &self.homepage {
+            station.homepage = Some(homepage.clone());
+        }
+        if let Some(favicon) = &self.favicon {
+            station.favicon = Some(favicon.clone());
+        }
+        if let Some(country) = &self.country {
+            station.country = Some(country.clone());
+        }
+        if let Some(country_code) = &self.country_code {
+            station.country_code = Some(country_code.clone());
+        }
+        if let Some(state) = &self.state {
+            station.state = Some(state.clone());
+        }
+        if let Some(languages) = &self.languages {
+            station.languages = languages.clone();
+        }
+        if let Some(tags) = &self.tags {
+            station.tags = tags.clone();
+        }
+    }
+}
+
+/// An admin edit that survives refreshes: the upstream station is hidden, pinned to the top
+/// of the default order, and/or patched every time a payload is served.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StationOverride {
+    pub station_id: String,
+    pub hidden: bool,
+    pub pinned: bool,
+    pub patch: StationPatch,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Applies overrides to a payload loaded from upstream or the database. Pinned stations move
+/// to the front in their existing relative order. `state_updated_at` is the station state
+/// marker every override write bumps; the payload takes it as its `updated_at` when newer, so
+/// conditional requests notice an override change, including the removal of the newest one.
+pub fn apply_station_overrides(
+    payload: &mut StationsPayload,
+    overrides: &[StationOverride],
+    state_updated_at: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    if let Some(state_updated_at) = state_updated_at {
+        payload.updated_at = payload.updated_at.max(state_updated_at);
+    }
+    if overrides.is_empty() {
+        return Ok(());
+    }
+    payload.stations = override_stations(std::mem::take(&mut payload.stations), overrides);
+    payload.total = payload.stations.len();
+
+    payload.fingerprint = None;
+    payload.ensure_fingerprint()?;
+    Ok(())
+}
+
+/// Applies overrides to a `/stations/changes` delta the way `apply_station_overrides` applies
+/// them to the full list: hidden stations move from `upserted` to `removed`, the rest are
+/// patched, and pinned ones lead.
+pub fn apply_change_overrides(changes: &mut StationChanges, overrides: &[StationOverride]) {
+    if overrides.is_empty() {
+        return;
+    }
+    let hidden: HashSet<&str> = overrides
+        .iter()
+        .filter(|entry| entry.hidden)
+        .map(|entry| entry.station_id.as_str())
+        .collect();
+    changes.removed.extend(
+        changes
+            .upserted
+            .iter()
+            .filter(|station| hidden.contains(station.id.as_str()))
+            .map(|station| station.id.clone()),
+    );
+    changes.upserted = override_stations(std::mem::take(&mut changes.upserted), overrides);
+    changes.removed.sort_unstable();
+}
+
+/// Drops hidden stations, patches the rest, and moves pinned ones to the front in their
+/// existing relative order.
+fn override_stations(stations: Vec<Station>, overrides: &[StationOverride]) -> Vec<Station> {
+    let by_id: HashMap<&str, &StationOverride> = overrides
+        .iter()
+        .map(|entry| (entry.station_id.as_str(), entry))
+        .collect();
+
+    let mut pinned = Vec::new();
+    let mut rest = Vec::with_capacity(stations.len());
+    for mut station in stations {
+        match by_id.get(station.id.as_str()) {
+            Some(entry) if entry.hidden => continue,
+            Some(entry) => {
+                entry.patch.apply(&mut station);
+                if entry.pinned {
+                    pinned.push(station);
+                } else {
+                    rest.push(station);
+                }
+            }
+            None => rest.push(station),
+        }
+    }
+    pinned.extend(rest);
+    pinned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    fn entry(id: &str, hidden: bool, pinned: bool, patch: StationPatch) -> StationOverride {
+        StationOverride {
+            station_id: id.into(),
+            hidden,
+            pinned,
+            patch,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn hides_pins_and_patches_stations() {
+        let mut payload = StationsPayload {
+            schema_version: None,
+            updated_at: DateTime::<Utc>::MIN_UTC,
+            source: None,
+            requests: vec![],
+            total: 4,
+            stations: ["a", "b", "c", "d"].map(test_station).to_vec(),
+            fingerprint: Some("stale".into()),
+        };
+        let overrides = [
+            entry("b", true, true, StationPatch::default()),
+            entry(
+                "d",
+                false,
+                true,
+                StationPatch {
+                    name: Some("Delta FM".into()),
+                    tags: Some(vec!["jazz".into()]),
+                    ..StationPatch::default()
+                },
+            ),
+            entry("c", false, true, StationPatch::default()),
+            entry("missing", true, false, StationPatch::default()),
+        ];
+
+        let state_updated_at = Utc::now();
+        apply_station_overrides(&mut payload, &overrides, Some(state_updated_at)).unwrap();
+
+        let ids: Vec<_> = payload.stations.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "d", "a"]);
+        assert_eq!(payload.total, 3);
+        assert_eq!(payload.stations[1].name, "Delta FM");
+        assert_eq!(payload.stations[1].tags, vec!["jazz"]);
+        assert_ne!(payload.fingerprint.as_deref(), Some("stale"));
+        assert_eq!(payload.updated_at, state_updated_at);
+
+        // Deleting the last override still moves the payload forward.
+        let later = state_updated_at + chrono::Duration::seconds(1);
+        apply_station_overrides(&mut payload, &[], Some(later)).unwrap();
+        assert_eq!(payload.updated_at, later);
+    }
+    #[test]
+    fn hidden_stations_leave_the_change_feed() {
+        let mut changes = StationChanges {
+            fingerprint: Some("current".into()),
+            change_sets: 2,
+            upserted: ["a", "b", "c"].map(test_station).to_vec(),
+            removed: vec!["z".into()],
+        };
+        let overrides = [
+            entry("a", true, false, StationPatch::default()),
+            entry(
+                "c",
+                false,
+                true,
+                StationPatch {
+                    name: Some("Charlie FM".into()),
+                    ..StationPatch::default()
+                },
+            ),
+        ];
+
+        apply_change_overrides(&mut changes, &overrides);
+
+        let ids: Vec<_> = changes.upserted.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b"]);
+        assert_eq!(changes.upserted[0].name, "Charlie FM");
+        assert_eq!(changes.removed, vec!["a", "z"]);
+    }
+}
@@ -1,8 +1,18 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
 
 use serde::Serialize;
 
-use super::Station;
+use super::{
+    facets::{FacetCounts, FacetIndex},
+    geo::GeoIndex,
+    ordering::{SortOrder, StationOrderings, StationSort},
+    search::SearchIndex,
+    similar::SimilarityIndex,
+    Station,
+};
 
 const MAX_GENRES: usize = 200;
 
@@ -11,11 +21,26 @@ pub struct ProcessedStations {
     pub station_count: usize,
     pub countries: Vec<String>,
     pub genres: Vec<String>,
-    pub search_texts: Vec<String>,
+    #[serde(skip)]
+    search_index: Arc<SearchIndex>,
+    #[serde(skip)]
+    geo_index: Arc<GeoIndex>,
+    #[serde(skip)]
+    orderings: Arc<StationOrderings>,
+    #[serde(skip)]
+    facets: Arc<FacetIndex>,
+    #[serde(skip)]
+    similarity: Arc<SimilarityIndex>,
     station_index_by_id: HashMap<String, usize>,
     index_by_country: HashMap<String, Vec<usize>>,
     index_by_language: HashMap<String, Vec<usize>>,
     index_by_tag: HashMap<String, Vec<usize>>,
+    index_by_codec: HashMap<String, Vec<usize>>,
+    hls_indexes: Vec<usize>,
+    non_hls_indexes: Vec<usize>,
+    online_indexes: Vec<usize>,
+    offline_indexes: Vec<usize>,
+    bitrate_order: Vec<(i32, usize)>,
 }
 
 impl ProcessedStations {
@@ -25,8 +50,13 @@ impl ProcessedStations {
         let mut index_by_country: HashMap<String, Vec<usize>> = HashMap::new();
         let mut index_by_language: HashMap<String, Vec<usize>> = HashMap::new();
         let mut index_by_tag: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut index_by_codec: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut hls_indexes = Vec::new();
+        let mut non_hls_indexes = Vec::new();
+        let mut online_indexes = Vec::new();
+        let mut offline_indexes = Vec::new();
+        let mut bitrate_order = Vec::new();
         let mut station_index_by_id: HashMap<String, usize> = HashMap::new();
-        let mut search_texts = Vec::with_capacity(stations.len());
 
         for (idx, station) in stations.iter().enumerate() {
             station_index_by_id.insert(station.id.clone(), idx);
@@ -70,20 +100,26 @@ impl ProcessedStations {
                 }
             }
 
-            let mut search_parts = Vec::new();
-            search_parts.push(station.name.to_lowercase());
-            for tag in &station.tags {
-                search_parts.push(tag.to_lowercase());
+            if let Some(codec) = station.codec.as_deref().and_then(normalize_token) {
+                index_by_codec.entry(codec).or_default().push(idx);
             }
-            for language in &station.languages {
-                search_parts.push(language.to_lowercase());
+            if station.hls {
+                hls_indexes.push(idx);
+            } else {
+                non_hls_indexes.push(idx);
+            }
+            if station.is_online {
+                online_indexes.push(idx);
+            } else {
+                offline_indexes.push(idx);
             }
-            if let Some(country) = &station.country {
-                search_parts.push(country.to_lowercase());
+            if let Some(bitrate) = station.bitrate.filter(|value| *value > 0) {
+                bitrate_order.push((bitrate, idx));
             }
-            search_texts.push(search_parts.join(" "));
         }
 
+        bitrate_order.sort_unstable();
+
         let mut genres: Vec<_> = genre_counts.into_values().collect();
         genres.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
         let genres = genres
@@ -96,11 +132,21 @@ impl ProcessedStations {
             station_count: stations.len(),
             countries: countries_set.into_iter().collect(),
             genres,
-            search_texts,
+            search_index: Arc::new(SearchIndex::build(stations)),
+            geo_index: Arc::new(GeoIndex::build(stations)),
+            orderings: Arc::new(StationOrderings::build(stations)),
+            facets: Arc::new(FacetIndex::build(stations)),
+            similarity: Arc::new(SimilarityIndex::build(stations)),
             station_index_by_id,
             index_by_country,
             index_by_language,
             index_by_tag,
+            index_by_codec,
+            hls_indexes,
+            non_hls_indexes,
+            online_indexes,
+            offline_indexes,
+            bitrate_order,
         }
     }
 
@@ -122,13 +168,88 @@ impl ProcessedStations {
             .map(|list| list.as_slice())
     }
 
+    pub fn indexes_for_codec(&self, codec: &str) -> Option<&[usize]> {
+        self.index_by_codec
+            .get(&codec.to_lowercase())
+            .map(|list| list.as_slice())
+    }
+
+    pub fn indexes_for_hls(&self, hls: bool) -> &[usize] {
+        if hls {
+            &self.hls_indexes
+        } else {
+            &self.non_hls_indexes
+        }
+    }
+
+    pub fn indexes_for_online(&self, is_online: bool) -> &[usize] {
+        if is_online {
+            &self.online_indexes
+        } else {
+            &self.offline_indexes
+        }
+    }
+
+    /// Stations with a known bitrate inside the inclusive range, in payload order.
+    pub fn indexes_for_bitrate(&self, min: Option<i32>, max: Option<i32>) -> Vec<usize> {
+        let start = min.map_or(0, |min| {
+            self.bitrate_order
+                .partition_point(|(bitrate, _)| *bitrate < min)
+        });
+        let end = max.map_or(self.bitrate_order.len(), |max| {
+            self.bitrate_order
+                .partition_point(|(bitrate, _)| *bitrate <= max)
+        });
+        let mut indexes: Vec<usize> = self
+            .bitrate_order
+            .get(start..end.max(start))
+            .unwrap_or_default()
+            .iter()
+            .map(|(_, idx)| *idx)
+            .collect();
+        indexes.sort_unstable();
+        indexes
+    }
+
     pub fn station_index(&self, station_id: &str) -> Option<usize> {
         self.station_index_by_id.get(station_id).copied()
     }
 
+    /// Keeps the candidates that match `search` and orders them by relevance, best first.
+    /// Ties keep payload order. A search with no searchable tokens (only punctuation, say)
+    /// matches nothing; only a blank one leaves the candidates alone.
     pub fn search_matches(&self, search: &str, indexes: &mut Vec<usize>) {
-        let needle = search.to_lowercase();
-        indexes.retain(|idx| self.search_texts[*idx].contains(&needle));
+        let Some(scores) = self.search_index.score(search) else {
+            if !search.trim().is_empty() {
+                indexes.clear();
+            }
+            return;
+        };
+        indexes.retain(|idx| scores.contains_key(idx));
+        indexes.sort_by(|a, b| scores[b].total_cmp(&scores[a]).then(a.cmp(b)));
+    }
+
+    /// Stations with coordinates within `radius_km` of the point, nearest first, paired with
+    /// their great-circle distance in km.
+    pub fn nearby(&self, lat: f64, lon: f64, radius_km: f64) -> Vec<(usize, f64)> {
+        self.geo_index.within(lat, lon, radius_km)
+    }
+
+    /// Stations most like the one at `idx`, best first, with their similarity in (0, 1].
+    pub fn similar(&self, idx: usize, limit: usize) -> Vec<(usize, f32)> {
+        self.similarity.similar(idx, limit)
+    }
+
+    pub fn facet_counts(&self, indexes: &[usize]) -> FacetCounts {
+        self.facets.count(indexes)
+    }
+
+    pub fn random_seed(&self) -> u64 {
+        self.orderings.random_seed()
+    }
+
+    pub fn sort_indexes(&self, sort: StationSort, order: SortOrder, indexes: &mut [usize]) {
+        self.orderings.sort(sort, order, indexes);
     }
 }
 
@@ -147,6 +268,14 @@ fn normalize_token(value: &str) -> Option<String> {
     }
 }
 
+/// Merges candidate lists into one ascending, de-duplicated list (OR semantics).
+pub fn union_lists<'a>(lists: impl IntoIterator<Item = &'a [usize]>) -> Vec<usize> {
+    let mut merged: Vec<usize> = lists.into_iter().flatten().copied().collect();
+    merged.sort_unstable();
+    merged.dedup();
+    merged
+}
+
 pub fn intersect_lists(lists: &[Vec<usize>], station_count: usize) -> Vec<usize> {
     if lists.is_empty() {
         return (0..station_count).collect();
@@ -163,3 +292,54 @@ pub fn intersect_lists(lists: &[Vec<usize>], station_count: usize) -> Vec<usize>
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    #[test]
+    fn technical_indexes_cover_codec_bitrate_and_hls() {
+        let processed = ProcessedStations::build(&[
+            Station {
+                codec: Some("MP3".into()),
+                bitrate: Some(320),
+                ..test_station("a")
+            },
+            Station {
+                codec: Some("AAC".into()),
+                bitrate: Some(64),
+                hls: true,
+                ..test_station("b")
+            },
+            Station {
+                codec: Some("mp3".into()),
+                ..test_station("c")
+            },
+            Station {
+                codec: Some("AAC+".into()),
+                bitrate: Some(128),
+                ..test_station("d")
+            },
+        ]);
+
+        assert_eq!(processed.indexes_for_codec("mp3"), Some(&[0, 2][..]));
+        assert_eq!(processed.indexes_for_bitrate(Some(100), None), vec![0, 3]);
+        assert_eq!(processed.indexes_for_bitrate(None, Some(128)), vec![1, 3]);
+        assert!(processed.indexes_for_bitrate(Some(400), None).is_empty());
+        assert_eq!(processed.indexes_for_hls(true), &[1]);
+
+        let either = union_lists([
+            processed.indexes_for_codec("aac").unwrap(),
+            processed.indexes_for_codec("aac+").unwrap(),
+        ]);
+        assert_eq!(either, vec![1, 3]);
+        assert_eq!(intersect_lists(&[either, vec![0, 3]], 4), vec![3]);
+
+        let mut indexes = vec![0, 1, 2, 3];
+        processed.search_matches("  ", &mut indexes);
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+        processed.search_matches("!!!", &mut indexes);
+        assert!(indexes.is_empty());
+    }
+}
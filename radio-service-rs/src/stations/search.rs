@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::Station;
+
+const NAME_WEIGHT: f64 = 3.0;
+const TAG_WEIGHT: f64 = 2.0;
+const PLACE_WEIGHT: f64 = 1.5;
+const LANGUAGE_WEIGHT: f64 = 1.0;
+
+const EXACT_SCORE: f64 = 1.0;
+const PREFIX_SCORE: f64 = 0.75;
+const FUZZY_SCORE: [f64; 3] = [1.0, 0.6, 0.4];
+const MIN_PREFIX_LEN: usize = 2;
+const MIN_FUZZY_LEN: usize = 4;
+const TWO_EDIT_LEN: usize = 8;
+
+const NAME_EXACT_BONUS: f64 = 2.0;
+const NAME_PREFIX_BONUS: f64 = 1.0;
+const VOTE_WEIGHT: f64 = 0.06;
+const CLICK_WEIGHT: f64 = 0.04;
+const MAX_POPULARITY_BOOST: f64 = 1.0;
+
+#[derive(Clone, Copy)]
+struct Posting {
+    station: usize,
+    weight: f64,
+}
+
+/// Token index over station names, tags, places and languages. Terms are diacritic-folded so
+/// "Café" and "cafe" land on the same posting list, and looked up by exact, prefix and
+/// edit-distance matching.
+#[derive(Default)]
+pub struct SearchIndex {
+    terms: BTreeMap<String, Vec<Posting>>,
+    terms_by_length: HashMap<usize, Vec<String>>,
+    names: Vec<String>,
+    popularity: Vec<f64>,
+}
+
+impl SearchIndex {
+    pub fn build(stations: &[Station]) -> Self {
+        let mut terms: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+        let mut names = Vec::with_capacity(stations.len());
+        let mut popularity = Vec::with_capacity(stations.len());
+
+        for (idx, station) in stations.iter().enumerate() {
+            let mut weights: HashMap<String, f64> = HashMap::new();
+            let mut add_field = |value: &str, weight: f64| {
+                for token in tokenize(value) {
+                    let entry = weights.entry(token).or_insert(0.0);
+                    *entry = entry.max(weight);
+                }
+            };
+            add_field(&station.name, NAME_WEIGHT);
+            for tag in &station.tags {
+                add_field(tag, TAG_WEIGHT);
+            }
+            for place in [&station.country, &station.state].into_iter().flatten() {
+                add_field(place, PLACE_WEIGHT);
+            }
+            for language in &station.languages {
+                add_field(language, LANGUAGE_WEIGHT);
+            }
+            for (term, weight) in weights {
+                terms.entry(term).or_default().push(Posting {
+                    station: idx,
+                    weight,
+                });
+            }
+
+            names.push(tokenize(&station.name).join(" "));
+            popularity.push(popularity_boost(station));
+        }
+
+        let mut terms_by_length: HashMap<usize, Vec<String>> = HashMap::new();
+        for term in terms.keys() {
+            terms_by_length
+                .entry(term.chars().count())
+                .or_default()
+                .push(term.clone());
+        }
+
+        Self {
+            terms,
+            terms_by_length,
+            names,
+            popularity,
+        }
+    }
+
+    /// Scores every station that matches all query tokens. Returns `None` when the query has
+    /// no searchable tokens.
+    pub fn score(&self, query: &str) -> Option<HashMap<usize, f64>> {
+        let tokens = tokenize(query);
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let mut totals: Option<HashMap<usize, f64>> = None;
+        for token in &tokens {
+            let matches = self.match_token(token);
+            totals = Some(match totals {
+                None => matches,
+                Some(mut current) => {
+                    current.retain(|idx, total| match matches.get(idx) {
+                        Some(score) => {
+                            *total += score;
+                            true
+                        }
+                        None => false,
+                    });
+                    current
+                }
+            });
+            if totals.as_ref().is_some_and(HashMap::is_empty) {
+                break;
+            }
+        }
+
+        let phrase = tokens.join(" ");
+        let mut totals = totals.unwrap_or_default();
+        for (idx, total) in totals.iter_mut() {
+            let name = &self.names[*idx];
+            if *name == phrase {
+                *total += NAME_EXACT_BONUS;
+            } else if name.starts_with(&phrase) {
+                *total += NAME_PREFIX_BONUS;
+            }
+            *total *= 1.0 + self.popularity[*idx];
+        }
+        Some(totals)
+    }
+
+    fn match_token(&self, token: &str) -> HashMap<usize, f64> {
+        let mut best: HashMap<usize, f64> = HashMap::new();
+        let mut record = |postings: &[Posting], factor: f64| {
+            for posting in postings {
+                let score = posting.weight * factor;
+                let entry = best.entry(posting.station).or_insert(0.0);
+                if score > *entry {
+                    *entry = score;
+                }
+            }
+        };
+
+        let token_len = token.chars().count();
+        if token_len >= MIN_PREFIX_LEN {
+            for (term, postings) in self
+                .terms
+                .range::<str, _>((Bound::Included(token), Bound::Unbounded))
+            {
+                if !term.starts_with(token) {
+                    break;
+                }
+                let factor = if term == token {
+                    EXACT_SCORE
+                } else {
+                    PREFIX_SCORE
+                };
+                record(postings, factor);
+            }
+        } else if let Some(postings) = self.terms.get(token) {
+            record(postings, EXACT_SCORE);
+        }
+
+        if token_len >= MIN_FUZZY_LEN {
+            let max_edits = if token_len >= TWO_EDIT_LEN { 2 } else { 1 };
+            let token_chars: Vec<char> = token.chars().collect();
+            for len in token_len.saturating_sub(max_edits)..=token_len + max_edits {
+                for term in self.terms_by_length.get(&len).into_iter().flatten() {
+                    let Some(edits) = bounded_edit_distance(&token_chars, term, max_edits) else {
+                        continue;
+                    };
+                    if edits == 0 {
+                        continue;
+                    }
+                    if let Some(postings) = self.terms.get(term) {
+                        record(postings, FUZZY_SCORE[edits]);
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Lowercases, strips diacritics and splits on anything that is not a letter or digit.
+pub fn tokenize(value: &str) -> Vec<String> {
+    fold(value)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub(super) fn fold(value: &str) -> String {
+    let mut folded = String::with_capacity(value.len());
+    for c in value.nfkd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'ß' => folded.push_str("ss"),
+            'æ' | 'Æ' => folded.push_str("ae"),
+            'œ' | 'Œ' => folded.push_str("oe"),
+            'ø' | 'Ø' => folded.push('o'),
+            'đ' | 'Đ' => folded.push('d'),
+            'ł' | 'Ł' => folded.push('l'),
+            'ı' => folded.push('i'),
+            _ => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded
+}
+
+fn popularity_boost(station: &Station) -> f64 {
+    let votes = f64::from(station.votes.max(0)).ln_1p();
+    let clicks = f64::from(station.click_count.max(0)).ln_1p();
+    (votes * VOTE_WEIGHT + clicks * CLICK_WEIGHT).min(MAX_POPULARITY_BOOST)
+}
+
+/// Levenshtein distance between `left` and `right`, or `None` once it exceeds `max`.
+fn bounded_edit_distance(left: &[char], right: &str, max: usize) -> Option<usize> {
+    let right: Vec<char> = right.chars().collect();
+    if left.len().abs_diff(right.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_char) in left.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, right_char) in right.iter().enumerate() {
+            let cost = usize::from(left_char != right_char);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let distance = previous[right.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    fn ranked(index: &SearchIndex, query: &str) -> Vec<usize> {
+        let mut scored: Vec<_> = index.score(query).unwrap().into_iter().collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    #[test]
+    fn folds_diacritics_and_punctuation() {
+        assert_eq!(tokenize("Café Ñandú-FM!"), vec!["cafe", "nandu", "fm"]);
+        assert_eq!(tokenize("Straße Øst"), vec!["strasse", "ost"]);
+    }
+
+    #[test]
+    fn matches_prefixes_and_typos() {
+        let index = SearchIndex::build(&[
+            Station {
+                name: "Jazz Café".into(),
+                tags: vec!["jazz".into()],
+                ..test_station("a")
+            },
+            Station {
+                name: "Classic Rock Radio".into(),
+                tags: vec!["rock".into()],
+                ..test_station("b")
+            },
+        ]);
+        assert_eq!(ranked(&index, "cafe"), vec![0]);
+        assert_eq!(ranked(&index, "clas"), vec![1]);
+        assert_eq!(ranked(&index, "clasic rock"), vec![1]);
+        assert!(ranked(&index, "metal").is_empty());
+        assert!(index.score("  -- ").is_none());
+    }
+
+    #[test]
+    fn ranks_by_relevance_then_popularity() {
+        let index = SearchIndex::build(&[
+            Station {
+                name: "Morning Mix".into(),
+                tags: vec!["jazz".into()],
+                votes: 5000,
+                ..test_station("a")
+            },
+            Station {
+                name: "Jazz FM".into(),
+                tags: vec!["smooth".into()],
+                ..test_station("b")
+            },
+            Station {
+                name: "Jazz Lounge".into(),
+                tags: vec!["jazz".into()],
+                votes: 200,
+                ..test_station("c")
+            },
+        ]);
+        assert_eq!(ranked(&index, "jazz"), vec![2, 1, 0]);
+        assert_eq!(ranked(&index, "jazz fm"), vec![1]);
+    }
+}
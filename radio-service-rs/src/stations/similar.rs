@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use super::Station;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Feature {
+    Tag,
+    Language,
+    Country,
+    Codec,
+}
+
+impl Feature {
+    /// Tags say the most about what a station sounds like; the codec only separates otherwise
+    /// equal matches.
+    fn weight(self) -> f32 {
+        match self {
+            Self::Tag => 3.0,
+            Self::Language => 2.0,
+            Self::Country => 1.5,
+            Self::Codec => 0.5,
+        }
+    }
+}
+
+/// Sparse feature vectors over tags, languages, country and codec, weighted by how rare each
+/// value is and normalized so the dot product of two stations is their cosine similarity.
+/// Built once per payload; a lookup only walks the postings of the station's own features.
+#[derive(Default)]
+pub struct SimilarityIndex {
+    kinds: Vec<Feature>,
+    /// Feature id -> (station index, weight), in station order.
+    postings: Vec<Vec<(u32, f32)>>,
+    /// Station index -> (feature id, weight), sorted by feature id.
+    vectors: Vec<Vec<(u32, f32)>>,
+    popularity: Vec<i64>,
+}
+
+impl SimilarityIndex {
+    pub fn build(stations: &[Station]) -> Self {
+        let mut ids: HashMap<(Feature, String), u32> = HashMap::new();
+        let mut kinds = Vec::new();
+        let mut raw: Vec<Vec<u32>> = Vec::with_capacity(stations.len());
+
+        for station in stations {
+            let country = station
+                .country_code
+                .as_deref()
+                .or(station.country.as_deref());
+            let values = station
+                .tags
+                .iter()
+                .map(|tag| (Feature::Tag, tag.as_str()))
+                .chain(
+                    station
+                        .languages
+                        .iter()
+                        .map(|language| (Feature::Language, language.as_str())),
+                )
+                .chain(country.map(|country| (Feature::Country, country)))
+                .chain(
+                    station
+                        .codec
+                        .as_deref()
+                        .map(|codec| (Feature::Codec, codec)),
+                );
+
+            let mut features = Vec::new();
+            for (kind, value) in values {
+                let value = value.trim().to_lowercase();
+                if value.is_empty() {
+                    continue;
+                }
+                let next_id = kinds.len() as u32;
+                let id = *ids.entry((kind, value)).or_insert_with(|| {
+                    kinds.push(kind);
+                    next_id
+                });
+                features.push(id);
+            }
+            features.sort_unstable();
+            features.dedup();
+            raw.push(features);
+        }
+
+        let mut document_frequency = vec![0usize; kinds.len()];
+        for features in &raw {
+            for feature in features {
+                document_frequency[*feature as usize] += 1;
+            }
+        }
+
+        let total = stations.len().max(1) as f32;
+        let mut postings = vec![Vec::new(); kinds.len()];
+        let mut vectors = Vec::with_capacity(raw.len());
+        for (idx, features) in raw.into_iter().enumerate() {
+            let mut vector: Vec<(u32, f32)> = features
+                .into_iter()
+                .map(|feature| {
+                    let df = document_frequency[feature as usize] as f32;
+                    let weight = kinds[feature as usize].weight() * (1.0 + total / df).ln();
+                    (feature, weight)
+                })
+                .collect();
+            let norm = vector
+                .iter()
+                .map(|(_, weight)| weight * weight)
+                .sum::<f32>()
+                .sqrt();
+            if norm > 0.0 {
+                for (feature, weight) in &mut vector {
+                    *weight /= norm;
+                    postings[*feature as usize].push((idx as u32, *weight));
+                }
+            }
+            vectors.push(vector);
+        }
+
+        Self {
+            kinds,
+            postings,
+            vectors,
+            popularity: stations
+                .iter()
+                .map(|station| i64::from(station.votes) + i64::from(station.click_count))
+                .collect(),
+        }
+    }
+
+    /// Up to `limit` other stations ranked by similarity to the station at `idx`, paired with
+    /// their score in (0, 1]. Equal scores go to the more popular station. Stations that only
+    /// share a codec are not considered similar.
+    pub fn similar(&self, idx: usize, limit: usize) -> Vec<(usize, f32)> {
+        let Some(vector) = self.vectors.get(idx).filter(|_| limit > 0) else {
+            return Vec::new();
+        };
+        let mut scores = vec![0f32; self.vectors.len()];
+        let mut candidates = Vec::new();
+        for (feature, weight) in vector {
+            if self.kinds[*feature as usize] == Feature::Codec {
+                continue;
+            }
+            for (other, other_weight) in &self.postings[*feature as usize] {
+                let other = *other as usize;
+                if other == idx {
+                    continue;
+                }
+                if scores[other] == 0.0 {
+                    candidates.push(other);
+                }
+                scores[other] += weight * other_weight;
+            }
+        }
+        for (feature, weight) in vector {
+            if self.kinds[*feature as usize] != Feature::Codec {
+                continue;
+            }
+            for &other in &candidates {
+                if let Ok(position) =
+                    self.vectors[other].binary_search_by_key(feature, |(id, _)| *id)
+                {
+                    scores[other] += weight * self.vectors[other][position].1;
+                }
+            }
+        }
+
+        let rank = |a: &usize, b: &usize| {
+            scores[*b]
+                .total_cmp(&scores[*a])
+                .then_with(|| self.popularity[*b].cmp(&self.popularity[*a]))
+                .then(a.cmp(b))
+        };
+        if candidates.len() > limit {
+            candidates.select_nth_unstable_by(limit - 1, rank);
+            candidates.truncate(limit);
+        }
+        candidates.sort_unstable_by(rank);
+        candidates
+            .into_iter()
+            .map(|other| (other, scores[other].min(1.0)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+
+    #[test]
+    fn ranks_by_shared_features_then_popularity() {
+        let stations = [
+            Station {
+                country_code: Some("DE".into()),
+                languages: vec!["german".into()],
+                tags: vec!["jazz".into(), "smooth".into()],
+                codec: Some("MP3".into()),
+                ..test_station("jazz-de")
+            },
+            Station {
+                country_code: Some("DE".into()),
+                languages: vec!["german".into()],
+                tags: vec!["Jazz".into()],
+                codec: Some("MP3".into()),
+                votes: 1,
+                ..test_station("jazz-de-quiet")
+            },
+            Station {
+                country_code: Some("DE".into()),
+                languages: vec!["german".into()],
+                tags: vec!["jazz".into()],
+                codec: Some("MP3".into()),
+                votes: 50,
+                ..test_station("jazz-de-loud")
+            },
+            Station {
+                country_code: Some("US".into()),
+                languages: vec!["english".into()],
+                tags: vec!["jazz".into()],
+                codec: Some("MP3".into()),
+                ..test_station("jazz-us")
+            },
+            Station {
+                country_code: Some("DE".into()),
+                languages: vec!["german".into()],
+                tags: vec!["news".into()],
+                codec: Some("MP3".into()),
+                votes: 900,
+                ..test_station("news-de")
+            },
+            Station {
+                country_code: Some("FR".into()),
+                languages: vec!["french".into()],
+                tags: vec!["rock".into()],
+                codec: Some("MP3".into()),
+                votes: 900,
+                ..test_station("rock-fr")
+            },
+        ];
+        let index = SimilarityIndex::build(&stations);
+
+        let ranked: Vec<_> = index
+            .similar(0, 10)
+            .into_iter()
+            .map(|(idx, _)| stations[idx].id.as_str())
+            .collect();
+        assert_eq!(
+            ranked,
+            vec!["jazz-de-loud", "jazz-de-quiet", "jazz-us", "news-de"]
+        );
+
+        let top = index.similar(0, 2);
+        assert_eq!(
+            top.iter().map(|(idx, _)| *idx).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(top[0].1, top[1].1);
+        assert!(top[0].1 > 0.0 && top[0].1 <= 1.0);
+        assert!(index.similar(5, 10).is_empty());
+        assert!(index.similar(99, 10).is_empty());
+    }
+}
@@ -1,12 +1,55 @@
-use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sqlx::{
     postgres::{PgRow, Postgres},
     PgPool, QueryBuilder, Row, Transaction,
 };
 use thiserror::Error;
 
-use super::{build_stations_fingerprint, Station, StationCoordinates, StationsPayload};
+use super::{
+    build_station_content_signature, build_stations_fingerprint,
+    overrides::{StationOverride, StationPatch},
+    Station, StationCoordinates, StationsPayload,
+};
+
+const CHANGE_SET_RETENTION_DAYS: i64 = 14;
+/// The cursor `/stations/changes` hands out: the newest change set's fingerprint, which is the
+/// payload's unless an override was written since.
+const CURRENT_CHANGE_CURSOR: &str = r#"
+    SELECT COALESCE(
+        (SELECT fingerprint FROM station_change_sets ORDER BY id DESC LIMIT 1),
+        (SELECT sp.fingerprint
+         FROM station_state ss
+         JOIN station_payloads sp ON sp.id = ss.payload_id)
+    )
+"#;
+const STATION_SELECT: &str = r#"
+    SELECT id,
+           name,
+           stream_url,
+           homepage,
+           favicon,
+           country,
+           country_code,
+           state,
+           languages,
+           tags,
+           coordinates,
+           bitrate,
+           codec,
+           hls,
+           is_online,
+           last_checked_at,
+           last_changed_at,
+           click_count,
+           click_trend,
+           votes
+    FROM stations
+"#;
 
 #[derive(Debug, Error)]
 pub enum StorageError {
@@ -20,16 +63,61 @@ pub enum StorageError {
 pub struct PersistOutcome {
     pub payload_id: i64,
     pub changed: bool,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Where a client's last sync left off: the payload fingerprint it holds, or the time it
+/// last synced.
+#[derive(Debug, Clone)]
+pub enum ChangeCursor {
+    Fingerprint(String),
+    Timestamp(DateTime<Utc>),
+}
+
+/// Net effect of every change set after a cursor.
+#[derive(Debug, Default)]
+pub struct StationChanges {
+    pub fingerprint: Option<String>,
+    pub change_sets: usize,
+    pub upserted: Vec<Station>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug)]
+struct ChangeSet {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// A retained payload snapshot that the live catalogue can be rolled back to.
+#[derive(Debug, Serialize)]
+pub struct PayloadHistoryEntry {
+    pub id: i64,
+    pub fingerprint: String,
+    pub total: i64,
+    pub source: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    pub active: bool,
 }
 
 #[derive(Clone)]
 pub struct StationStorage {
     pool: PgPool,
+    history_limit: usize,
 }
 
 impl StationStorage {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, history_limit: usize) -> Self {
+        Self {
+            pool,
+            history_limit: history_limit.max(1),
+        }
     }
 
     pub async fn load_latest_payload(&self) -> Result<Option<StationsPayload>, StorageError> {
@@ -71,33 +159,9 @@ impl StationStorage {
             fingerprint: row.try_get::<Option<String>, _>("fingerprint")?,
         };
 
-        let station_rows = sqlx::query(
-            r#"
-            SELECT id,
-                   name,
-                   stream_url,
-                   homepage,
-                   favicon,
-                   country,
-                   country_code,
-                   state,
-                   languages,
-                   tags,
-                   coordinates,
-                   bitrate,
-                   codec,
-                   hls,
-                   is_online,
-                   last_checked_at,
-                   last_changed_at,
-                   click_count,
-                   click_trend,
-                   votes
-            FROM stations
-            WHERE payload_id = $1
-            ORDER BY name ASC
-            "#,
-        )
+        let station_rows = sqlx::query(&format!(
+            "{STATION_SELECT} WHERE payload_id = $1 ORDER BY name ASC"
+        ))
         .bind(payload_id)
         .fetch_all(&self.pool)
         .await?;
@@ -114,9 +178,24 @@ impl StationStorage {
         Ok(Some(payload))
     }
 
+    pub async fn has_live_payload(&self) -> Result<bool, StorageError> {
+        let live: Option<bool> =
+            sqlx::query_scalar("SELECT payload_id IS NOT NULL FROM station_state LIMIT 1")
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(live.unwrap_or(false))
+    }
+
+    /// Applies a refreshed payload as a diff against the stored stations: only added or
+    /// changed rows are upserted, missing ones are deleted, and the diff is recorded as a
+    /// change set for `/stations/changes`.
+    ///
+    /// `record_history` snapshots the payload for rollback; re-validation passes skip it so
+    /// small corrections do not push real refreshes out of the retained history.
     pub async fn persist_payload(
         &self,
         payload: &StationsPayload,
+        record_history: bool,
     ) -> Result<PersistOutcome, StorageError> {
         let mut tx = self.pool.begin().await?;
 
@@ -126,6 +205,8 @@ impl StationStorage {
                 .map_err(|err| StorageError::InvalidData(format!("fingerprint error: {err}")))?,
         };
 
+        let mut current_payload_id: Option<i64> = None;
+        let mut previous_fingerprint: Option<String> = None;
         if let Some(existing) = sqlx::query(
             r#"
             SELECT ss.payload_id, sp.fingerprint
@@ -138,40 +219,127 @@ impl StationStorage {
         .await?
         {
             let existing_fingerprint: Option<String> = existing.try_get("fingerprint")?;
+            let payload_id: Option<i64> = existing.try_get("payload_id").ok().flatten();
             if Some(fingerprint.clone()) == existing_fingerprint {
                 sqlx::query("UPDATE station_state SET updated_at = NOW() WHERE id = TRUE")
                     .execute(&mut *tx)
                     .await?;
                 tx.commit().await?;
-                let payload_id: Option<i64> = existing.try_get("payload_id").ok();
                 return Ok(PersistOutcome {
                     payload_id: payload_id.unwrap_or_default(),
                     changed: false,
+                    added: 0,
+                    updated: 0,
+                    removed: 0,
                 });
             }
+            current_payload_id = payload_id;
+            previous_fingerprint = existing_fingerprint;
         }
 
         let req_json = serde_json::to_value(&payload.requests).unwrap_or(Value::Null);
         let schema_version = payload.schema_version.map(|value| value.to_string());
-        let inserted = sqlx::query(
-            r#"
-            INSERT INTO station_payloads (schema_version, updated_at, source, requests, total, fingerprint)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id
-            "#,
-        )
-        .bind(&schema_version)
-        .bind(payload.updated_at)
-        .bind(&payload.source)
-        .bind(req_json)
-        .bind(i64::try_from(payload.total).unwrap_or(payload.total as i64))
-        .bind(&fingerprint)
-        .fetch_one(&mut *tx)
-        .await?;
+        let total = i64::try_from(payload.total).unwrap_or(payload.total as i64);
+        let payload_id: i64 = match current_payload_id {
+            Some(payload_id) => {
+                // Older payload rows only exist on databases written before diffs were stored;
+                // their stations cascade away with them.
+                sqlx::query("DELETE FROM station_payloads WHERE id <> $1")
+                    .bind(payload_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    r#"
+                    UPDATE station_payloads
+                    SET schema_version = $2, updated_at = $3, source = $4, requests = $5,
+                        total = $6, fingerprint = $7
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(payload_id)
+                .bind(&schema_version)
+                .bind(payload.updated_at)
+                .bind(&payload.source)
+                .bind(req_json)
+                .bind(total)
+                .bind(&fingerprint)
+                .execute(&mut *tx)
+                .await?;
+                payload_id
+            }
+            None => {
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO station_payloads (schema_version, updated_at, source, requests, total, fingerprint)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id
+                    "#,
+                )
+                .bind(&schema_version)
+                .bind(payload.updated_at)
+                .bind(&payload.source)
+                .bind(req_json)
+                .bind(total)
+                .bind(&fingerprint)
+                .fetch_one(&mut *tx)
+                .await?;
+                inserted.try_get("id")?
+            }
+        };
 
-        let payload_id: i64 = inserted.try_get("id")?;
-        self.insert_stations(&mut tx, payload_id, &payload.stations)
+        let stored: HashMap<String, Option<String>> =
+            sqlx::query("SELECT id, signature FROM stations WHERE payload_id = $1")
+                .bind(payload_id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|row| Ok((row.try_get("id")?, row.try_get("signature")?)))
+                .collect::<Result<_, sqlx::Error>>()?;
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut upserts = Vec::new();
+        let mut seen = HashSet::with_capacity(payload.stations.len());
+        for station in &payload.stations {
+            if !seen.insert(station.id.as_str()) {
+                continue;
+            }
+            let signature = build_station_content_signature(station)
+                .map_err(|err| StorageError::InvalidData(format!("signature error: {err}")))?;
+            match stored.get(&station.id) {
+                None => added.push(station.id.clone()),
+                Some(existing) if existing.as_deref() != Some(signature.as_str()) => {
+                    changed.push(station.id.clone())
+                }
+                Some(_) => continue,
+            }
+            upserts.push((station, signature));
+        }
+        let mut removed: Vec<String> = stored
+            .keys()
+            .filter(|id| !seen.contains(id.as_str()))
+            .cloned()
+            .collect();
+        removed.sort_unstable();
+
+        self.upsert_stations(&mut tx, payload_id, &upserts).await?;
+        if !removed.is_empty() {
+            sqlx::query("DELETE FROM stations WHERE id = ANY($1)")
+                .bind(&removed)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if record_history {
+            self.record_history(
+                &mut tx,
+                payload,
+                &fingerprint,
+                schema_version.as_deref(),
+                total,
+            )
             .await?;
+        }
 
         sqlx::query(
             r#"
@@ -184,8 +352,21 @@ impl StationStorage {
         .execute(&mut *tx)
         .await?;
 
-        sqlx::query("DELETE FROM station_payloads WHERE id <> $1")
-            .bind(payload_id)
+        sqlx::query(
+            r#"
+            INSERT INTO station_change_sets (fingerprint, previous_fingerprint, added, changed, removed)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&fingerprint)
+        .bind(&previous_fingerprint)
+        .bind(&added)
+        .bind(&changed)
+        .bind(&removed)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM station_change_sets WHERE created_at < $1")
+            .bind(Utc::now() - Duration::days(CHANGE_SET_RETENTION_DAYS))
             .execute(&mut *tx)
             .await?;
 
@@ -194,20 +375,393 @@ impl StationStorage {
         Ok(PersistOutcome {
             payload_id,
             changed: true,
+            added: added.len(),
+            updated: changed.len(),
+            removed: removed.len(),
         })
     }
 
-    async fn insert_stations(
+    /// Folds every change set recorded after `cursor` into one delta. Returns `None` when
+    /// the retained history does not reach back to the cursor, in which case the client has
+    /// to reload the full list.
+    pub async fn load_changes_since(
+        &self,
+        cursor: &ChangeCursor,
+    ) -> Result<Option<StationChanges>, StorageError> {
+        // One snapshot, so the change sets and station rows agree with each other.
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let current_fingerprint: Option<String> = sqlx::query_scalar(CURRENT_CHANGE_CURSOR)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let after_id: Option<i64> = match cursor {
+            ChangeCursor::Fingerprint(fingerprint) => {
+                if current_fingerprint.as_deref() == Some(fingerprint.as_str()) {
+                    return Ok(Some(StationChanges {
+                        fingerprint: current_fingerprint,
+                        ..StationChanges::default()
+                    }));
+                }
+                sqlx::query("SELECT MAX(id) AS id FROM station_change_sets WHERE fingerprint = $1")
+                    .bind(fingerprint)
+                    .fetch_one(&mut *tx)
+                    .await?
+                    .try_get("id")?
+            }
+            ChangeCursor::Timestamp(since) => {
+                sqlx::query("SELECT MAX(id) AS id FROM station_change_sets WHERE created_at <= $1")
+                    .bind(since)
+                    .fetch_one(&mut *tx)
+                    .await?
+                    .try_get("id")?
+            }
+        };
+        let Some(after_id) = after_id else {
+            return Ok(None);
+        };
+
+        let sets = sqlx::query(
+            r#"
+            SELECT added, changed, removed
+            FROM station_change_sets
+            WHERE id > $1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(after_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(ChangeSet {
+                added: row.try_get("added")?,
+                changed: row.try_get("changed")?,
+                removed: row.try_get("removed")?,
+            })
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        let (upserted_ids, removed) = fold_change_sets(&sets);
+        let mut upserted = Vec::with_capacity(upserted_ids.len());
+        if !upserted_ids.is_empty() {
+            let rows = sqlx::query(&format!(
+                "{STATION_SELECT} WHERE id = ANY($1) ORDER BY name ASC"
+            ))
+            .bind(&upserted_ids)
+            .fetch_all(&mut *tx)
+            .await?;
+            for row in rows {
+                upserted.push(row_to_station(row)?);
+            }
+        }
+        tx.commit().await?;
+
+        Ok(Some(StationChanges {
+            fingerprint: current_fingerprint,
+            change_sets: sets.len(),
+            upserted,
+            removed,
+        }))
+    }
+
+    /// Retained snapshots, newest first. `active` marks the one currently served.
+    pub async fn list_payload_history(&self) -> Result<Vec<PayloadHistoryEntry>, StorageError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT h.id,
+                   h.fingerprint,
+                   h.total,
+                   h.source,
+                   h.updated_at,
+                   h.created_at,
+                   h.fingerprint = sp.fingerprint AS active
+            FROM station_payload_history h
+            LEFT JOIN station_state ss ON ss.id = TRUE
+            LEFT JOIN station_payloads sp ON sp.id = ss.payload_id
+            ORDER BY h.id DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(PayloadHistoryEntry {
+                    id: row.try_get("id")?,
+                    fingerprint: row.try_get("fingerprint")?,
+                    total: row.try_get("total")?,
+                    source: row.try_get("source")?,
+                    updated_at: row.try_get("updated_at")?,
+                    created_at: row.try_get("created_at")?,
+                    active: row.try_get::<Option<bool>, _>("active")?.unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn load_history_payload(
+        &self,
+        history_id: i64,
+    ) -> Result<Option<StationsPayload>, StorageError> {
+        let Some(row) = sqlx::query(
+            r#"
+            SELECT schema_version, updated_at, source, requests, total, fingerprint, stations
+            FROM station_payload_history
+            WHERE id = $1
+            "#,
+        )
+        .bind(history_id)
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let total: i64 = row.try_get("total")?;
+        let stations: Vec<Station> = serde_json::from_value(row.try_get("stations")?)
+            .map_err(|err| StorageError::InvalidData(format!("snapshot error: {err}")))?;
+        Ok(Some(StationsPayload {
+            schema_version: parse_schema_version(row.try_get("schema_version")?),
+            updated_at: row.try_get("updated_at")?,
+            source: row.try_get("source")?,
+            requests: json_array_to_vec(row.try_get("requests")?),
+            total: total.try_into().unwrap_or_default(),
+            stations,
+            fingerprint: row.try_get("fingerprint")?,
+        }))
+    }
+
+    /// Snapshots the payload and drops all but the newest `history_limit` snapshots. A
+    /// payload that is already retained (a rollback target) keeps its original position.
+    async fn record_history(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        payload: &StationsPayload,
+        fingerprint: &str,
+        schema_version: Option<&str>,
+        total: i64,
+    ) -> Result<(), StorageError> {
+        let stations = serde_json::to_value(&payload.stations)
+            .map_err(|err| StorageError::InvalidData(format!("snapshot error: {err}")))?;
+        sqlx::query(
+            r#"
+            INSERT INTO station_payload_history (fingerprint, schema_version, updated_at, source, requests, total, stations)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (fingerprint) DO NOTHING
+            "#,
+        )
+        .bind(fingerprint)
+        .bind(schema_version)
+        .bind(payload.updated_at)
+        .bind(&payload.source)
+        .bind(serde_json::to_value(&payload.requests).unwrap_or(Value::Null))
+        .bind(total)
+        .bind(stations)
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM station_payload_history
+            WHERE id NOT IN (
+                SELECT id FROM station_payload_history ORDER BY id DESC LIMIT $1
+            )
+            "#,
+        )
+        .bind(i64::try_from(self.history_limit).unwrap_or(i64::MAX))
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Stations that failed stream validation, kept so a later re-check can restore them.
+    pub async fn load_quarantined(&self) -> Result<Vec<Station>, StorageError> {
+        let rows: Vec<Value> = sqlx::query_scalar("SELECT station FROM station_quarantine")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect())
+    }
+
+    /// Replaces the quarantine with the stations a full refresh dropped; anything Radio
+    /// Browser no longer lists goes away with it.
+    pub async fn replace_quarantine(
+        &self,
+        dropped: &[(Station, String)],
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM station_quarantine")
+            .execute(&mut *tx)
+            .await?;
+        Self::insert_quarantined(&mut tx, dropped).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Applies a re-validation pass: newly failing stations enter the quarantine and
+    /// recovered ones leave it.
+    pub async fn update_quarantine(
+        &self,
+        dropped: &[(Station, String)],
+        restored: &[String],
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        if !restored.is_empty() {
+            sqlx::query("DELETE FROM station_quarantine WHERE id = ANY($1)")
+                .bind(restored)
+                .execute(&mut *tx)
+                .await?;
+        }
+        Self::insert_quarantined(&mut tx, dropped).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_quarantined(
+        tx: &mut Transaction<'_, Postgres>,
+        dropped: &[(Station, String)],
+    ) -> Result<(), StorageError> {
+        const INSERT_BATCH_SIZE: usize = 500;
+
+        // One row per id, or the upsert would touch the same row twice in one statement.
+        let mut seen = HashSet::with_capacity(dropped.len());
+        let dropped: Vec<&(Station, String)> = dropped
+            .iter()
+            .filter(|(station, _)| seen.insert(station.id.as_str()))
+            .collect();
+        for chunk in dropped.chunks(INSERT_BATCH_SIZE) {
+            let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+                "INSERT INTO station_quarantine (id, stream_url, station, reason) ",
+            );
+            builder.push_values(chunk, |mut row, &(station, reason)| {
+                row.push_bind(&station.id)
+                    .push_bind(&station.stream_url)
+                    .push_bind(serde_json::to_value(station).unwrap_or(Value::Null))
+                    .push_bind(reason);
+            });
+            builder.push(
+                " ON CONFLICT (id) DO UPDATE SET stream_url = EXCLUDED.stream_url, \
+                 station = EXCLUDED.station, reason = EXCLUDED.reason, dropped_at = NOW()",
+            );
+            builder.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn load_overrides(&self) -> Result<Vec<StationOverride>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT station_id, hidden, pinned, patch, updated_at FROM station_overrides ORDER BY station_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(row_to_override).collect()
+    }
+
+    /// Stores an override and bumps the `station_state` marker so every replica drops its
+    /// cached catalogue and re-applies overrides on its next load.
+    pub async fn upsert_override(
+        &self,
+        station_id: &str,
+        hidden: bool,
+        pinned: bool,
+        patch: &StationPatch,
+    ) -> Result<StationOverride, StorageError> {
+        let patch = serde_json::to_value(patch)
+            .map_err(|err| StorageError::InvalidData(format!("override patch: {err}")))?;
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r#"
+            INSERT INTO station_overrides (station_id, hidden, pinned, patch, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (station_id) DO UPDATE SET
+                hidden = EXCLUDED.hidden,
+                pinned = EXCLUDED.pinned,
+                patch = EXCLUDED.patch,
+                updated_at = NOW()
+            RETURNING station_id, hidden, pinned, patch, updated_at
+            "#,
+        )
+        .bind(station_id)
+        .bind(hidden)
+        .bind(pinned)
+        .bind(patch)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE station_state SET updated_at = NOW() WHERE id = TRUE")
+            .execute(&mut *tx)
+            .await?;
+        self.record_override_change(&mut tx, station_id).await?;
+        tx.commit().await?;
+        row_to_override(row)
+    }
+
+    /// Removes an override; returns whether one existed.
+    pub async fn delete_override(&self, station_id: &str) -> Result<bool, StorageError> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = sqlx::query("DELETE FROM station_overrides WHERE station_id = $1")
+            .bind(station_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if deleted > 0 {
+            sqlx::query("UPDATE station_state SET updated_at = NOW() WHERE id = TRUE")
+                .execute(&mut *tx)
+                .await?;
+            self.record_override_change(&mut tx, station_id).await?;
+        }
+        tx.commit().await?;
+        Ok(deleted > 0)
+    }
+
+    /// Records an override write as a change set naming the station, so `/stations/changes`
+    /// clients refetch it hidden, patched or restored. The payload fingerprint does not move,
+    /// so the set gets a cursor of its own derived from the previous one.
+    async fn record_override_change(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: &str,
+    ) -> Result<(), StorageError> {
+        let previous: Option<String> = sqlx::query_scalar(CURRENT_CHANGE_CURSOR)
+            .fetch_one(&mut **tx)
+            .await?;
+        let mut hasher = Sha256::new();
+        hasher.update(previous.as_deref().unwrap_or_default());
+        hasher.update(b"\n");
+        hasher.update(station_id);
+        hasher.update(b"\n");
+        hasher.update(Utc::now().to_rfc3339());
+        let fingerprint = hex::encode(hasher.finalize());
+
+        sqlx::query(
+            r#"
+            INSERT INTO station_change_sets (fingerprint, previous_fingerprint, changed)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(&fingerprint)
+        .bind(&previous)
+        .bind(vec![station_id.to_string()])
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn upsert_stations(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         payload_id: i64,
-        stations: &[Station],
+        stations: &[(&Station, String)],
     ) -> Result<(), StorageError> {
         if stations.is_empty() {
             return Ok(());
         }
 
-        const COLUMNS: &str = r#"(id, payload_id, name, stream_url, homepage, favicon, country, country_code, state, languages, tags, coordinates, bitrate, codec, hls, is_online, last_checked_at, last_changed_at, click_count, click_trend, votes)"#;
+        const COLUMNS: &str = r#"(id, payload_id, name, stream_url, homepage, favicon, country, country_code, state, languages, tags, coordinates, bitrate, codec, hls, is_online, last_checked_at, last_changed_at, click_count, click_trend, votes, signature)"#;
         const INSERT_BATCH_SIZE: usize = 500;
         const UPSERT_SUFFIX: &str = r#"
             ON CONFLICT (id) DO UPDATE SET
@@ -231,6 +785,7 @@ impl StationStorage {
                 click_count = EXCLUDED.click_count,
                 click_trend = EXCLUDED.click_trend,
                 votes = EXCLUDED.votes,
+                signature = EXCLUDED.signature,
                 updated_at = NOW()
         "#;
 
@@ -240,7 +795,7 @@ impl StationStorage {
             }
             let mut builder = QueryBuilder::<Postgres>::new("INSERT INTO stations ");
             builder.push(COLUMNS).push(' ');
-            builder.push_values(chunk.iter(), |mut row, station| {
+            builder.push_values(chunk.iter(), |mut row, (station, signature)| {
                 let coordinates = station
                     .coordinates
                     .as_ref()
@@ -266,7 +821,8 @@ impl StationStorage {
                     .push_bind(&station.last_changed_at)
                     .push_bind(station.click_count)
                     .push_bind(station.click_trend)
-                    .push_bind(station.votes);
+                    .push_bind(station.votes)
+                    .push_bind(signature);
             });
 
             builder.push(UPSERT_SUFFIX);
@@ -277,6 +833,32 @@ impl StationStorage {
     }
 }
 
+/// Net result of applying change sets in order: a station removed and later re-added ends
+/// up upserted, and one changed and later removed ends up removed.
+fn fold_change_sets(sets: &[ChangeSet]) -> (Vec<String>, Vec<String>) {
+    let mut latest: HashMap<&str, bool> = HashMap::new();
+    for set in sets {
+        for id in set.added.iter().chain(&set.changed) {
+            latest.insert(id, true);
+        }
+        for id in &set.removed {
+            latest.insert(id, false);
+        }
+    }
+    let mut upserted = Vec::new();
+    let mut removed = Vec::new();
+    for (id, present) in latest {
+        if present {
+            upserted.push(id.to_string());
+        } else {
+            removed.push(id.to_string());
+        }
+    }
+    upserted.sort_unstable();
+    removed.sort_unstable();
+    (upserted, removed)
+}
+
 fn json_array_to_vec(value: Value) -> Vec<String> {
     match value {
         Value::Array(items) => items
@@ -319,6 +901,18 @@ fn row_to_station(row: PgRow) -> Result<Station, StorageError> {
     })
 }
 
+fn row_to_override(row: PgRow) -> Result<StationOverride, StorageError> {
+    let patch: Value = row.try_get("patch")?;
+    Ok(StationOverride {
+        station_id: row.try_get("station_id")?,
+        hidden: row.try_get("hidden")?,
+        pinned: row.try_get("pinned")?,
+        patch: serde_json::from_value(patch)
+            .map_err(|err| StorageError::InvalidData(format!("override patch: {err}")))?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
 fn parse_schema_version(value: Option<String>) -> Option<i32> {
     value.and_then(|raw| raw.trim().parse::<i32>().ok())
 }
@@ -330,3 +924,29 @@ fn normalize_string_array(value: Option<Vec<Option<String>>>) -> Vec<String> {
         .filter_map(|item| item.map(|v| v.trim().to_string()).filter(|s| !s.is_empty()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(added: &[&str], changed: &[&str], removed: &[&str]) -> ChangeSet {
+        let ids = |values: &[&str]| values.iter().map(|id| id.to_string()).collect();
+        ChangeSet {
+            added: ids(added),
+            changed: ids(changed),
+            removed: ids(removed),
+        }
+    }
+
+    #[test]
+    fn folds_change_sets_in_order() {
+        let (upserted, removed) = fold_change_sets(&[
+            set(&["a", "b"], &["c"], &["d"]),
+            set(&["d"], &["a"], &["b"]),
+            set(&[], &[], &["c"]),
+        ]);
+        assert_eq!(upserted, vec!["a", "d"]);
+        assert_eq!(removed, vec!["b", "c"]);
+        assert_eq!(fold_change_sets(&[]), (vec![], vec![]));
+    }
+}
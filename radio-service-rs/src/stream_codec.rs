@@ -0,0 +1,463 @@
+//! Identifies the audio inside the first bytes of a stream, so validation can correct the
+//! codec and bitrate a directory reports and reject streams that are not audio at all.
+
+/// What the first bytes of a stream turned out to be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffedAudio {
+    /// Container or framing the bytes matched, e.g. `ogg` or `adts`.
+    pub format: &'static str,
+    /// Codec in the labels Radio Browser uses, when the bytes tell. MPEG-TS without a program
+    /// table in the sample, for instance, only identifies the container.
+    pub codec: Option<&'static str>,
+    /// Average over the frames in the sample, in kbit/s. Unknown for containers whose headers
+    /// do not carry it and for lossless codecs.
+    pub bitrate: Option<i32>,
+    pub sample_rate: Option<u32>,
+}
+
+/// Consecutive frames that must line up before an MPEG audio or ADTS match is trusted; a
+/// single sync word shows up in random data far too often.
+const MIN_CONFIRMED_FRAMES: usize = 2;
+const TS_PACKET_SIZE: usize = 188;
+
+pub fn sniff_audio(bytes: &[u8]) -> Option<SniffedAudio> {
+    let Some(bytes) = skip_id3(bytes) else {
+        // Artwork easily makes the tag longer than the sample. An ID3 header is good enough
+        // evidence of audio; the codec stays whatever the directory says.
+        return Some(SniffedAudio {
+            format: "id3",
+            codec: None,
+            bitrate: None,
+            sample_rate: None,
+        });
+    };
+    if bytes.starts_with(b"fLaC") {
+        return Some(sniff_flac(bytes));
+    }
+    if let Some(ogg) = sniff_ogg(bytes) {
+        return Some(ogg);
+    }
+    if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return Some(SniffedAudio {
+            format: "webm",
+            codec: None,
+            bitrate: None,
+            sample_rate: None,
+        });
+    }
+    sniff_mpeg_ts(bytes)
+        .or_else(|| sniff_frames(bytes, adts_frame, "adts"))
+        .or_else(|| sniff_frames(bytes, mpeg_audio_frame, "mpeg-audio"))
+}
+
+/// Groups codec labels that name the same codec, so a correct but differently spelled label
+/// (`AAC+` for HE-AAC, `OGG` for Vorbis) is left alone.
+pub fn codec_family(codec: &str) -> Option<&'static str> {
+    match codec.trim().to_ascii_lowercase().as_str() {
+        "aac" | "aac+" | "aacp" | "he-aac" | "heaac" | "aac lc" => Some("AAC"),
+        "mp3" | "mpeg" | "mpeg audio" => Some("MP3"),
+        "mp2" => Some("MP2"),
+        "ogg" | "vorbis" | "ogg vorbis" => Some("OGG"),
+        "opus" | "ogg opus" => Some("OPUS"),
+        "flac" => Some("FLAC"),
+        "ac3" | "ac-3" => Some("AC3"),
+        _ => None,
+    }
+}
+
+/// The bytes after a leading ID3v2 tag, or `None` when the tag runs past the sample.
+fn skip_id3(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.len() < 10 || !bytes.starts_with(b"ID3") {
+        return Some(bytes);
+    }
+    // The tag size is four 7-bit bytes, excluding the 10-byte header.
+    let size = bytes[6..10]
+        .iter()
+        .fold(0usize, |acc, byte| (acc << 7) | usize::from(byte & 0x7F));
+    bytes.get(10 + size..).filter(|rest| !rest.is_empty())
+}
+
+fn sniff_flac(bytes: &[u8]) -> SniffedAudio {
+    // STREAMINFO is always the first metadata block: 4 bytes of block header after the
+    // marker, then 10 bytes of block and frame sizes before the 20-bit sample rate.
+    let sample_rate = bytes.get(18..21).map(|rate| {
+        (u32::from(rate[0]) << 12) | (u32::from(rate[1]) << 4) | (u32::from(rate[2]) >> 4)
+    });
+    SniffedAudio {
+        format: "flac",
+        codec: Some("FLAC"),
+        bitrate: None,
+        sample_rate: sample_rate.filter(|rate| *rate > 0),
+    }
+}
+
+/// Icecast sends the identification headers to every new listener, so the first page of an
+/// Ogg stream names its codec.
+fn sniff_ogg(bytes: &[u8]) -> Option<SniffedAudio> {
+    let start = find(bytes, b"OggS")?;
+    let page = &bytes[start..];
+    let segments = usize::from(*page.get(26)?);
+    let packet = page.get(27 + segments..).unwrap_or_default();
+    let le_u32 = |offset: usize| {
+        packet
+            .get(offset..offset + 4)
+            .map(|raw| u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+    };
+
+    let mut sniffed = SniffedAudio {
+        format: "ogg",
+        codec: None,
+        bitrate: None,
+        sample_rate: None,
+    };
+    if packet.starts_with(b"\x01vorbis") {
+        sniffed.codec = Some("OGG");
+        sniffed.sample_rate = le_u32(12).filter(|rate| *rate > 0);
+        sniffed.bitrate = le_u32(20)
+            .map(|nominal| nominal as i32)
+            .filter(|nominal| *nominal > 0)
+            .map(|nominal| nominal.saturating_add(500) / 1000);
+    } else if packet.starts_with(b"OpusHead") {
+        // Opus always decodes at 48 kHz whatever the input rate in the header says.
+        sniffed.codec = Some("OPUS");
+        sniffed.sample_rate = Some(48_000);
+    } else if packet.starts_with(b"\x7fFLAC") {
+        sniffed.codec = Some("FLAC");
+        // Mapping header: marker, version and header count, then a native `fLaC` stream.
+        sniffed.sample_rate = packet
+            .get(9..)
+            .and_then(|flac| sniff_flac(flac).sample_rate);
+    }
+    Some(sniffed)
+}
+
+fn sniff_mpeg_ts(bytes: &[u8]) -> Option<SniffedAudio> {
+    let start = (0..TS_PACKET_SIZE.min(bytes.len())).find(|offset| {
+        (0..3).all(|packet| bytes.get(offset + packet * TS_PACKET_SIZE) == Some(&0x47))
+    })?;
+    let packets: Vec<&[u8]> = bytes[start..]
+        .chunks_exact(TS_PACKET_SIZE)
+        .take_while(|packet| packet[0] == 0x47)
+        .collect();
+
+    let mut pmt_pid = None;
+    let mut codec = None;
+    for packet in &packets {
+        let pid = (u16::from(packet[1] & 0x1F) << 8) | u16::from(packet[2]);
+        let Some(section) = ts_section(packet) else {
+            continue;
+        };
+        if pid == 0 && section.first() == Some(&0x00) {
+            // PAT: the first program's PMT PID.
+            pmt_pid = section
+                .get(10..12)
+                .map(|raw| (u16::from(raw[0] & 0x1F) << 8) | u16::from(raw[1]));
+        } else if Some(pid) == pmt_pid && section.first() == Some(&0x02) {
+            codec = pmt_audio_codec(section);
+            break;
+        }
+    }
+    Some(SniffedAudio {
+        format: "mpeg-ts",
+        codec,
+        bitrate: None,
+        sample_rate: None,
+    })
+}
+
+/// The PSI section starting in `packet`, if the packet starts one.
+fn ts_section(packet: &[u8]) -> Option<&[u8]> {
+    let payload_unit_start = packet[1] & 0x40 != 0;
+    let adaptation = (packet[3] >> 4) & 0x03;
+    if !payload_unit_start || adaptation & 0x01 == 0 {
+        return None;
+    }
+    let mut offset = 4;
+    if adaptation & 0x02 != 0 {
+        offset += 1 + usize::from(*packet.get(4)?);
+    }
+    let pointer = usize::from(*packet.get(offset)?);
+    packet.get(offset + 1 + pointer..)
+}
+
+fn pmt_audio_codec(section: &[u8]) -> Option<&'static str> {
+    let section_length = usize::from(section.get(1)? & 0x0F) << 8 | usize::from(*section.get(2)?);
+    let end = (3 + section_length).saturating_sub(4).min(section.len());
+    let program_info_length =
+        usize::from(section.get(10)? & 0x0F) << 8 | usize::from(*section.get(11)?);
+    let mut offset = 12 + program_info_length;
+    while offset + 5 <= end {
+        let stream_type = section[offset];
+        let es_info_length =
+            usize::from(section[offset + 3] & 0x0F) << 8 | usize::from(section[offset + 4]);
+        let codec = match stream_type {
+            0x03 | 0x04 => Some("MP3"),
+            0x0F | 0x11 => Some("AAC"),
+            0x81 => Some("AC3"),
+            _ => None,
+        };
+        if codec.is_some() {
+            return codec;
+        }
+        offset += 5 + es_info_length;
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Frame {
+    codec: &'static str,
+    length: usize,
+    bitrate_bps: u32,
+    sample_rate: u32,
+}
+
+/// Finds the first run of `MIN_CONFIRMED_FRAMES` back-to-back frames with the same codec and
+/// sample rate, then averages the bitrate over every frame that follows in the sample.
+fn sniff_frames(
+    bytes: &[u8],
+    parse: fn(&[u8]) -> Option<Frame>,
+    format: &'static str,
+) -> Option<SniffedAudio> {
+    for start in 0..bytes.len() {
+        let Some(first) = parse(&bytes[start..]) else {
+            continue;
+        };
+        let mut frames = vec![first];
+        let mut offset = start + first.length;
+        while let Some(frame) = bytes.get(offset..).and_then(parse) {
+            if frame.codec != first.codec || frame.sample_rate != first.sample_rate {
+                break;
+            }
+            frames.push(frame);
+            offset += frame.length;
+        }
+        if frames.len() < MIN_CONFIRMED_FRAMES {
+            continue;
+        }
+        let average_bps = frames
+            .iter()
+            .map(|frame| u64::from(frame.bitrate_bps))
+            .sum::<u64>()
+            / frames.len() as u64;
+        return Some(SniffedAudio {
+            format,
+            codec: Some(first.codec),
+            bitrate: i32::try_from((average_bps + 500) / 1000)
+                .ok()
+                .filter(|kbps| *kbps > 0),
+            sample_rate: Some(first.sample_rate),
+        });
+    }
+    None
+}
+
+const ADTS_SAMPLE_RATES: [u32; 13] = [
+    96_000, 88_200, 64_000, 48_000, 44_100, 32_000, 24_000, 22_050, 16_000, 12_000, 11_025, 8_000,
+    7_350,
+];
+
+fn adts_frame(bytes: &[u8]) -> Option<Frame> {
+    let header = bytes.get(..7)?;
+    // 12-bit sync word followed by the two layer bits, which ADTS always sets to zero.
+    if header[0] != 0xFF || header[1] & 0xF6 != 0xF0 {
+        return None;
+    }
+    let sample_rate = *ADTS_SAMPLE_RATES.get(usize::from((header[2] >> 2) & 0x0F))?;
+    let length = usize::from(header[3] & 0x03) << 11
+        | usize::from(header[4]) << 3
+        | usize::from(header[5] >> 5);
+    if length < 7 {
+        return None;
+    }
+    Some(Frame {
+        codec: "AAC",
+        length,
+        // Every ADTS frame holds 1024 samples.
+        bitrate_bps: u32::try_from(length as u64 * 8 * u64::from(sample_rate) / 1024).ok()?,
+        sample_rate,
+    })
+}
+
+const MPEG1_LAYER3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MPEG1_LAYER2_KBPS: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+];
+const MPEG2_LAYER23_KBPS: [u32; 15] =
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+
+/// MPEG-1/2/2.5 Layer II and III frame headers. Layer I and free-format frames are too rare
+/// in radio to be worth telling apart from noise.
+fn mpeg_audio_frame(bytes: &[u8]) -> Option<Frame> {
+    let header = bytes.get(..4)?;
+    if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (header[1] >> 3) & 0x03;
+    let layer = (header[1] >> 1) & 0x03;
+    let bitrate_index = usize::from(header[2] >> 4);
+    let sample_rate_index = usize::from((header[2] >> 2) & 0x03);
+    let padding = usize::from((header[2] >> 1) & 0x01);
+    if version == 0x01 || bitrate_index == 0 || bitrate_index == 0x0F {
+        return None;
+    }
+
+    let mpeg1 = version == 0x03;
+    let (codec, kbps) = match (layer, mpeg1) {
+        (0x01, true) => ("MP3", MPEG1_LAYER3_KBPS[bitrate_index]),
+        (0x01, false) => ("MP3", MPEG2_LAYER23_KBPS[bitrate_index]),
+        (0x02, true) => ("MP2", MPEG1_LAYER2_KBPS[bitrate_index]),
+        (0x02, false) => ("MP2", MPEG2_LAYER23_KBPS[bitrate_index]),
+        _ => return None,
+    };
+    let sample_rate = MPEG1_SAMPLE_RATES.get(sample_rate_index)?
+        / match version {
+            0x03 => 1,
+            0x02 => 2,
+            _ => 4,
+        };
+    // MPEG-2/2.5 Layer III frames hold half as many samples as every other combination.
+    let samples_per_byte = if layer == 0x01 && !mpeg1 { 72 } else { 144 };
+    let length = (samples_per_byte * kbps * 1000 / sample_rate) as usize + padding;
+    Some(Frame {
+        codec,
+        length,
+        bitrate_bps: kbps * 1000,
+        sample_rate,
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Back-to-back frames with `header` and a zero-filled body, behind some leading garbage.
+    fn frames(header: &[u8], length: usize, count: usize) -> Vec<u8> {
+        let mut bytes = vec![0x12, 0xFF, 0x00];
+        for _ in 0..count {
+            bytes.extend_from_slice(header);
+            bytes.resize(bytes.len() + length - header.len(), 0);
+        }
+        bytes
+    }
+
+    #[test]
+    fn detects_mpeg_audio_and_adts_frames() {
+        // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, no padding: 417-byte frames.
+        let mp3 = sniff_audio(&frames(&[0xFF, 0xFB, 0x90, 0x64], 417, 4)).unwrap();
+        assert_eq!(mp3.codec, Some("MP3"));
+        assert_eq!(mp3.bitrate, Some(128));
+        assert_eq!(mp3.sample_rate, Some(44_100));
+
+        // ADTS AAC-LC, 44.1 kHz, 372-byte frames: 372 * 8 * 44100 / 1024 ≈ 128 kbit/s.
+        let length = 372usize;
+        let header = [
+            0xFF,
+            0xF1,
+            0x50,
+            0x80 | ((length >> 11) as u8 & 0x03),
+            (length >> 3) as u8,
+            ((length as u8 & 0x07) << 5) | 0x1F,
+            0xFC,
+        ];
+        let aac = sniff_audio(&frames(&header, length, 3)).unwrap();
+        assert_eq!(aac.format, "adts");
+        assert_eq!(aac.codec, Some("AAC"));
+        assert_eq!(aac.bitrate, Some(128));
+        assert_eq!(aac.sample_rate, Some(44_100));
+
+        // A lone sync word without a following frame is not audio.
+        assert_eq!(
+            sniff_audio(&frames(&[0xFF, 0xFB, 0x90, 0x64], 417, 1)),
+            None
+        );
+        assert_eq!(sniff_audio(b"<html><body>Not found</body></html>"), None);
+    }
+
+    #[test]
+    fn detects_ogg_flac_and_mpeg_ts() {
+        let mut vorbis = b"OggS\x00\x02".to_vec();
+        vorbis.resize(26, 0);
+        vorbis.extend_from_slice(&[1, 30]);
+        vorbis.extend_from_slice(b"\x01vorbis");
+        vorbis.extend_from_slice(&0u32.to_le_bytes());
+        vorbis.push(2);
+        vorbis.extend_from_slice(&48_000u32.to_le_bytes());
+        vorbis.extend_from_slice(&0i32.to_le_bytes());
+        vorbis.extend_from_slice(&96_000i32.to_le_bytes());
+        let ogg = sniff_audio(&vorbis).unwrap();
+        assert_eq!(ogg.codec, Some("OGG"));
+        assert_eq!(ogg.bitrate, Some(96));
+        assert_eq!(ogg.sample_rate, Some(48_000));
+
+        let mut flac = b"fLaC\x00\x00\x00\x22".to_vec();
+        flac.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        flac.extend_from_slice(&[0x0A, 0xC4, 0x42]);
+        assert_eq!(sniff_audio(&flac).unwrap().sample_rate, Some(44_100));
+
+        let mut ts = Vec::new();
+        let mut packet = |pid: u16, section: &[u8]| {
+            let mut bytes = vec![0x47, 0x40 | (pid >> 8) as u8, pid as u8, 0x10, 0x00];
+            bytes.extend_from_slice(section);
+            bytes.resize(TS_PACKET_SIZE, 0xFF);
+            ts.extend_from_slice(&bytes);
+        };
+        packet(
+            0,
+            &[
+                0x00, 0xB0, 0x0D, 0, 1, 0xC1, 0, 0, 0, 1, 0xF0, 0x00, 0, 0, 0, 0,
+            ],
+        );
+        packet(
+            0x1000,
+            &[
+                0x02, 0xB0, 0x12, 0, 1, 0xC1, 0, 0, 0xE1, 0x00, 0xF0, 0x00, 0x0F, 0xE1, 0x01, 0xF0,
+                0x00, 0, 0, 0, 0,
+            ],
+        );
+        packet(0x0101, &[]);
+        let sniffed = sniff_audio(&ts).unwrap();
+        assert_eq!(sniffed.format, "mpeg-ts");
+        assert_eq!(sniffed.codec, Some("AAC"));
+
+        assert_eq!(codec_family("AAC+"), codec_family("aac"));
+        assert_ne!(codec_family("OGG"), codec_family("OPUS"));
+    }
+
+    #[test]
+    fn truncated_headers_are_inconclusive_rather_than_fatal() {
+        let mut ogg_flac = b"OggS\x00\x02".to_vec();
+        ogg_flac.resize(26, 0);
+        ogg_flac.extend_from_slice(&[1, 51]);
+        ogg_flac.extend_from_slice(b"\x7fFLAC\x01");
+        for len in 0..=ogg_flac.len() {
+            sniff_audio(&ogg_flac[..len]);
+        }
+        let sniffed = sniff_audio(&ogg_flac).unwrap();
+        assert_eq!(sniffed.codec, Some("FLAC"));
+        assert_eq!(sniffed.sample_rate, None);
+
+        let mut vorbis = b"OggS\x00\x02".to_vec();
+        vorbis.resize(26, 0);
+        vorbis.extend_from_slice(&[1, 30]);
+        vorbis.extend_from_slice(b"\x01vorbis");
+        vorbis.resize(vorbis.len() + 13, 0);
+        vorbis.extend_from_slice(&i32::MAX.to_le_bytes());
+        assert_eq!(sniff_audio(&vorbis).unwrap().bitrate, Some(i32::MAX / 1000));
+
+        // A 1 MB ID3 tag (artwork) in a 4 KB sample.
+        let mut tagged = b"ID3\x04\x00\x00\x00\x40\x00\x00".to_vec();
+        tagged.resize(4096, 0);
+        let sniffed = sniff_audio(&tagged).unwrap();
+        assert_eq!(sniffed.format, "id3");
+    }
+}
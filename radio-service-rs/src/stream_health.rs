@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+
+/// Rolling window the uptime and reliability figures are computed over.
+const HEALTH_WINDOW_DAYS: i64 = 7;
+/// Raw checks are kept a little longer than the window so it never starts half empty.
+const HEALTH_RETENTION_DAYS: i64 = 14;
+/// Age at which a check counts half as much as a fresh one in the reliability score.
+const RECENCY_HALF_LIFE_HOURS: f64 = 24.0;
+/// Median latency at which the reliability penalty starts and where it is fully applied.
+const LATENCY_PENALTY_START_MS: f64 = 750.0;
+const LATENCY_PENALTY_FULL_MS: f64 = 5000.0;
+const MAX_LATENCY_PENALTY: f64 = 0.2;
+const INSERT_CHUNK_SIZE: usize = 1000;
+
+/// One `StreamValidator` probe of a station's stream.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub station_id: String,
+    pub stream_url: String,
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub reason: Option<String>,
+    pub latency_ms: Option<u32>,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HealthScore {
+    /// Share of successful checks in the window, 0–100.
+    #[serde(rename = "uptime")]
+    pub uptime_percent: f64,
+    /// Recency-weighted uptime shrunk towards 50 for stations with few checks and penalised
+    /// for slow streams, 0–100.
+    pub reliability: f64,
+    #[serde(rename = "checks")]
+    pub check_count: i64,
+}
+
+/// Health scores keyed by station id, tagged with the catalogue version they were loaded for.
+#[derive(Debug, Default)]
+pub struct HealthScores {
+    pub version: String,
+    /// The later of the station state marker and the newest check in the window; health
+    /// changes move listings' `Last-Modified` even when the catalogue did not.
+    pub updated_at: Option<DateTime<Utc>>,
+    scores: HashMap<String, HealthScore>,
+}
+
+impl HealthScores {
+    pub fn get(&self, station_id: &str) -> Option<HealthScore> {
+        self.scores.get(station_id).copied()
+    }
+}
+
+pub async fn record_checks(postgres: &PgPool, checks: &[HealthCheck]) -> anyhow::Result<()> {
+    if checks.is_empty() {
+        return Ok(());
+    }
+    let mut tx = postgres.begin().await?;
+    for chunk in checks.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO station_health_checks (station_id, stream_url, ok, status, reason, latency_ms, content_type) ",
+        );
+        builder.push_values(chunk, |mut row, check| {
+            row.push_bind(&check.station_id)
+                .push_bind(&check.stream_url)
+                .push_bind(check.ok)
+                .push_bind(check.status.map(i32::from))
+                .push_bind(&check.reason)
+                .push_bind(
+                    check
+                        .latency_ms
+                        .map(|ms| i32::try_from(ms).unwrap_or(i32::MAX)),
+                )
+                .push_bind(&check.content_type);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+    sqlx::query(
+        "DELETE FROM station_health_checks WHERE checked_at < NOW() - ($1 * interval '1 day')",
+    )
+    .bind(HEALTH_RETENTION_DAYS)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn load_scores(
+    postgres: &PgPool,
+    updated_at: Option<DateTime<Utc>>,
+) -> anyhow::Result<HealthScores> {
+    let rows = sqlx::query(
+        r#"
+        SELECT station_id,
+               COUNT(*) AS checks,
+               COUNT(*) FILTER (WHERE ok) AS ok_checks,
+               SUM(POWER(0.5, EXTRACT(EPOCH FROM NOW() - checked_at) / 3600.0 / $2)) AS weight,
+               SUM(POWER(0.5, EXTRACT(EPOCH FROM NOW() - checked_at) / 3600.0 / $2))
+                   FILTER (WHERE ok) AS ok_weight,
+               PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY latency_ms)
+                   FILTER (WHERE ok AND latency_ms IS NOT NULL) AS median_latency_ms,
+               MAX(checked_at) AS last_checked_at
+        FROM station_health_checks
+        WHERE checked_at > NOW() - ($1 * interval '1 day')
+        GROUP BY station_id
+        "#,
+    )
+    .bind(HEALTH_WINDOW_DAYS)
+    .bind(RECENCY_HALF_LIFE_HOURS)
+    .fetch_all(postgres)
+    .await?;
+
+    let mut scores = HashMap::with_capacity(rows.len());
+    let mut updated_at = updated_at;
+    for row in rows {
+        let last_checked_at: DateTime<Utc> = row.try_get("last_checked_at")?;
+        updated_at = updated_at.max(Some(last_checked_at));
+        let checks: i64 = row.try_get("checks")?;
+        let ok_checks: i64 = row.try_get("ok_checks")?;
+        let weight: f64 = row.try_get::<Option<f64>, _>("weight")?.unwrap_or(0.0);
+        let ok_weight: f64 = row.try_get::<Option<f64>, _>("ok_weight")?.unwrap_or(0.0);
+        let median_latency: Option<f64> = row.try_get("median_latency_ms")?;
+        scores.insert(
+            row.try_get("station_id")?,
+            score(checks, ok_checks, weight, ok_weight, median_latency),
+        );
+    }
+    Ok(HealthScores {
+        version: updated_at
+            .map(|value| value.timestamp_millis().to_string())
+            .unwrap_or_default(),
+        updated_at,
+        scores,
+    })
+}
+
+fn score(
+    checks: i64,
+    ok_checks: i64,
+    weight: f64,
+    ok_weight: f64,
+    median_latency_ms: Option<f64>,
+) -> HealthScore {
+    let uptime = if checks > 0 {
+        ok_checks as f64 / checks as f64
+    } else {
+        0.0
+    };
+    // One success and one failure of prior keeps a single lucky check from scoring 100.
+    let weighted = (ok_weight + 1.0) / (weight + 2.0);
+    let latency_penalty = median_latency_ms.map_or(0.0, |latency| {
+        let over = (latency - LATENCY_PENALTY_START_MS)
+            / (LATENCY_PENALTY_FULL_MS - LATENCY_PENALTY_START_MS);
+        over.clamp(0.0, 1.0) * MAX_LATENCY_PENALTY
+    });
+    HealthScore {
+        uptime_percent: round_tenth(uptime * 100.0),
+        reliability: round_tenth(weighted * (1.0 - latency_penalty) * 100.0),
+        check_count: checks,
+    }
+}
+
+fn round_tenth(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reliability_rewards_history_and_penalises_latency() {
+        let steady = score(40, 40, 20.0, 20.0, Some(300.0));
+        assert_eq!(steady.uptime_percent, 100.0);
+        assert!(steady.reliability > 95.0);
+
+        let single = score(1, 1, 1.0, 1.0, Some(300.0));
+        assert_eq!(single.uptime_percent, 100.0);
+        assert!(single.reliability < steady.reliability);
+
+        let slow = score(40, 40, 20.0, 20.0, Some(LATENCY_PENALTY_FULL_MS * 2.0));
+        assert!((slow.reliability - steady.reliability * (1.0 - MAX_LATENCY_PENALTY)).abs() < 0.2);
+
+        // Recent failures weigh more than the overall uptime suggests.
+        let degrading = score(10, 8, 4.0, 2.0, None);
+        assert_eq!(degrading.uptime_percent, 80.0);
+        assert_eq!(degrading.reliability, 50.0);
+
+        let dead = score(5, 0, 3.0, 0.0, None);
+        assert_eq!(dead.uptime_percent, 0.0);
+        assert_eq!(dead.reliability, 20.0);
+    }
+}
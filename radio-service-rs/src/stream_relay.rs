@@ -0,0 +1,486 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures_util::{stream, Stream, StreamExt};
+use reqwest::{header::HeaderMap, Client, StatusCode};
+use serde_json::json;
+use thiserror::Error;
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        OnceCell,
+    },
+    task::JoinHandle,
+    time::timeout,
+};
+
+use crate::logging::logger;
+use crate::{config::StreamRelayConfig, stations::Station};
+
+/// Response headers worth passing on to every listener of a shared relay.
+const RELAYED_HEADERS: &[&str] = &[
+    "content-type",
+    "icy-br",
+    "icy-description",
+    "icy-genre",
+    "icy-name",
+    "icy-sr",
+    "icy-url",
+];
+
+#[derive(Debug, Clone, Error)]
+pub enum RelayError {
+    #[error("stream relay capacity reached")]
+    AtCapacity,
+    #[error("stream is not a continuous audio stream")]
+    NotRelayable,
+    #[error("upstream request timed out")]
+    Timeout,
+    #[error("failed to reach stream URL")]
+    Network,
+    #[error("upstream returned {0}")]
+    Upstream(StatusCode),
+}
+
+/// Fans a single upstream connection per station out to every listener. Listeners that
+/// fall behind skip ahead to live audio and are disconnected if they keep lagging.
+#[derive(Clone)]
+pub struct StreamRelayHub {
+    client: Client,
+    config: StreamRelayConfig,
+    user_agent: String,
+    connect_timeout: Duration,
+    relays: Arc<Mutex<HashMap<String, Arc<Relay>>>>,
+}
+
+struct Relay {
+    stream_url: String,
+    sender: Mutex<Option<broadcast::Sender<Bytes>>>,
+    burst: Mutex<Burst>,
+    burst_limit: usize,
+    ready: OnceCell<Result<HeaderMap, RelayError>>,
+    listeners: Mutex<usize>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[derive(Default)]
+struct Burst {
+    chunks: VecDeque<Bytes>,
+    bytes: usize,
+}
+
+pub struct RelayListener {
+    pub headers: HeaderMap,
+    prefill: Vec<Bytes>,
+    receiver: broadcast::Receiver<Bytes>,
+    max_lag_events: usize,
+    station_id: String,
+    guard: ListenerGuard,
+}
+
+struct ListenerGuard {
+    station_id: String,
+    relay: Arc<Relay>,
+    relays: Arc<Mutex<HashMap<String, Arc<Relay>>>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelayStats {
+    pub relays: usize,
+    pub listeners: usize,
+}
+
+impl StreamRelayHub {
+    pub fn new(
+        config: StreamRelayConfig,
+        client: Client,
+        user_agent: String,
+        connect_timeout: Duration,
+    ) -> Self {
+        Self {
+            client,
+            config,
+            user_agent,
+            connect_timeout,
+            relays: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn stats(&self) -> RelayStats {
+        let relays = self.relays.lock().unwrap_or_else(|err| err.into_inner());
+        RelayStats {
+            relays: relays.len(),
+            listeners: relays
+                .values()
+                .map(|relay| {
+                    *relay
+                        .listeners
+                        .lock()
+                        .unwrap_or_else(|err| err.into_inner())
+                })
+                .sum(),
+        }
+    }
+
+    /// Joins (or starts) the relay for a station. The listener is registered before the
+    /// upstream connection completes so no audio produced in the meantime is lost.
+    pub async fn join(&self, station: &Station) -> Result<RelayListener, RelayError> {
+        let (relay, prefill, receiver, guard) = {
+            let mut relays = self.relays.lock().unwrap_or_else(|err| err.into_inner());
+            let relay = match relays.get(&station.id) {
+                Some(existing) if existing.stream_url == station.stream_url => existing.clone(),
+                _ => {
+                    if relays.len() >= self.config.max_relays {
+                        return Err(RelayError::AtCapacity);
+                    }
+                    let relay = Arc::new(Relay::new(&station.stream_url, &self.config));
+                    relays.insert(station.id.clone(), relay.clone());
+                    relay
+                }
+            };
+            *relay
+                .listeners
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()) += 1;
+            let (prefill, receiver) = relay.subscribe();
+            let guard = ListenerGuard {
+                station_id: station.id.clone(),
+                relay: relay.clone(),
+                relays: self.relays.clone(),
+            };
+            (relay, prefill, receiver, guard)
+        };
+
+        let headers = relay
+            .ready
+            .get_or_init(|| self.connect(station, relay.clone()))
+            .await
+            .clone()?;
+
+        Ok(RelayListener {
+            headers,
+            prefill,
+            receiver,
+            max_lag_events: self.config.max_lag_events,
+            station_id: station.id.clone(),
+            guard,
+        })
+    }
+
+    async fn connect(&self, station: &Station, relay: Arc<Relay>) -> Result<HeaderMap, RelayError> {
+        let request = self
+            .client
+            .get(&station.stream_url)
+            .header("accept", "*/*")
+            .header("user-agent", &self.user_agent);
+        let response = timeout(self.connect_timeout, request.send())
+            .await
+            .map_err(|_| RelayError::Timeout)?
+            .map_err(|_| RelayError::Network)?;
+        if !response.status().is_success() {
+            return Err(RelayError::Upstream(response.status()));
+        }
+
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        if !is_relayable_content_type(content_type) {
+            return Err(RelayError::NotRelayable);
+        }
+
+        let mut headers = HeaderMap::new();
+        for &name in RELAYED_HEADERS {
+            if let Some(value) = response.headers().get(name) {
+                if let Ok(header_name) =
+                    reqwest::header::HeaderName::from_lowercase(name.as_bytes())
+                {
+                    headers.insert(header_name, value.clone());
+                }
+            }
+        }
+
+        logger().info(
+            "stream_relay.started",
+            json!({ "stationId": station.id, "relays": self.stats().relays }),
+        );
+        let station_id = station.id.clone();
+        let relays = self.relays.clone();
+        let pump_relay = relay.clone();
+        let task = tokio::spawn(async move {
+            let mut upstream = response.bytes_stream();
+            let reason = loop {
+                match upstream.next().await {
+                    Some(Ok(chunk)) => pump_relay.publish(chunk),
+                    Some(Err(_)) => break "upstream-error",
+                    None => break "upstream-ended",
+                }
+            };
+            logger().info(
+                "stream_relay.stopped",
+                json!({ "stationId": station_id, "reason": reason }),
+            );
+            // Unlist the relay first so nobody joins it, then close the channel so the
+            // current listeners finish their responses.
+            {
+                let mut relays = relays.lock().unwrap_or_else(|err| err.into_inner());
+                if relays
+                    .get(&station_id)
+                    .is_some_and(|current| Arc::ptr_eq(current, &pump_relay))
+                {
+                    relays.remove(&station_id);
+                }
+            }
+            pump_relay.close();
+        });
+        *relay.task.lock().unwrap_or_else(|err| err.into_inner()) = Some(task);
+        Ok(headers)
+    }
+}
+
+impl Relay {
+    fn new(stream_url: &str, config: &StreamRelayConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.buffer_chunks);
+        Self {
+            stream_url: stream_url.to_string(),
+            sender: Mutex::new(Some(sender)),
+            burst: Mutex::new(Burst::default()),
+            burst_limit: config.burst_bytes,
+            ready: OnceCell::new(),
+            listeners: Mutex::new(0),
+            task: Mutex::new(None),
+        }
+    }
+
+    fn subscribe(&self) -> (Vec<Bytes>, broadcast::Receiver<Bytes>) {
+        // Holding the burst lock while subscribing keeps the prefill and the live
+        // channel contiguous: publish() appends and sends under the same lock.
+        let burst = self.burst.lock().unwrap_or_else(|err| err.into_inner());
+        let receiver = match self
+            .sender
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .as_ref()
+        {
+            Some(sender) => sender.subscribe(),
+            None => broadcast::channel(1).1,
+        };
+        (burst.chunks.iter().cloned().collect(), receiver)
+    }
+
+    fn publish(&self, chunk: Bytes) {
+        if chunk.is_empty() {
+            return;
+        }
+        let mut burst = self.burst.lock().unwrap_or_else(|err| err.into_inner());
+        burst.bytes += chunk.len();
+        burst.chunks.push_back(chunk.clone());
+        while burst.bytes > self.burst_limit && burst.chunks.len() > 1 {
+            if let Some(front) = burst.chunks.pop_front() {
+                burst.bytes -= front.len();
+            }
+        }
+        if let Some(sender) = self
+            .sender
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .as_ref()
+        {
+            let _ = sender.send(chunk);
+        }
+    }
+
+    fn close(&self) {
+        self.sender
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+    }
+}
+
+impl RelayListener {
+    /// Audio for this listener: the burst buffer first, then live chunks.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send {
+        let RelayListener {
+            prefill,
+            receiver,
+            max_lag_events,
+            station_id,
+            guard,
+            ..
+        } = self;
+        let live = stream::unfold(
+            (receiver, 0usize, guard),
+            move |(mut receiver, mut lag_events, guard)| {
+                let station_id = station_id.clone();
+                async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(chunk) => return Some((Ok(chunk), (receiver, lag_events, guard))),
+                            Err(RecvError::Lagged(skipped)) => {
+                                lag_events += 1;
+                                if lag_events > max_lag_events {
+                                    logger().info(
+                                        "stream_relay.listener_dropped",
+                                        json!({ "stationId": station_id, "skipped": skipped }),
+                                    );
+                                    return None;
+                                }
+                            }
+                            Err(RecvError::Closed) => return None,
+                        }
+                    }
+                }
+            },
+        );
+        stream::iter(prefill.into_iter().map(Ok)).chain(live)
+    }
+}
+
+impl Drop for ListenerGuard {
+    fn drop(&mut self) {
+        let mut relays = self.relays.lock().unwrap_or_else(|err| err.into_inner());
+        let mut listeners = self
+            .relay
+            .listeners
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *listeners = listeners.saturating_sub(1);
+        if *listeners > 0 {
+            return;
+        }
+        if relays
+            .get(&self.station_id)
+            .is_some_and(|current| Arc::ptr_eq(current, &self.relay))
+        {
+            relays.remove(&self.station_id);
+        }
+        if let Some(task) = self
+            .relay
+            .task
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+        {
+            task.abort();
+        }
+    }
+}
+
+fn is_relayable_content_type(content_type: &str) -> bool {
+    let lower = content_type.to_ascii_lowercase();
+    if lower.contains("mpegurl") || lower.contains("scpls") || lower.contains("x-ms-asf") {
+        return false;
+    }
+    lower.starts_with("audio/")
+        || lower.starts_with("application/ogg")
+        || lower.starts_with("video/mp2t")
+        || lower.starts_with("application/octet-stream")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stations::test_station;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{body::Body, response::Response, routing::get, Router};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn listeners_share_one_upstream_connection() {
+        crate::logging::init_logger("radio-service-test");
+        let connections = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel::<Bytes>(8);
+        let receiver = Arc::new(tokio::sync::Mutex::new(Some(receiver)));
+        let router = Router::new().route(
+            "/live",
+            get({
+                let connections = connections.clone();
+                move || {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let receiver = receiver.clone();
+                    async move {
+                        let receiver = receiver.lock().await.take().expect("single upstream");
+                        let chunks = stream::unfold(receiver, |mut receiver| async {
+                            receiver
+                                .recv()
+                                .await
+                                .map(|chunk| (Ok::<_, std::io::Error>(chunk), receiver))
+                        });
+                        Response::builder()
+                            .header("content-type", "audio/mpeg")
+                            .body(Body::from_stream(chunks))
+                            .unwrap()
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let hub = StreamRelayHub::new(
+            StreamRelayConfig {
+                enabled: true,
+                max_relays: 1,
+                buffer_chunks: 8,
+                burst_bytes: 1024,
+                max_lag_events: 3,
+            },
+            Client::new(),
+            "relay-test".into(),
+            Duration::from_secs(5),
+        );
+        let station = Station {
+            stream_url: format!("http://{addr}/live"),
+            ..test_station("relay-station")
+        };
+
+        let first = hub.join(&station).await.expect("first listener");
+        sender.send(Bytes::from_static(b"intro")).await.unwrap();
+        let mut first = Box::pin(first.into_stream());
+        assert_eq!(first.next().await.unwrap().unwrap(), "intro");
+
+        // Late joiners get the burst buffer before live audio.
+        let second = hub.join(&station).await.expect("second listener");
+        assert_eq!(second.headers["content-type"], "audio/mpeg");
+        let mut second = Box::pin(second.into_stream());
+        assert_eq!(second.next().await.unwrap().unwrap(), "intro");
+        sender.send(Bytes::from_static(b"live")).await.unwrap();
+        assert_eq!(first.next().await.unwrap().unwrap(), "live");
+        assert_eq!(second.next().await.unwrap().unwrap(), "live");
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        assert_eq!(hub.stats().relays, 1);
+        assert_eq!(hub.stats().listeners, 2);
+        let mut other = station.clone();
+        other.id = "other".into();
+        assert!(matches!(
+            hub.join(&other).await,
+            Err(RelayError::AtCapacity)
+        ));
+
+        drop(first);
+        drop(second);
+        assert_eq!(hub.stats().relays, 0);
+    }
+
+    #[test]
+    fn only_continuous_audio_is_relayed() {
+        assert!(is_relayable_content_type("audio/mpeg"));
+        assert!(is_relayable_content_type("application/ogg"));
+        assert!(!is_relayable_content_type("audio/x-mpegurl"));
+        assert!(!is_relayable_content_type("audio/x-scpls"));
+        assert!(!is_relayable_content_type("text/html"));
+    }
+}
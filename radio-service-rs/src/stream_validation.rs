@@ -1,21 +1,39 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::Arc,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use futures_util::{stream, StreamExt};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use sqlx::PgPool;
-use tokio::time::timeout;
+use tokio::time::{timeout, timeout_at};
+use url::Url;
 
 use crate::{
     config::StreamValidationConfig,
-    stations::{build_station_signature, is_blocked_domain, Station},
+    hls::{
+        first_variant_uri, is_hls_url, is_segment_origin_allowed, last_media_segment,
+        resolve_playlist_uri, MediaSegment,
+    },
+    logging::logger,
+    playlist::{is_hls_playlist, PlaylistEntry, StreamPlaylist},
+    stations::{build_station_signature, is_blocked_domain, sanitize_stream_url, Station},
+    stream_codec::{codec_family, sniff_audio, SniffedAudio},
+    stream_health::{self, HealthCheck},
 };
 
+/// Wrapper playlists list a handful of mirrors; probing more only slows a refresh down.
+const MAX_PLAYLIST_CANDIDATES: usize = 5;
+const MAX_PLAYLIST_BYTES: usize = 64 * 1024;
+/// Bytes sniffed for the codec; matches the range requested in `VALIDATION_HEADERS`.
+const SNIFF_BYTES: usize = 4096;
+/// Directory bitrates within this share of the measured one are left alone: VBR streams and
+/// a few frames of sample never average out to the nominal rate exactly.
+const BITRATE_TOLERANCE: f64 = 0.2;
+
 const VALIDATION_HEADERS: &[(&str, &str)] = &[
     ("range", "bytes=0-4095"),
     ("accept", "*/*"),
@@ -34,6 +52,8 @@ pub struct ValidationSummary {
     pub stations: Vec<Station>,
     pub dropped: usize,
     pub reasons: HashMap<String, i32>,
+    /// Dropped stations with their reason, so they can be re-checked and restored later.
+    pub dropped_stations: Vec<(Station, String)>,
 }
 
 impl StreamValidator {
@@ -51,6 +71,7 @@ impl StreamValidator {
                 stations,
                 dropped: 0,
                 reasons: HashMap::new(),
+                dropped_stations: Vec::new(),
             });
         }
 
@@ -60,8 +81,7 @@ impl StreamValidator {
             .collect();
         let cache = Arc::new(self.load_cache(postgres, &stream_urls).await?);
         let now = current_timestamp();
-        let validation_user_agent = std::env::var("RADIO_BROWSER_USER_AGENT")
-            .unwrap_or_else(|_| "gitgud.zip blog".to_string());
+        let validation_user_agent = validation_user_agent();
 
         let outcomes = stream::iter(stations.into_iter().enumerate())
             .map(|(idx, station)| {
@@ -80,6 +100,8 @@ impl StreamValidator {
         let mut dropped = 0;
         let mut reasons: HashMap<String, i32> = HashMap::new();
         let mut cache_updates: HashMap<String, CacheEntry> = HashMap::new();
+        let mut health_checks = Vec::new();
+        let mut dropped_stations = Vec::new();
 
         for outcome in outcomes {
             match outcome {
@@ -87,20 +109,26 @@ impl StreamValidator {
                     idx,
                     station,
                     cache_update,
+                    health_check,
                 } => {
                     if let Some((key, entry)) = cache_update {
                         cache_updates.insert(key, entry);
                     }
+                    health_checks.extend(health_check);
                     accepted.push((idx, *station));
                 }
                 ValidationOutcome::Dropped {
+                    station,
                     reason,
                     cache_update,
+                    health_check,
                 } => {
                     if let Some((key, entry)) = cache_update {
                         cache_updates.insert(key, entry);
                     }
+                    health_checks.extend(health_check);
                     record_drop(&mut reasons, Some(&reason));
+                    dropped_stations.push((*station, reason));
                     dropped += 1;
                 }
             }
@@ -109,11 +137,13 @@ impl StreamValidator {
         accepted.sort_by_key(|(idx, _)| *idx);
         let stations = accepted.into_iter().map(|(_, station)| station).collect();
         self.write_cache(postgres, cache_updates).await?;
+        stream_health::record_checks(postgres, &health_checks).await?;
 
         Ok(ValidationSummary {
             stations,
             dropped,
             reasons,
+            dropped_stations,
         })
     }
 
@@ -130,16 +160,34 @@ impl StreamValidator {
             if entry.is_valid(now, &signature, &self.config) {
                 if entry.ok {
                     station = entry.apply(station);
-                    return ValidationOutcome::accepted(idx, station, None);
+                    return ValidationOutcome::accepted(idx, station, None, None);
                 }
                 return ValidationOutcome::dropped(
+                    station,
                     entry.reason.unwrap_or_else(|| "invalid".into()),
                     None,
+                    None,
                 );
             }
         }
 
-        match self.validate_station(&station, validation_user_agent).await {
+        let mut probe = ProbeDetails::default();
+        let started = Instant::now();
+        let result = self
+            .validate_station(&station, validation_user_agent, &mut probe)
+            .await;
+        let mut health_check = HealthCheck {
+            station_id: station.id.clone(),
+            stream_url: station.stream_url.clone(),
+            ok: result.is_ok(),
+            status: probe.status,
+            reason: result.as_ref().err().cloned(),
+            latency_ms: probe
+                .responded
+                .then(|| u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX)),
+            content_type: probe.content_type,
+        };
+        match result {
             Ok(result) => {
                 let cache_entry = CacheEntry::success(&result, &signature, &self.config);
                 if let Some(final_url) = result.final_url {
@@ -148,13 +196,27 @@ impl StreamValidator {
                 if result.force_hls {
                     station.hls = true;
                 }
+                if let Some(audio) = &result.audio {
+                    correct_audio(&mut station, audio.codec, audio.bitrate);
+                }
                 let stream_url = station.stream_url.clone();
-                ValidationOutcome::accepted(idx, station, Some((stream_url, cache_entry)))
+                health_check.stream_url = stream_url.clone();
+                ValidationOutcome::accepted(
+                    idx,
+                    station,
+                    Some((stream_url, cache_entry)),
+                    Some(health_check),
+                )
             }
             Err(reason) => {
                 let cache_entry = CacheEntry::failure(&reason, &signature, &self.config);
                 let stream_url = station.stream_url.clone();
-                ValidationOutcome::dropped(reason, Some((stream_url, cache_entry)))
+                ValidationOutcome::dropped(
+                    station,
+                    reason,
+                    Some((stream_url, cache_entry)),
+                    Some(health_check),
+                )
             }
         }
     }
@@ -163,13 +225,121 @@ impl StreamValidator {
         &self,
         station: &Station,
         validation_user_agent: &str,
+        probe: &mut ProbeDetails,
     ) -> Result<ValidatedStream, String> {
-        if is_blocked_domain(&station.stream_url) {
+        match self
+            .probe_stream(&station.stream_url, validation_user_agent, probe)
+            .await?
+        {
+            Probed::Stream(stream) => Ok(stream),
+            Probed::Playlist(entries) => {
+                self.resolve_entries(&entries, validation_user_agent, probe)
+                    .await
+            }
+        }
+    }
+
+    /// Resolves a station whose URL is a PLS, M3U or ASX playlist to the first working HTTPS
+    /// stream it lists; a URL that already serves a stream (including HLS) resolves to itself.
+    /// The result is read from and written to the validation cache, so stream requests do not
+    /// probe the playlist again until the entry expires.
+    pub async fn resolve_station(
+        &self,
+        station: Station,
+        postgres: &PgPool,
+    ) -> Result<Station, String> {
+        let signature = build_station_signature(&station);
+        let cached = match self
+            .load_cache(postgres, std::slice::from_ref(&station.stream_url))
+            .await
+        {
+            Ok(mut cache) => cache.remove(&station.stream_url),
+            Err(error) => {
+                logger().warn(
+                    "stream.resolve_cache_failed",
+                    json!({ "stationId": station.id, "error": error.to_string() }),
+                );
+                None
+            }
+        };
+        if let Some(entry) = cached.filter(|entry| {
+            entry.ok && entry.is_valid(current_timestamp(), &signature, &self.config)
+        }) {
+            return Ok(entry.apply(station));
+        }
+
+        let user_agent = validation_user_agent();
+        let mut probe = ProbeDetails::default();
+        let stream = self
+            .validate_station(&station, &user_agent, &mut probe)
+            .await?;
+        let entry = CacheEntry::success(&stream, &signature, &self.config);
+        let update = HashMap::from([(station.stream_url.clone(), entry.clone())]);
+        if let Err(error) = self.write_cache(postgres, update).await {
+            logger().warn(
+                "stream.resolve_cache_failed",
+                json!({ "stationId": station.id, "error": error.to_string() }),
+            );
+        }
+        Ok(entry.apply(station))
+    }
+
+    /// Resolves an already downloaded wrapper playlist; see `resolve_station`.
+    pub async fn resolve_playlist_entries(
+        &self,
+        entries: &[PlaylistEntry],
+    ) -> Result<String, String> {
+        let user_agent = validation_user_agent();
+        let mut probe = ProbeDetails::default();
+        let stream = self
+            .resolve_entries(entries, &user_agent, &mut probe)
+            .await?;
+        Ok(stream.final_url.unwrap_or_default())
+    }
+
+    /// Probes the playlist's entries in order and returns the first that serves a stream.
+    /// Entries go through the same sanitizing as station URLs, so a playlist cannot point the
+    /// service at a private host or a plain-HTTP stream, and nested playlists are not followed.
+    async fn resolve_entries(
+        &self,
+        entries: &[PlaylistEntry],
+        validation_user_agent: &str,
+        probe: &mut ProbeDetails,
+    ) -> Result<ValidatedStream, String> {
+        let mut seen = HashSet::new();
+        let candidates = entries
+            .iter()
+            .filter_map(|entry| sanitize_stream_url(&entry.url))
+            .filter(|url| seen.insert(url.clone()))
+            .take(MAX_PLAYLIST_CANDIDATES);
+
+        let mut last_error = "playlist-empty".to_string();
+        for candidate in candidates {
+            *probe = ProbeDetails::default();
+            match self
+                .probe_stream(&candidate, validation_user_agent, probe)
+                .await
+            {
+                Ok(Probed::Stream(stream)) => return Ok(stream),
+                Ok(Probed::Playlist(_)) => last_error = "nested-playlist".to_string(),
+                Err(reason) => last_error = reason,
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn probe_stream(
+        &self,
+        url: &str,
+        validation_user_agent: &str,
+        probe: &mut ProbeDetails,
+    ) -> Result<Probed, String> {
+        if is_blocked_domain(url) {
             return Err("blocked-domain".to_string());
         }
         let request = self
             .client
-            .get(&station.stream_url)
+            .get(url)
             .headers(build_validation_headers(validation_user_agent));
 
         let response = timeout(
@@ -179,6 +349,7 @@ impl StreamValidator {
         .await
         .map_err(|_| "timeout".to_string())?
         .map_err(|_| "network".to_string())?;
+        probe.status = Some(response.status().as_u16());
 
         if !(response.status().is_success() || response.status() == StatusCode::PARTIAL_CONTENT) {
             return Err(format!("status-{}", response.status().as_u16()));
@@ -209,28 +380,164 @@ impl StreamValidator {
             .and_then(|value| value.to_str().ok())
             .unwrap_or("")
             .to_string();
+        probe.content_type = Some(content_type.clone()).filter(|value| !value.is_empty());
+
+        let wrapper = StreamPlaylist::detect(&final_url, &content_type);
+        let force_hls =
+            content_type.to_ascii_lowercase().contains("mpegurl") || is_hls_url(&final_url);
+        if wrapper.is_some() || force_hls {
+            let body = read_playlist_body(response).await?;
+            probe.responded = true;
+            if is_hls_playlist(&body) {
+                let audio = self
+                    .validate_hls(&final_url, &body, validation_user_agent)
+                    .await?;
+                return Ok(Probed::Stream(ValidatedStream {
+                    final_url: Some(final_url),
+                    force_hls: true,
+                    audio,
+                }));
+            }
+            let kind = wrapper.unwrap_or_else(|| StreamPlaylist::sniff(&body));
+            return Ok(Probed::Playlist(kind.parse(&body)));
+        }
+
         if !is_known_stream_type(&content_type) {
             return Err("unexpected-content-type".to_string());
         }
 
-        let mut has_data = false;
+        let sample = self.read_sample(response).await?;
+        probe.responded = true;
+        let audio = sniff_audio(&sample).ok_or_else(|| "unknown-audio-format".to_string())?;
+
+        Ok(Probed::Stream(ValidatedStream {
+            final_url: Some(final_url),
+            force_hls,
+            audio: Some(audio),
+        }))
+    }
+
+    /// Walks an HLS stream the way a player does: the first variant of a master playlist, then
+    /// the newest segment of the media playlist. Browsers only reach those through the stream
+    /// proxy, so each hop must also pass its origin check. Failures name the hop, e.g.
+    /// `hls-variant-status-404` or `hls-segment-origin`. Returns the segment's audio when the
+    /// container is one `sniff_audio` knows; fMP4 segments are accepted without it.
+    async fn validate_hls(
+        &self,
+        stream_url: &str,
+        playlist: &str,
+        validation_user_agent: &str,
+    ) -> Result<Option<SniffedAudio>, String> {
+        let mut media_url = Url::parse(stream_url).map_err(|_| "hls-invalid-url".to_string())?;
+        let mut media_playlist = playlist.to_string();
+        if let Some(variant) = first_variant_uri(playlist) {
+            let variant_url = hls_target(stream_url, &media_url, &variant, "hls-variant")?;
+            let response = self
+                .fetch_hls(&variant_url, validation_user_agent, "hls-variant", false)
+                .await?;
+            media_playlist = read_playlist_body(response)
+                .await
+                .map_err(|reason| format!("hls-variant-{reason}"))?;
+            if !is_hls_playlist(&media_playlist) {
+                return Err("hls-variant-invalid".to_string());
+            }
+            media_url = variant_url;
+        } else if playlist.len() >= SNIFF_BYTES {
+            // The probe's byte range may have cut the playlist short of its newest segments.
+            let response = self
+                .fetch_hls(&media_url, validation_user_agent, "hls-playlist", false)
+                .await?;
+            media_playlist = read_playlist_body(response)
+                .await
+                .map_err(|reason| format!("hls-playlist-{reason}"))?;
+        }
+
+        let segment =
+            newest_segment(&media_playlist).ok_or_else(|| "hls-no-segments".to_string())?;
+        let segment_url = hls_target(stream_url, &media_url, &segment.uri, "hls-segment")?;
+        let response = self
+            .fetch_hls(&segment_url, validation_user_agent, "hls-segment", true)
+            .await?;
+        let sample = self
+            .read_sample(response)
+            .await
+            .map_err(|reason| format!("hls-segment-{reason}"))?;
+        Ok(sniff_audio(&sample))
+    }
+
+    /// Playlists are fetched whole so their newest segment is in the body; segments only need
+    /// the sniffing range.
+    async fn fetch_hls(
+        &self,
+        url: &Url,
+        validation_user_agent: &str,
+        step: &str,
+        ranged: bool,
+    ) -> Result<reqwest::Response, String> {
+        let mut headers = build_validation_headers(validation_user_agent);
+        if !ranged {
+            headers.remove(reqwest::header::RANGE);
+        }
+        let request = self.client.get(url.clone()).headers(headers);
+        let response = timeout(
+            Duration::from_millis(self.config.timeout_ms),
+            request.send(),
+        )
+        .await
+        .map_err(|_| format!("{step}-timeout"))?
+        .map_err(|_| format!("{step}-network"))?;
+        if !(response.status().is_success() || response.status() == StatusCode::PARTIAL_CONTENT) {
+            return Err(format!("{step}-status-{}", response.status().as_u16()));
+        }
+        Ok(response)
+    }
+
+    /// Reads up to `SNIFF_BYTES` of the stream. Live streams trickle in at their bitrate, so
+    /// reading stops at the probe timeout and sniffs whatever arrived by then.
+    async fn read_sample(&self, response: reqwest::Response) -> Result<Vec<u8>, String> {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(self.config.timeout_ms);
+        let mut sample = Vec::new();
         let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|_| "network".to_string())?;
-            if !chunk.is_empty() {
-                has_data = true;
-                break;
+        while sample.len() < SNIFF_BYTES {
+            match timeout_at(deadline, stream.next()).await {
+                Ok(Some(chunk)) => {
+                    sample.extend_from_slice(&chunk.map_err(|_| "network".to_string())?)
+                }
+                Ok(None) => break,
+                Err(_) if sample.is_empty() => return Err("timeout".to_string()),
+                Err(_) => break,
             }
         }
-        if !has_data {
+        if sample.is_empty() {
             return Err("empty-response".to_string());
         }
+        Ok(sample)
+    }
 
-        let force_hls = content_type.to_ascii_lowercase().contains("mpegurl");
-        Ok(ValidatedStream {
-            final_url: Some(final_url),
-            force_hls,
-        })
+    /// Candidate stream URLs whose cached validation has expired, longest expired first.
+    pub async fn expired_stream_urls(
+        &self,
+        postgres: &PgPool,
+        candidates: &[String],
+        limit: usize,
+    ) -> anyhow::Result<Vec<String>> {
+        if candidates.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(sqlx::query_scalar(
+            r#"
+            SELECT stream_url
+            FROM radio_stream_validation_cache
+            WHERE stream_url = ANY($1)
+              AND expires_at <= NOW()
+            ORDER BY expires_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(candidates)
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(postgres)
+        .await?)
     }
 
     async fn load_cache(
@@ -301,6 +608,50 @@ impl StreamValidator {
     }
 }
 
+/// Resolves a variant or segment URI as the stream proxy would and applies its origin check.
+fn hls_target(stream_url: &str, base: &Url, uri: &str, step: &str) -> Result<Url, String> {
+    let target = resolve_playlist_uri(base, uri).ok_or_else(|| format!("{step}-insecure"))?;
+    if !is_segment_origin_allowed(stream_url, &target) || is_blocked_domain(target.as_str()) {
+        return Err(format!("{step}-origin"));
+    }
+    Ok(target)
+}
+
+/// The newest segment of a media playlist read by `read_playlist_body`. A body cut off at
+/// `MAX_PLAYLIST_BYTES` may end mid-line, and that partial line is not a segment URI; a
+/// complete playlist is free to omit its final newline.
+fn newest_segment(playlist: &str) -> Option<MediaSegment> {
+    let complete = match playlist.rfind('\n') {
+        Some(end) if playlist.len() >= MAX_PLAYLIST_BYTES && !playlist.ends_with('\n') => {
+            &playlist[..end]
+        }
+        _ => playlist,
+    };
+    last_media_segment(complete)
+}
+
+fn validation_user_agent() -> String {
+    std::env::var("RADIO_BROWSER_USER_AGENT").unwrap_or_else(|_| "gitgud.zip blog".to_string())
+}
+
+/// Reads a playlist body up to `MAX_PLAYLIST_BYTES`; wrapper playlists are a few lines long.
+async fn read_playlist_body(response: reqwest::Response) -> Result<String, String> {
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|_| "network".to_string())?;
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_PLAYLIST_BYTES {
+            body.truncate(MAX_PLAYLIST_BYTES);
+            break;
+        }
+    }
+    if body.is_empty() {
+        return Err("empty-response".to_string());
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
 fn build_validation_headers(user_agent: &str) -> reqwest::header::HeaderMap {
     let mut map = reqwest::header::HeaderMap::new();
     for (key, value) in VALIDATION_HEADERS {
@@ -342,6 +693,13 @@ struct CacheEntry {
     final_url: Option<String>,
     #[serde(default)]
     force_hls: bool,
+    /// Codec, bitrate and sample rate sniffed from the stream's first bytes.
+    #[serde(default)]
+    codec: Option<String>,
+    #[serde(default)]
+    bitrate: Option<i32>,
+    #[serde(default)]
+    sample_rate: Option<u32>,
     #[serde(default)]
     validated_at: i64,
     #[serde(default)]
@@ -357,6 +715,13 @@ impl CacheEntry {
             reason: None,
             final_url: result.final_url.clone(),
             force_hls: result.force_hls,
+            codec: result
+                .audio
+                .as_ref()
+                .and_then(|audio| audio.codec)
+                .map(str::to_string),
+            bitrate: result.audio.as_ref().and_then(|audio| audio.bitrate),
+            sample_rate: result.audio.as_ref().and_then(|audio| audio.sample_rate),
             validated_at: current_timestamp(),
             signature: Some(signature.to_string()),
             ttl_seconds: Some(config.cache_ttl_seconds),
@@ -369,6 +734,9 @@ impl CacheEntry {
             reason: Some(reason.to_string()),
             final_url: None,
             force_hls: false,
+            codec: None,
+            bitrate: None,
+            sample_rate: None,
             validated_at: current_timestamp(),
             signature: Some(signature.to_string()),
             ttl_seconds: Some(config.failure_cache_ttl_seconds),
@@ -394,14 +762,53 @@ impl CacheEntry {
         if self.force_hls {
             station.hls = true;
         }
+        correct_audio(&mut station, self.codec.as_deref(), self.bitrate);
         station
     }
 }
 
+/// Replaces the directory's codec and bitrate with what the stream actually sent. A codec
+/// label naming the same codec (`AAC+` for sniffed AAC) is kept, as is a bitrate close to the
+/// measured one.
+fn correct_audio(station: &mut Station, codec: Option<&str>, bitrate: Option<i32>) {
+    if let Some(codec) = codec {
+        let listed = station.codec.as_deref().and_then(codec_family);
+        if listed != codec_family(codec) {
+            station.codec = Some(codec.to_string());
+        }
+    }
+    if let Some(bitrate) = bitrate {
+        let close = station.bitrate.is_some_and(|listed| {
+            listed > 0
+                && f64::from((listed - bitrate).abs()) <= f64::from(bitrate) * BITRATE_TOLERANCE
+        });
+        if !close {
+            station.bitrate = Some(bitrate);
+        }
+    }
+}
+
+/// What a probe saw before it succeeded or failed, for the health history.
+#[derive(Debug, Default)]
+struct ProbeDetails {
+    status: Option<u16>,
+    content_type: Option<String>,
+    /// Audio arrived; latency is only meaningful for probes that got this far.
+    responded: bool,
+}
+
 #[derive(Debug)]
 struct ValidatedStream {
     final_url: Option<String>,
     force_hls: bool,
+    /// None for HLS segments in containers `sniff_audio` does not parse, such as fMP4.
+    audio: Option<SniffedAudio>,
+}
+
+enum Probed {
+    Stream(ValidatedStream),
+    /// A PLS, M3U or ASX file listing streams rather than serving one.
+    Playlist(Vec<PlaylistEntry>),
 }
 
 enum ValidationOutcome {
@@ -409,26 +816,42 @@ enum ValidationOutcome {
         idx: usize,
         station: Box<Station>,
         cache_update: Option<(String, CacheEntry)>,
+        health_check: Option<HealthCheck>,
     },
     Dropped {
+        station: Box<Station>,
         reason: String,
         cache_update: Option<(String, CacheEntry)>,
+        health_check: Option<HealthCheck>,
     },
 }
 
 impl ValidationOutcome {
-    fn accepted(idx: usize, station: Station, cache_update: Option<(String, CacheEntry)>) -> Self {
+    fn accepted(
+        idx: usize,
+        station: Station,
+        cache_update: Option<(String, CacheEntry)>,
+        health_check: Option<HealthCheck>,
+    ) -> Self {
         Self::Accepted {
             idx,
             station: Box::new(station),
             cache_update,
+            health_check,
         }
     }
 
-    fn dropped(reason: String, cache_update: Option<(String, CacheEntry)>) -> Self {
+    fn dropped(
+        station: Station,
+        reason: String,
+        cache_update: Option<(String, CacheEntry)>,
+        health_check: Option<HealthCheck>,
+    ) -> Self {
         Self::Dropped {
+            station: Box::new(station),
             reason,
             cache_update,
+            health_check,
         }
     }
 }
@@ -439,3 +862,28 @@ fn current_timestamp() -> i64 {
         .unwrap_or_default()
         .as_millis() as i64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_only_playlists_cut_off_at_the_body_cap() {
+        let single = "#EXTM3U\n#EXTINF:6,\nseg-1.aac";
+        assert_eq!(
+            newest_segment(single).map(|segment| segment.uri).as_deref(),
+            Some("seg-1.aac")
+        );
+
+        let mut capped = "#EXTM3U\n#EXTINF:6,\nseg-1.aac\n".to_string();
+        capped.push_str(&"#".repeat(MAX_PLAYLIST_BYTES - capped.len() - 8));
+        capped.push_str("\nseg-2.a");
+        assert_eq!(capped.len(), MAX_PLAYLIST_BYTES);
+        assert_eq!(
+            newest_segment(&capped)
+                .map(|segment| segment.uri)
+                .as_deref(),
+            Some("seg-1.aac")
+        );
+    }
+}